@@ -0,0 +1,18 @@
+//! Nightly API needs `generic_const_exprs`, so it's compiled only by nightly compiler (`cfg(nightly)`).
+//! `stable` feature adds typenum based API on top of it and doesn't switch anything off.
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(nightly)");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default();
+    if version.contains("nightly") || version.contains("-dev") {
+        println!("cargo:rustc-cfg=nightly");
+    }
+}
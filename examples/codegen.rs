@@ -0,0 +1,75 @@
+#![cfg_attr(nightly, feature(generic_const_exprs))]
+#![cfg_attr(nightly, allow(incomplete_features))]
+
+//! Functions checked by `tests/codegen.rs`, each write must compile to plain stores.
+//! Nightly API isn't compiled by stable compiler, so only `main` is left there.
+#[cfg(nightly)]
+use const_writer::ConstWrite;
+
+macro_rules! codegen_write {
+    ($name:ident, $method:ident, $type:ty) => {
+        #[cfg(nightly)]
+        #[no_mangle]
+        pub fn $name(buff: &mut [u8; 16], value: $type) {
+            buff.const_writer::<16>().$method(value);
+        }
+    }
+}
+
+codegen_write!(codegen_write_u8_le, write_u8_le, u8);
+codegen_write!(codegen_write_u16_le, write_u16_le, u16);
+codegen_write!(codegen_write_u32_le, write_u32_le, u32);
+codegen_write!(codegen_write_u64_le, write_u64_le, u64);
+codegen_write!(codegen_write_u128_le, write_u128_le, u128);
+codegen_write!(codegen_write_u16_be, write_u16_be, u16);
+codegen_write!(codegen_write_u32_be, write_u32_be, u32);
+codegen_write!(codegen_write_u64_be, write_u64_be, u64);
+codegen_write!(codegen_write_u128_be, write_u128_be, u128);
+codegen_write!(codegen_write_f32_le, write_f32_le, f32);
+codegen_write!(codegen_write_f64_le, write_f64_le, f64);
+
+/// Chain from README, constants are merged into wide stores
+#[cfg(nightly)]
+#[no_mangle]
+pub fn codegen_write_chain(buff: &mut [u8; 16]) {
+    buff.const_writer::<16>()
+        .write_u32_le(34)
+        .write_u16_le(2)
+        .write_u16_le(3)
+        .write_u16_le(4)
+        .write_u16_le(5);
+}
+
+/// Shrinking has no checks
+#[cfg(nightly)]
+#[no_mangle]
+pub fn codegen_write_shrink(buff: &mut [u8; 16], value: u32) {
+    buff.const_writer::<16>()
+        .write_u32_le(value)
+        .shrink::<8>()
+        .write_u32_le(value)
+        .shrink::<4>()
+        .write_u32_le(value);
+}
+
+/// Slice writer checks length once, then writes are plain stores
+#[cfg(nightly)]
+#[no_mangle]
+pub fn codegen_write_slice(buff: &mut &mut [u8], value: u32) {
+    buff.const_writer::<12>()
+        .write_u32_le(value)
+        .write_u32_le(value)
+        .write_u32_le(value);
+}
+
+/// Vec writer reserves once, then writes are plain stores
+#[cfg(nightly)]
+#[no_mangle]
+pub fn codegen_write_vec(vec: &mut Vec<u8>, value: u32) {
+    vec.const_writer::<12>()
+        .write_u32_le(value)
+        .write_u32_le(value)
+        .write_u32_le(value);
+}
+
+fn main() {}
@@ -1,16 +1,21 @@
-#![feature(const_generics)]
-#![feature(const_evaluatable_checked)]
-#![feature(type_name_of_val)]
-#![allow(incomplete_features)]
+#![cfg_attr(nightly, feature(generic_const_exprs))]
+#![cfg_attr(nightly, allow(incomplete_features))]
 
+// nightly API isn't compiled by stable compiler, so example is empty there
+#[cfg(not(nightly))]
+fn main() {}
 
+#[cfg(nightly)]
 use const_writer::{ConstWriter, ConstWrite, ConstWriterAdapter};
+#[cfg(nightly)]
 use const_writer::slice::SliceWriterAdapter;
 
-pub fn write_struct<'a, T: ConstWriterAdapter>(writer: ConstWriter<T, 10>) -> ConstWriter<T, 0> {
+#[cfg(nightly)]
+pub fn write_struct<T: ConstWriterAdapter>(writer: ConstWriter<T, 10>) -> ConstWriter<T, 0> {
     writer.write_u16_le(34).write_u16_le(2).write_u16_le(3).write_u16_le(4).write_u16_le(5)
 }
 
+#[cfg(nightly)]
 fn main() {
     let mut buff = [0u8; 24];
     let mut ref_buff = buff.as_mut() as &mut [u8];
@@ -18,14 +23,13 @@ fn main() {
     let writer: ConstWriter<SliceWriterAdapter<'_, '_>, 20> = ref_buff.const_writer::<20>();
 
     // decrease writer size without check
-    let writer = write_struct(writer.convert());
-    // increase writer size with check
-    write_struct(writer.convert());
+    write_struct(writer.shrink());
     println!("{:?}", buff);
 
     let mut vec = Vec::new();
-    write_struct(vec.const_writer());
-    write_struct(vec.const_writer());
+    let writer = write_struct(vec.const_writer());
+    // increase writer size, only growable adapters like vector allow it
+    write_struct(writer.grow_to());
     vec.const_writer::<24>().write_slice(&[11u8; 24]);
 
     write_conditional_len(vec.const_writer(), false);
@@ -40,7 +44,7 @@ fn main() {
     vec.const_writer::<5>()
         .write_u32_le(123)
         .write_u8_le(1)
-        .convert::<10>()
+        .grow_to::<10>()
         .write_u32_le(124)
         .write_u8_le(24);
 
@@ -50,6 +54,7 @@ fn main() {
     println!("{:?}", vec.capacity());
 }
 
+#[cfg(nightly)]
 fn write_conditional_len<T: ConstWriterAdapter>(writer: ConstWriter<T, 32>, flag: bool) {
     let writer = writer.write_u32_le(24);
 
@@ -58,7 +63,7 @@ fn write_conditional_len<T: ConstWriterAdapter>(writer: ConstWriter<T, 32>, flag
             .write_u64_le(32)
             .write_u128_le(64)
     } else {
-        writer.convert() // rust infer same len as top branch
+        writer.shrink() // rust infer same len as top branch
     };
 
     assert_eq!(writer.remaining(), 4);
@@ -0,0 +1,111 @@
+//! Slice adapter which checks that start of buffer is aligned to `ALIGN` bytes,
+//! e.g. for filling DMA buffers.
+//!
+//! ```
+//! use const_writer::aligned::{self, AlignedWriterAdapter};
+//!
+//! #[repr(align(8))]
+//! struct Dma([u8; 16]);
+//!
+//! let mut dma = Dma([0; 16]);
+//! let mut buff = dma.0.as_mut() as &mut [u8];
+//! aligned::const_writer::<8, 16>(&mut buff)
+//!     .write_u64_le(1)
+//!     .write_u64_le(2);
+//! assert_eq!(AlignedWriterAdapter::<8>::ALIGN, 8);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter, ConstWriterAdapterCreate};
+use crate::slice::SliceWriterAdapter;
+
+/// Wrapper of [`SliceWriterAdapter`] which start address is aligned to `ALIGN`
+pub struct AlignedWriterAdapter<'a, 'inner, const ALIGN: usize> {
+    inner: SliceWriterAdapter<'a, 'inner>,
+}
+
+impl<'a, 'inner, const ALIGN: usize> AlignedWriterAdapter<'a, 'inner, ALIGN> {
+    /// Alignment of buffer start address
+    pub const ALIGN: usize = ALIGN;
+}
+
+impl<'a, 'inner, const ALIGN: usize> crate::sealed::Sealed for AlignedWriterAdapter<'a, 'inner, ALIGN> {}
+
+impl<'a, 'inner, const ALIGN: usize> core::fmt::Debug for AlignedWriterAdapter<'a, 'inner, ALIGN> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a, 'inner, const ALIGN: usize> ConstWriterAdapter for AlignedWriterAdapter<'a, 'inner, ALIGN> {
+    fn written_so_far(&self) -> usize {
+        self.inner.written_so_far()
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        self.inner = self.inner.write_raw(value);
+        self
+    }
+
+    unsafe fn grow<const M: usize>(mut self) -> Self {
+        self.inner = self.inner.grow::<M>();
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(mut self) -> Self {
+        self.inner = self.inner.poison::<N>();
+        self
+    }
+}
+
+/// Get [`ConstWriter`] to write `N` bytes to slice, which start must be aligned to `ALIGN` bytes
+///
+/// # Panics
+/// If `ALIGN` isn't power of two, slice start isn't aligned or slice is shorter than `N`
+#[track_caller]
+pub fn const_writer<'a, 'inner, const ALIGN: usize, const N: usize>(
+    slice: &'a mut &'inner mut [u8]
+) -> ConstWriter<AlignedWriterAdapter<'a, 'inner, ALIGN>, N> {
+    assert!(ALIGN.is_power_of_two(), "alignment must be power of two: {}", ALIGN);
+    assert_eq!(
+        slice.as_ptr().addr() % ALIGN,
+        0,
+        "slice isn't aligned to {} bytes ({})",
+        ALIGN,
+        core::any::type_name::<AlignedWriterAdapter<ALIGN>>()
+    );
+    unsafe {
+        ConstWriter {
+            writer_adapter: crate::poisoned::<_, N>(AlignedWriterAdapter {
+                inner: SliceWriterAdapter::new::<N>(slice),
+            })
+        }
+    }
+}
+
+/// Number of padding bytes to align `offset` to `align`
+pub const fn padding(offset: usize, align: usize) -> usize {
+    (align - offset % align) % align
+}
+
+/// Writes `count` zero bytes of padding, used by format modules and their macros
+#[doc(hidden)]
+pub unsafe fn write_padding<T: ConstWriterAdapter>(mut writer_adapter: T, count: usize) -> T {
+    for _ in 0..count {
+        writer_adapter = writer_adapter.write(&[0]);
+    }
+    writer_adapter
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[should_panic(expected = "isn't aligned")]
+    fn aligned_misaligned() {
+        #[repr(align(4))]
+        struct Buff([u8; 8]);
+
+        let mut buff = Buff([0; 8]);
+        let mut slice = &mut buff.0[1..];
+        super::const_writer::<4, 4>(&mut slice);
+    }
+}
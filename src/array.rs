@@ -0,0 +1,157 @@
+//! Adapter for `[u8; L]`. Writer length is checked against array length at compile time,
+//! so creating writer and writing to it never panics.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//!
+//! let mut buff = [0u8; 6];
+//! buff.const_writer::<6>()
+//!     .write_u32_le(1)
+//!     .write_u16_be(2);
+//! assert_eq!(buff, [1, 0, 0, 0, 0, 2]);
+//! ```
+//!
+//! Writer longer than array doesn't compile:
+//! ```compile_fail
+//! use const_writer::ConstWrite;
+//!
+//! let mut buff = [0u8; 4];
+//! buff.const_writer::<6>();
+//! ```
+use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate};
+
+struct Fits<const N: usize, const L: usize>;
+
+impl<const N: usize, const L: usize> Fits<N, L> {
+    const CHECK: () = assert!(N <= L, "array too short for writer");
+}
+
+/// Writes from start of array
+pub struct ArrayWriterAdapter<'a, const L: usize> {
+    array: &'a mut [u8; L],
+    written: usize,
+}
+
+unsafe impl<'a, const L: usize> ConstWriterAdapterCreate<'a, [u8; L]> for ArrayWriterAdapter<'a, L> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(array: &'a mut [u8; L]) -> Self {
+        let () = Fits::<N, L>::CHECK;
+        Self {
+            array,
+            written: 0,
+        }
+    }
+}
+
+impl<'a, const L: usize> crate::sealed::Sealed for ArrayWriterAdapter<'a, L> {}
+
+impl<'a, const L: usize> core::fmt::Debug for ArrayWriterAdapter<'a, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a, const L: usize> ConstWriterAdapter for ArrayWriterAdapter<'a, L> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        let ptr = self.array.as_mut_ptr().add(self.written);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr, N);
+        self.written += N;
+        self
+    }
+
+    /// Only panicking path of adapter, growing can't be checked at compile time
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= L - self.written,
+            "remaining array too short to grow: {} < {} ({})",
+            L - self.written,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        core::ptr::write_bytes(self.array.as_mut_ptr().add(self.written), crate::POISON, N);
+        self
+    }
+}
+
+impl<'a, const L: usize> ConstWrite<'a, ArrayWriterAdapter<'a, L>> for [u8; L] {}
+
+/// Get [`ConstWriter`](crate::ConstWriter) which must fill whole array
+/// ```
+/// let mut buff = [0u8; 4];
+/// const_writer::array::const_writer_exact(&mut buff).write_u32_be(1);
+/// assert_eq!(buff, [0, 0, 0, 1]);
+/// ```
+pub fn const_writer_exact<const N: usize>(array: &mut [u8; N]) -> crate::ConstWriter<ArrayWriterAdapter<'_, N>, N> {
+    array.const_writer::<N>()
+}
+
+/// Proves that writing to array compiles to panic-free code.
+///
+/// If code between guard creation and `forget` can unwind, guard's drop references
+/// undefined symbol and test binary fails to link. Needs optimizations, so run with
+/// `cargo test --release --features no-panic`.
+#[cfg(all(test, feature = "no-panic", not(debug_assertions)))]
+mod no_panic {
+    use crate::ConstWrite;
+
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            extern "C" {
+                fn const_writer_array_write_may_panic() -> !;
+            }
+            unsafe { const_writer_array_write_may_panic() }
+        }
+    }
+
+    #[inline(never)]
+    fn fill(buff: &mut [u8; 31], a: u8, b: u16, c: u32, d: u64, e: u128) {
+        let guard = Guard;
+        buff.const_writer::<31>()
+            .write_u8_le(a)
+            .write_u16_be(b)
+            .write_u32_le(c)
+            .write_u64_be(d)
+            .write_u128_le(e);
+        core::mem::forget(guard);
+    }
+
+    #[test]
+    fn array_write_no_panic() {
+        let mut buff = [0u8; 31];
+        fill(&mut buff, 1, 2, 3, 4, 5);
+        assert_eq!(buff[..3], [1, 0, 2]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    #[allow(deprecated)]
+    #[should_panic(expected = "remaining array too short to grow: 2 < 4")]
+    fn array_grow_too_short() {
+        let mut buff = [0u8; 6];
+        buff.const_writer::<4>().write_u32_le(1).convert::<4>();
+    }
+
+    #[test]
+    fn array_shrink() {
+        let mut buff = [0u8; 4];
+        let writer = buff.const_writer::<4>().write_u8_le(1).shrink::<2>().write_u16_be(2);
+        assert_eq!(writer.written_so_far(), 3);
+        assert_eq!(buff[..3], [1, 0, 2]);
+    }
+}
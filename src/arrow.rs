@@ -0,0 +1,94 @@
+//! Framing of [Arrow IPC](https://arrow.apache.org/docs/format/Columnar.html#encapsulated-message-format) messages.
+//!
+//! Message is `[0xFFFFFFFF][metadata_len i32 le][metadata][padding][body]`, metadata length
+//! includes padding, so body starts at multiple of 8 bytes.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::arrow;
+//!
+//! let metadata = [1u8; 10]; // flatbuffer of Message
+//! let mut vec = vec![];
+//! let writer = arrow::write_continuation_and_len(vec.const_writer::<33>(), metadata.len());
+//! let (writer, padding) = arrow::write_padding(writer.write_slice(&metadata), metadata.len());
+//! assert_eq!(padding, 6);
+//! arrow::write_end_of_stream(writer);
+//! assert_eq!(vec[..8], [0xFF, 0xFF, 0xFF, 0xFF, 16, 0, 0, 0]);
+//! assert_eq!(vec.len(), 8 + 16 + 8);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+use crate::aligned::padding;
+
+/// Marker before metadata length
+pub const CONTINUATION: [u8; 4] = [0xFF; 4];
+/// Magic at start and end of IPC file, padded to 8 bytes at start
+pub const FILE_MAGIC: [u8; 6] = *b"ARROW1";
+
+/// Metadata length padded to 8 bytes
+pub const fn padded_len(metadata_len: usize) -> usize {
+    metadata_len + padding(metadata_len, 8)
+}
+
+/// Writes continuation marker and metadata length padded to 8 bytes
+///
+/// # Panics
+/// If padded length doesn't fit in `i32`
+#[track_caller]
+pub fn write_continuation_and_len<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, metadata_len: usize) -> ConstWriter<T, {N - 8}> {
+    let len = padded_len(metadata_len);
+    assert!(len <= i32::MAX as usize, "metadata too long: {}", len);
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&CONTINUATION)
+                .write(&(len as i32).to_le_bytes()),
+        }
+    }
+}
+
+/// Writes zeros after metadata of `metadata_len` bytes up to multiple of 8.
+/// Reserves 7 bytes of budget, returns number of written bytes.
+pub fn write_padding<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, metadata_len: usize) -> (ConstWriter<T, {N - 7}>, usize) {
+    let len = padding(metadata_len, 8);
+    let writer_adapter = unsafe { crate::aligned::write_padding(writer.writer_adapter, len) };
+    (ConstWriter { writer_adapter }, len)
+}
+
+/// Writes end of stream: continuation marker and zero length
+pub fn write_end_of_stream<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 8}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&CONTINUATION)
+                .write(&[0; 4]),
+        }
+    }
+}
+
+/// Writes file magic padded to 8 bytes, which starts IPC file
+pub fn write_file_magic<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 8}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&FILE_MAGIC)
+                .write(&[0; 2]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn arrow_aligned_metadata_has_no_padding() {
+        let mut buff = [0xAAu8; 23];
+        let writer = super::write_file_magic(buff.const_writer::<23>());
+        let (_, padding) = super::write_padding(super::write_continuation_and_len(writer, 24), 24);
+        assert_eq!(padding, 0);
+        assert_eq!(buff[..8], *b"ARROW1\0\0");
+        assert_eq!(buff[8..16], [0xFF, 0xFF, 0xFF, 0xFF, 24, 0, 0, 0]);
+    }
+}
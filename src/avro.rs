@@ -0,0 +1,124 @@
+//! [Avro object container files](https://avro.apache.org/docs/1.11.1/specification/#object-container-files).
+//!
+//! File is magic, metadata map, sync marker and blocks of `[count][size][data][sync marker]`.
+//! Avro `long` is zigzag varint, so its writer reserves worst case [`MAX_LONG_LEN`] bytes and
+//! returns actual length.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::avro;
+//!
+//! let sync = [7u8; 16];
+//! let mut file = vec![];
+//! let writer = avro::write_magic(file.const_writer::<105>());
+//! let (writer, _) = avro::write_long(writer, 1); // map block of 1 entry
+//! let (writer, _) = avro::write_metadata_entry(writer, b"avro.codec", b"null");
+//! let writer = writer.write_u8_le(0); // end of map
+//! let writer = avro::write_sync_marker(writer, &sync);
+//! // block of 1 record of fixed(4)
+//! let (writer, _) = avro::write_block_header(writer, 1, 4);
+//! let writer = avro::write_fixed(writer, &[1, 2, 3, 4]);
+//! avro::write_sync_marker(writer, &sync);
+//! assert_eq!(file[..5], [b'O', b'b', b'j', 1, 2]);
+//! assert_eq!(file[5..7], [20, b'a']);
+//! assert_eq!(file.len(), 5 + 11 + 5 + 1 + 16 + 2 + 4 + 16);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Magic at start of container file
+pub const MAGIC: [u8; 4] = *b"Obj\x01";
+/// Worst case length of `long`
+pub const MAX_LONG_LEN: usize = 10;
+/// Length of sync marker
+pub const SYNC_LEN: usize = 16;
+
+/// Writes zigzag varint, returns adapter and written length
+unsafe fn write_varint<T: ConstWriterAdapter>(mut writer_adapter: T, value: i64) -> (T, usize) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+    let mut len = 0;
+    loop {
+        len += 1;
+        if value < 0x80 {
+            return (writer_adapter.write(&[value as u8]), len);
+        }
+        writer_adapter = writer_adapter.write(&[value as u8 | 0x80]);
+        value >>= 7;
+    }
+}
+
+/// Writes file magic
+pub fn write_magic<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 4}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&MAGIC),
+        }
+    }
+}
+
+/// Writes Avro `long`, returns number of written bytes
+pub fn write_long<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, value: i64) -> (ConstWriter<T, {N - MAX_LONG_LEN}>, usize) {
+    let (writer_adapter, len) = unsafe { write_varint(writer.writer_adapter, value) };
+    (ConstWriter { writer_adapter }, len)
+}
+
+/// Writes value of Avro `fixed` type of `M` bytes, it has no length prefix
+pub fn write_fixed<const M: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, value: &[u8; M]) -> ConstWriter<T, {N - M}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(value),
+        }
+    }
+}
+
+/// Writes entry of file metadata map, both `key` and `value` are length prefixed.
+/// Returns number of written bytes.
+pub fn write_metadata_entry<const K: usize, const V: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    key: &[u8; K],
+    value: &[u8; V],
+) -> (ConstWriter<T, {N - (K + V + 2 * MAX_LONG_LEN)}>, usize) {
+    unsafe {
+        let (writer_adapter, key_len) = write_varint(writer.writer_adapter, K as i64);
+        let (writer_adapter, value_len) = write_varint(writer_adapter.write(key), V as i64);
+        (ConstWriter { writer_adapter: writer_adapter.write(value) }, key_len + K + value_len + V)
+    }
+}
+
+/// Writes 16 byte sync marker, which ends file header and every block
+pub fn write_sync_marker<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, sync: &[u8; SYNC_LEN]) -> ConstWriter<T, {N - SYNC_LEN}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(sync),
+        }
+    }
+}
+
+/// Writes block header: number of records and size of serialized data in bytes.
+/// Returns number of written bytes.
+pub fn write_block_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, count: i64, size: i64) -> (ConstWriter<T, {N - 2 * MAX_LONG_LEN}>, usize) {
+    unsafe {
+        let (writer_adapter, count_len) = write_varint(writer.writer_adapter, count);
+        let (writer_adapter, size_len) = write_varint(writer_adapter, size);
+        (ConstWriter { writer_adapter }, count_len + size_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn avro_long_zigzag() {
+        let mut buff = [0u8; 40];
+        let (writer, len) = super::write_long(buff.const_writer::<40>(), -1);
+        assert_eq!(len, 1);
+        let (writer, len) = super::write_long(writer, 64);
+        assert_eq!(len, 2);
+        let (_, len) = super::write_long(writer, i64::MIN);
+        assert_eq!(len, 10);
+        assert_eq!(buff[..3], [0x01, 0x80, 0x01]);
+        assert_eq!(buff[3..13], [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+    }
+}
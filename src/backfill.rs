@@ -0,0 +1,77 @@
+//! Slots which are reserved in writer and patched after the rest is written,
+//! e.g. checksum which covers bytes after it.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//! use const_writer::checksum::internet_checksum;
+//!
+//! let mut vec = vec![];
+//! let (writer, checksum) = vec.const_writer::<8>()
+//!     .write_u16_be(0x1234)
+//!     .reserve_slot::<2>();
+//! writer.write_u32_be(0xDEADBEEF);
+//! let sum = internet_checksum(&vec);
+//! checksum.patch(&mut vec, &sum.to_be_bytes());
+//! assert_eq!(internet_checksum(&vec), 0);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Token of `M` reserved bytes, offset is counted from start of writer which reserved it
+#[must_use = "slot is filled with zeros until it's patched"]
+#[derive(Debug, PartialEq, Eq)]
+pub struct Slot<const M: usize> {
+    offset: usize,
+}
+
+impl<const M: usize> Slot<M> {
+    /// Offset of slot from start of writer
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Writes `value` into slot, `region` must start where writer started
+    ///
+    /// # Panics
+    /// If `region` ends before slot
+    #[track_caller]
+    pub fn patch(self, region: &mut [u8], value: &[u8; M]) {
+        assert!(
+            self.offset + M <= region.len(),
+            "slice too short: {} < {} ({})",
+            region.len(),
+            self.offset + M,
+            core::any::type_name::<Self>()
+        );
+        region[self.offset..self.offset + M].copy_from_slice(value);
+    }
+}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Reserves `M` bytes filled with zeros, which are patched later through returned [`Slot`]
+    pub fn reserve_slot<const M: usize>(self) -> (ConstWriter<T, {N - M}>, Slot<M>) {
+        let slot = Slot {
+            offset: self.writer_adapter.written_so_far(),
+        };
+        let writer = ConstWriter {
+            writer_adapter: unsafe { self.writer_adapter.write(&[0u8; M]) },
+        };
+        (writer, slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    #[should_panic(expected = "slice too short: 3 < 4")]
+    fn slot_patch_outside_region() {
+        let mut buff = [0u8; 4];
+        let slot = {
+            let (_writer, slot) = buff.const_writer::<4>().write_u16_le(1).reserve_slot::<2>();
+            slot
+        };
+        assert_eq!(slot.offset(), 2);
+        slot.patch(&mut buff[..3], &[7, 7]);
+    }
+}
@@ -0,0 +1,160 @@
+//! Writer of fixed size records to vector.
+//!
+//! Every record gets [`ConstWriter`] of exactly `RECORD` bytes, record which isn't filled
+//! completely is padded with zeros, so records stay at multiples of stride.
+//!
+//! ```
+//! use const_writer::batch::BatchWriter;
+//!
+//! let mut page = vec![];
+//! let mut batch = BatchWriter::<6>::new(&mut page);
+//! for (id, value) in [(1u32, 10u16), (2, 20)].iter() {
+//!     batch.push(|w| w.write_u32_le(*id).write_u16_le(*value));
+//! }
+//! assert_eq!(batch.len(), 2);
+//! assert_eq!(page, [1, 0, 0, 0, 10, 0, 2, 0, 0, 0, 20, 0]);
+//! ```
+//!
+//! Batch can be finished with offset index, like page directory in storage engines:
+//! ```
+//! use const_writer::batch::{BatchWriter, IndexPosition};
+//!
+//! let mut page = vec![];
+//! let mut batch = BatchWriter::<2>::new(&mut page);
+//! batch.push(|w| w.write_u16_be(7));
+//! batch.push(|w| w.write_u16_be(8));
+//! batch.finish_with_index(IndexPosition::Leading);
+//! assert_eq!(page, [
+//!     2, 0, 0, 0, // count
+//!     12, 0, 0, 0, 14, 0, 0, 0, // offsets from start of batch
+//!     0, 7, 0, 8,
+//! ]);
+//! ```
+use crate::{ConstWrite, ConstWriter};
+use crate::vec::VecWriterAdapter;
+
+extern crate alloc;
+use alloc::alloc::{Allocator, Global};
+use alloc::vec::Vec;
+
+/// Where [`BatchWriter::finish_with_index`] puts index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexPosition {
+    /// `[count u32][offset u32; count][records]`
+    Leading,
+    /// `[records][offset u32; count][count u32]`
+    Trailing,
+}
+
+/// Appends records of `RECORD` bytes to vector
+pub struct BatchWriter<'a, const RECORD: usize, A: Allocator = Global> {
+    vec: &'a mut Vec<u8, A>,
+    /// vector length before first record
+    start: usize,
+    count: usize,
+}
+
+impl<'a, const RECORD: usize, A: Allocator> BatchWriter<'a, RECORD, A> {
+    /// Records are appended after current vector content
+    pub fn new(vec: &'a mut Vec<u8, A>) -> Self {
+        Self {
+            start: vec.len(),
+            vec,
+            count: 0,
+        }
+    }
+
+    /// Writes one record with `f`, unwritten tail of record is filled with zeros
+    #[track_caller]
+    pub fn push<F>(&mut self, f: F)
+    where
+        F: for<'v> FnOnce(ConstWriter<VecWriterAdapter<'v, A>, RECORD>) -> ConstWriter<VecWriterAdapter<'v, A>, 0>,
+    {
+        let end = self.vec.len() + RECORD;
+        f(self.vec.const_writer::<RECORD>());
+        // writer can be shrunk before record is filled
+        self.vec.resize(end, 0);
+        self.count += 1;
+    }
+
+    /// Reserves space for `records` more records
+    pub fn reserve(&mut self, records: usize) {
+        self.vec.reserve(records * RECORD);
+    }
+
+    /// Number of records written
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Offset of record `index` from start of vector
+    pub fn offset(&self, index: usize) -> usize {
+        self.start + index * RECORD
+    }
+
+    /// Writes index of record offsets, all values are little endian `u32`
+    /// and offsets are counted from start of batch.
+    ///
+    /// # Panics
+    /// If batch is bigger than `u32::MAX`
+    #[track_caller]
+    pub fn finish_with_index(self, position: IndexPosition) {
+        let index_len = 4 + 4 * self.count;
+        let records_start = match position {
+            IndexPosition::Leading => index_len,
+            IndexPosition::Trailing => 0,
+        };
+        let total = records_start + self.count * RECORD + index_len;
+        assert!(total <= u32::MAX as usize, "batch too big for u32 index: {}", total);
+
+        let mut index = Vec::with_capacity(index_len);
+        if position == IndexPosition::Leading {
+            index.const_writer::<4>().write_u32_le(self.count as u32);
+        }
+        for i in 0..self.count {
+            index.const_writer::<4>().write_u32_le((records_start + i * RECORD) as u32);
+        }
+        if position == IndexPosition::Trailing {
+            index.const_writer::<4>().write_u32_le(self.count as u32);
+        }
+
+        match position {
+            IndexPosition::Leading => {
+                self.vec.splice(self.start..self.start, index);
+            }
+            IndexPosition::Trailing => self.vec.extend_from_slice(&index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BatchWriter;
+
+    extern crate alloc;
+    use alloc::vec;
+
+    #[test]
+    fn batch_pads_short_record() {
+        let mut vec = vec![9];
+        let mut batch = BatchWriter::<4>::new(&mut vec);
+        batch.push(|w| w.write_u16_be(1).shrink());
+        batch.push(|w| w.write_u32_be(2));
+        assert_eq!((batch.len(), batch.offset(1)), (2, 5));
+        assert_eq!(vec, [9, 0, 1, 0, 0, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn batch_trailing_index() {
+        let mut vec = vec![9];
+        let mut batch = BatchWriter::<3>::new(&mut vec);
+        batch.push(|w| w.write_u8_le(1).write_u16_le(2));
+        batch.push(|w| w.write_u8_le(3).write_u16_le(4));
+        batch.finish_with_index(super::IndexPosition::Trailing);
+        assert_eq!(vec, [9, 1, 2, 0, 3, 4, 0, 0, 0, 0, 0, 3, 0, 0, 0, 2, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,130 @@
+//! Bit packed integer blocks, values take `WIDTH` bits each.
+//!
+//! Bits are packed little endian, starting from lowest bit of first byte, like in Parquet
+//! and ORC, and in [`ConstBitWriter`](crate::bits::ConstBitWriter). Block of `COUNT` values takes [`packed_len`] bytes, so its size is known
+//! at compile time.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//!
+//! // frame of reference: timestamps are stored as 4 bit deltas from 1000
+//! let mut vec = vec![];
+//! vec.const_writer::<10>()
+//!     .write_u64_le(1000)
+//!     .write_delta_block::<4, 4>(&[1001, 1003, 1000, 1015], 1000);
+//! assert_eq!(vec[8..], [0x31, 0xF0]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+use crate::bits::BitQueue;
+
+/// Number of bytes taken by `count` values of `width` bits
+pub const fn packed_len(count: usize, width: usize) -> usize {
+    (count * width).div_ceil(8)
+}
+
+/// Compile time check of value width
+struct Width<const WIDTH: usize>;
+
+impl<const WIDTH: usize> Width<WIDTH> {
+    const CHECK: () = assert!(WIDTH >= 1 && WIDTH <= 64, "width is 1 to 64 bits");
+}
+
+/// Checks that every value mapped by `f` fits in `WIDTH` bits, `f` panics on its own invalid values
+#[track_caller]
+pub(crate) fn check_packed<const WIDTH: usize>(values: &[u64], f: impl Fn(u64) -> u64) {
+    let () = Width::<WIDTH>::CHECK;
+    for value in values {
+        let value = f(*value);
+        assert!(WIDTH == 64 || value >> WIDTH == 0, "value doesn't fit in {} bits: {}", WIDTH, value);
+    }
+}
+
+/// Writes `values` packed, `f` maps value to bits to store.
+/// All values are checked before first write, so adapter is untouched on panic.
+#[track_caller]
+pub(crate) unsafe fn write_packed<T: ConstWriterAdapter, const WIDTH: usize>(
+    mut writer_adapter: T,
+    values: &[u64],
+    f: impl Fn(u64) -> u64,
+) -> T {
+    check_packed::<WIDTH>(values, &f);
+    let mut queue = BitQueue::new();
+    for value in values {
+        queue.push(f(*value), WIDTH as u32);
+        writer_adapter = queue.flush(writer_adapter).0;
+    }
+    if let Some(byte) = queue.partial() {
+        writer_adapter = writer_adapter.write(&[byte]);
+    }
+    writer_adapter
+}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Writes `values` bit packed in `WIDTH` bits each
+    ///
+    /// # Panics
+    /// If value doesn't fit in `WIDTH` bits
+    #[track_caller]
+    pub fn write_bit_packed<const COUNT: usize, const WIDTH: usize>(self, values: &[u64; COUNT]) -> ConstWriter<T, {N - packed_len(COUNT, WIDTH)}> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: write_packed::<T, WIDTH>(self.writer_adapter, values, |value| value),
+            }
+        }
+    }
+
+    /// Writes deltas of `values` from `reference` bit packed in `WIDTH` bits each.
+    /// `reference` itself isn't written, usually it's block minimum stored in header.
+    ///
+    /// # Panics
+    /// If value is less than `reference` or delta doesn't fit in `WIDTH` bits
+    #[track_caller]
+    pub fn write_delta_block<const COUNT: usize, const WIDTH: usize>(self, values: &[u64; COUNT], reference: u64) -> ConstWriter<T, {N - packed_len(COUNT, WIDTH)}> {
+        let delta = |value: u64| {
+            assert!(value >= reference, "value is less than reference: {} < {}", value, reference);
+            value - reference
+        };
+        unsafe {
+            ConstWriter {
+                writer_adapter: write_packed::<T, WIDTH>(self.writer_adapter, values, delta),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn bit_packed_odd_width() {
+        let mut buff = [0u8; 13];
+        buff.const_writer::<13>()
+            .write_bit_packed::<3, 3>(&[1, 2, 7])
+            .write_bit_packed::<1, 64>(&[u64::MAX])
+            .write_bit_packed::<2, 12>(&[0xABC, 0x123]);
+        assert_eq!(buff, [0xD1, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xBC, 0x3A, 0x12]);
+    }
+
+    #[test]
+    #[should_panic(expected = "value doesn't fit in 4 bits: 16")]
+    fn delta_block_too_wide() {
+        let mut buff = [0u8; 1];
+        buff.const_writer::<1>().write_delta_block::<2, 4>(&[5, 21], 5);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn delta_block_checked_before_write() {
+        let mut buff = [0xAAu8; 2];
+        for values in [[1, 2, 3, 17], [1, 2, 3, 0]] {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                buff.const_writer::<2>().write_delta_block::<4, 4>(&values, 1);
+            }));
+            assert!(result.is_err());
+            assert_eq!(buff, [0xAA, 0xAA]);
+        }
+    }
+}
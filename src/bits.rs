@@ -0,0 +1,213 @@
+//! [`ConstBitWriter`] which tracks remaining space in bits.
+//!
+//! Bits are packed little endian, starting from lowest bit of first byte. [`tsdb`](crate::tsdb) XOR
+//! compression encodes through this writer, and [`bitpack`](crate::bitpack) uses same packing.
+//! Writing more bits than writer was created for is compile error.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::bits;
+//!
+//! let mut buff = [0xFFu8; 2];
+//! let len = bits::const_bit_writer::<12>(&mut buff)
+//!     .write_bool(true)
+//!     .write_bits::<3>(0b101)
+//!     .write_bits::<5>(0b11111)
+//!     .finish();
+//! assert_eq!(len, 2);
+//! assert_eq!(buff, [0b1111_1011, 0b0000_0001]);
+//! ```
+//!
+//! ```compile_fail
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::bits;
+//!
+//! let mut buff = [0u8; 2];
+//! bits::const_bit_writer::<12>(&mut buff).write_bits::<13>(0); // compile error
+//! ```
+
+/// Pending bits of little endian bit stream, first bit goes to lowest bit of byte.
+///
+/// Complete bytes have to be taken after every push, so queue holds at most 7 bits between pushes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct BitQueue {
+    /// lowest `bits` bits are valid
+    acc: u128,
+    bits: u32,
+}
+
+impl BitQueue {
+    pub(crate) const fn new() -> Self {
+        Self {
+            acc: 0,
+            bits: 0,
+        }
+    }
+
+    /// Appends low `bits` bits of `value`, up to 64 bits
+    pub(crate) fn push(&mut self, value: u64, bits: u32) {
+        if bits != 0 {
+            let value = value as u128 & ((1 << bits) - 1);
+            self.acc |= value << self.bits;
+            self.bits += bits;
+        }
+    }
+
+    /// Takes complete byte
+    pub(crate) fn pop_byte(&mut self) -> Option<u8> {
+        if self.bits < 8 {
+            return None;
+        }
+        let byte = self.acc as u8;
+        self.acc >>= 8;
+        self.bits -= 8;
+        Some(byte)
+    }
+
+    /// Incomplete byte padded with zeros, it stays in queue
+    pub(crate) fn partial(&self) -> Option<u8> {
+        if self.bits == 0 {
+            None
+        } else {
+            Some(self.acc as u8)
+        }
+    }
+
+    /// Number of pending bits
+    pub(crate) fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Writes complete bytes to adapter, returns number of written bytes
+    pub(crate) unsafe fn flush<T: crate::ConstWriterAdapter>(&mut self, mut writer_adapter: T) -> (T, usize) {
+        let mut len = 0;
+        while let Some(byte) = self.pop_byte() {
+            writer_adapter = writer_adapter.write(&[byte]);
+            len += 1;
+        }
+        (writer_adapter, len)
+    }
+}
+
+/// Compile time check of value width
+struct Width<const W: usize>;
+
+impl<const W: usize> Width<W> {
+    const CHECK: () = assert!(W <= 64, "value is up to 64 bits");
+}
+
+/// Writer of `BITS` bits
+pub struct ConstBitWriter<'a, const BITS: usize> {
+    buff: &'a mut [u8],
+    /// number of complete bytes written to `buff`
+    len: usize,
+    /// bits after complete bytes, they are also kept in `buff`
+    queue: BitQueue,
+}
+
+/// Get [`ConstBitWriter`] to write `BITS` bits to start of `buff`, bytes it covers are zeroed
+///
+/// # Panics
+/// If `buff` is shorter than `BITS` bits
+#[track_caller]
+pub fn const_bit_writer<const BITS: usize>(buff: &mut [u8]) -> ConstBitWriter<'_, BITS> {
+    let len = BITS.div_ceil(8);
+    assert!(
+        len <= buff.len(),
+        "slice too short: {} < {} ({})",
+        buff.len(),
+        len,
+        core::any::type_name::<ConstBitWriter<'_, BITS>>()
+    );
+    buff[..len].fill(0);
+    ConstBitWriter {
+        buff,
+        len: 0,
+        queue: BitQueue::new(),
+    }
+}
+
+impl<'a, const BITS: usize> ConstBitWriter<'a, BITS> {
+    /// Writes low `bits` bits of `value`, caller checks budget
+    pub(crate) fn put(&mut self, value: u64, bits: usize) {
+        self.queue.push(value, bits as u32);
+        while let Some(byte) = self.queue.pop_byte() {
+            self.buff[self.len] = byte;
+            self.len += 1;
+        }
+        if let Some(byte) = self.queue.partial() {
+            self.buff[self.len] = byte;
+        }
+    }
+
+    /// Changes budget, caller checks that `M` bits fit
+    pub(crate) fn cast<const M: usize>(self) -> ConstBitWriter<'a, M> {
+        ConstBitWriter {
+            buff: self.buff,
+            len: self.len,
+            queue: self.queue,
+        }
+    }
+
+    /// Writes `value` in `W` bits
+    ///
+    /// # Panics
+    /// If `value` doesn't fit in `W` bits
+    #[track_caller]
+    pub fn write_bits<const W: usize>(mut self, value: u64) -> ConstBitWriter<'a, {BITS - W}> {
+        let () = Width::<W>::CHECK;
+        assert!(W == 64 || value >> W == 0, "value doesn't fit in {} bits: {}", W, value);
+        self.put(value, W);
+        self.cast()
+    }
+
+    pub fn write_bool(mut self, value: bool) -> ConstBitWriter<'a, {BITS - 1}> {
+        self.put(value as u64, 1);
+        self.cast()
+    }
+
+    /// Writes `S` bits with `f`, like [`ConstWriter::write_with`](crate::ConstWriter::write_with)
+    pub fn write_with<const S: usize>(self, f: impl FnOnce(ConstBitWriter<'a, S>) -> ConstBitWriter<'a, 0>) -> ConstBitWriter<'a, {BITS - S}> {
+        f(self.cast()).cast()
+    }
+
+    /// Drops rest of budget, following bits are left zero
+    pub fn skip(self) -> ConstBitWriter<'a, 0> {
+        self.cast()
+    }
+
+    /// Number of bits written since writer was created
+    pub fn bits_written(&self) -> usize {
+        self.len * 8 + self.queue.bits() as usize
+    }
+
+    /// Number of bytes taken by written bits
+    pub fn finish(self) -> usize {
+        self.bits_written().div_ceil(8)
+    }
+}
+
+impl<'a, const BITS: usize> core::fmt::Debug for ConstBitWriter<'a, BITS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ConstBitWriter")
+            .field("remaining", &BITS)
+            .field("bits_written", &self.bits_written())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn bit_writer_64_bit_value_across_bytes() {
+        let mut buff = [0u8; 9];
+        let writer = super::const_bit_writer::<68>(&mut buff)
+            .write_bits::<4>(0xF)
+            .write_bits::<64>(u64::MAX);
+        assert_eq!(writer.bits_written(), 68);
+        assert_eq!(writer.finish(), 9);
+        assert_eq!(buff, [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+    }
+}
@@ -0,0 +1,112 @@
+//! Bluetooth LE advertising data, sequence of `[len][type][data]` AD structures.
+//!
+//! Legacy advertising payload is 31 bytes, [`AdvertisingData::writer`] checks at compile time
+//! that all AD structures fit in it.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ble::{self, AdvertisingData};
+//!
+//! let mut data = AdvertisingData::new();
+//! let w = ble::write_ad_structure::<1, _, _>(data.writer::<9>(), ble::FLAGS, |w| w.write_u8_le(0x06));
+//! ble::write_ad_structure::<4, _, _>(w, ble::COMPLETE_LOCAL_NAME, |w| w.write_slice(b"lamp"));
+//! assert_eq!(data.as_bytes(), [2, 0x01, 0x06, 5, 0x09, b'l', b'a', b'm', b'p']);
+//! ```
+//!
+//! ```compile_fail
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ble::{self, AdvertisingData};
+//!
+//! let mut data = AdvertisingData::new();
+//! ble::write_ad_structure::<30, _, _>(data.writer::<32>(), ble::MANUFACTURER_DATA, |w| w.write_slice(&[0; 30]));
+//! ```
+use crate::{ConstWrite, ConstWriter, ConstWriterAdapter};
+use crate::array::ArrayWriterAdapter;
+
+/// Length of legacy advertising payload
+pub const MAX_LEN: usize = 31;
+
+pub const FLAGS: u8 = 0x01;
+pub const INCOMPLETE_UUID16: u8 = 0x02;
+pub const COMPLETE_UUID16: u8 = 0x03;
+pub const INCOMPLETE_UUID128: u8 = 0x06;
+pub const COMPLETE_UUID128: u8 = 0x07;
+pub const SHORTENED_LOCAL_NAME: u8 = 0x08;
+pub const COMPLETE_LOCAL_NAME: u8 = 0x09;
+pub const TX_POWER_LEVEL: u8 = 0x0A;
+pub const SERVICE_DATA_UUID16: u8 = 0x16;
+pub const APPEARANCE: u8 = 0x19;
+pub const MANUFACTURER_DATA: u8 = 0xFF;
+
+/// Compile time check of advertising payload length
+struct PayloadLen<const LEN: usize>;
+
+impl<const LEN: usize> PayloadLen<LEN> {
+    const CHECK: () = assert!(LEN <= MAX_LEN, "advertising data is up to 31 bytes");
+}
+
+/// Writes AD structure with `LEN` bytes of data written by `f`
+pub fn write_ad_structure<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    ad_type: u8,
+    f: impl FnOnce(ConstWriter<T, LEN>) -> ConstWriter<T, 0>,
+) -> ConstWriter<T, {N - (LEN + 2)}> {
+    let () = PayloadLen::<{LEN + 2}>::CHECK;
+    let writer_adapter = unsafe { writer.writer_adapter.write(&[LEN as u8 + 1, ad_type]) };
+    ConstWriter {
+        writer_adapter: f(ConstWriter { writer_adapter }).writer_adapter,
+    }
+}
+
+/// Advertising payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdvertisingData {
+    data: [u8; MAX_LEN],
+    len: usize,
+}
+
+impl AdvertisingData {
+    pub fn new() -> Self {
+        Self {
+            data: [0; MAX_LEN],
+            len: 0,
+        }
+    }
+
+    /// Writer for payload of `LEN` bytes, replacing previous one. Bytes which
+    /// aren't written stay zero, which is valid early end of payload.
+    pub fn writer<const LEN: usize>(&mut self) -> ConstWriter<ArrayWriterAdapter<'_, MAX_LEN>, LEN> {
+        let () = PayloadLen::<LEN>::CHECK;
+        self.data = [0; MAX_LEN];
+        self.len = LEN;
+        self.data.const_writer::<LEN>()
+    }
+
+    /// Written AD structures
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl Default for AdvertisingData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn ble_full_payload() {
+        let mut buff = [0u8; 31];
+        super::write_ad_structure::<29, _, _>(buff.const_writer::<31>(), super::MANUFACTURER_DATA, |w| {
+            w.write_u16_le(0xFFFF).write_slice(&[7; 27])
+        });
+        assert_eq!(buff[..4], [30, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(buff[30], 7);
+    }
+}
@@ -0,0 +1,125 @@
+//! Bloom filters for page footers.
+//!
+//! Filter is written as `[k u8][bit array]`, bit array of `BITS` bits takes [`byte_len`] bytes,
+//! bit `i` is bit `i % 8` of byte `i / 8`. Bits are set from 64 bit hash of key with
+//! double hashing, hash function is left to caller.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::bloom::BitArray;
+//!
+//! let mut filter = BitArray::<64>::new();
+//! filter.insert_hash(3, 0x1234_5678_9ABC_DEF0);
+//! assert!(filter.contains_hash(3, 0x1234_5678_9ABC_DEF0));
+//!
+//! let mut footer = vec![];
+//! footer.const_writer::<9>().write_bloom_filter(3, &filter);
+//! assert_eq!((footer[0], &footer[1..]), (3, &filter.as_bytes()[..]));
+//! ```
+//!
+//! ```compile_fail
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! let filter = const_writer::bloom::BitArray::<0>::new(); // at least one bit is required
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Number of bytes taken by `bits` bits
+pub const fn byte_len(bits: usize) -> usize {
+    bits.div_ceil(8)
+}
+
+/// Fixed size array of `BITS` bits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitArray<const BITS: usize> where [(); byte_len(BITS)]: {
+    bytes: [u8; byte_len(BITS)],
+}
+
+impl<const BITS: usize> BitArray<BITS> where [(); byte_len(BITS)]: {
+    const CHECK: () = assert!(BITS > 0, "bit array must have at least one bit");
+
+    /// All bits cleared
+    pub const fn new() -> Self {
+        let () = Self::CHECK;
+        Self {
+            bytes: [0; byte_len(BITS)],
+        }
+    }
+
+    /// # Panics
+    /// If `index` isn't less than `BITS`
+    #[track_caller]
+    pub fn set(&mut self, index: usize) {
+        assert!(index < BITS, "bit index out of range: {} >= {}", index, BITS);
+        self.bytes[index / 8] |= 1 << (index % 8);
+    }
+
+    /// # Panics
+    /// If `index` isn't less than `BITS`
+    #[track_caller]
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < BITS, "bit index out of range: {} >= {}", index, BITS);
+        self.bytes[index / 8] & 1 << (index % 8) != 0
+    }
+
+    /// Sets `k` bits of key with `hash`
+    pub fn insert_hash(&mut self, k: u8, hash: u64) {
+        for i in 0..k {
+            self.set(Self::probe(hash, i));
+        }
+    }
+
+    /// Checks `k` bits of key with `hash`, false positives are possible
+    pub fn contains_hash(&self, k: u8, hash: u64) -> bool {
+        (0..k).all(|i| self.get(Self::probe(hash, i)))
+    }
+
+    /// Bit `i` of `k`: `h1 + i * h2`, halves of hash are `h1` and `h2`
+    fn probe(hash: u64, i: u8) -> usize {
+        let (h1, h2) = (hash & 0xFFFF_FFFF, hash >> 32);
+        (h1.wrapping_add((i as u64).wrapping_mul(h2)) % BITS as u64) as usize
+    }
+
+    pub fn as_bytes(&self) -> &[u8; byte_len(BITS)] {
+        &self.bytes
+    }
+}
+
+impl<const BITS: usize> Default for BitArray<BITS> where [(); byte_len(BITS)]: {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Writes `k` and bit array of filter
+    pub fn write_bloom_filter<const BITS: usize>(self, k: u8, bits: &BitArray<BITS>) -> ConstWriter<T, {N - (1 + byte_len(BITS))}>
+    where [(); byte_len(BITS)]: {
+        unsafe {
+            ConstWriter {
+                writer_adapter: self.writer_adapter
+                    .write(&[k])
+                    .write(bits.as_bytes()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::BitArray;
+
+    #[test]
+    fn bloom_filter_odd_bits() {
+        let mut filter = BitArray::<10>::new();
+        filter.set(0);
+        filter.set(9);
+        assert!(!filter.get(8));
+        let mut buff = [0u8; 3];
+        buff.const_writer::<3>().write_bloom_filter(2, &filter);
+        assert_eq!(buff, [2, 0x01, 0x02]);
+    }
+}
@@ -0,0 +1,122 @@
+//! Frames built directly in `Box` allocation, without `Vec` round-trip.
+//!
+//! [`ConstWriter::build_boxed`] allocates uninitialized `Box<[u8; N]>` and returns writer of it.
+//! Bytes left unwritten (e.g. after `shrink`) are zeroed when writer is finished. Shared frame
+//! from `into_rc` or `into_arc` takes one copy of `N` bytes to allocation with reference counts.
+//!
+//! ```
+//! use const_writer::ConstWriter;
+//!
+//! let frame = ConstWriter::build_boxed::<6>().write_u16_be(1).write_u32_le(2).finish();
+//! assert_eq!(*frame, [0, 1, 2, 0, 0, 0]);
+//!
+//! // immutable frame shared between send queues
+//! let shared = ConstWriter::build_boxed::<4>().write_u32_be(7).into_arc();
+//! let queued = std::sync::Arc::clone(&shared);
+//! assert_eq!(*queued, [0, 0, 0, 7]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+use core::mem::MaybeUninit;
+
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+
+/// Writes from start of uninitialized boxed array
+pub struct BoxedWriterAdapter<const L: usize> {
+    boxed: Box<MaybeUninit<[u8; L]>>,
+    written: usize,
+}
+
+impl<const L: usize> crate::sealed::Sealed for BoxedWriterAdapter<L> {}
+
+impl<const L: usize> core::fmt::Debug for BoxedWriterAdapter<L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<const L: usize> ConstWriterAdapter for BoxedWriterAdapter<L> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        let ptr = self.boxed.as_mut_ptr().cast::<u8>().add(self.written);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr, N);
+        self.written += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= L - self.written,
+            "remaining boxed array too short to grow: {} < {} ({})",
+            L - self.written,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(mut self) -> Self {
+        core::ptr::write_bytes(self.boxed.as_mut_ptr().cast::<u8>().add(self.written), crate::POISON, N);
+        self
+    }
+}
+
+impl ConstWriter<BoxedWriterAdapter<0>, 0> {
+    /// Allocates uninitialized `Box<[u8; N]>` and returns writer of it
+    pub fn build_boxed<const N: usize>() -> ConstWriter<BoxedWriterAdapter<N>, N> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: crate::poisoned::<_, N>(BoxedWriterAdapter {
+                    boxed: Box::new_uninit(),
+                    written: 0,
+                }),
+            }
+        }
+    }
+}
+
+impl<const L: usize, const N: usize> ConstWriter<BoxedWriterAdapter<L>, {N}> {
+    /// Zeroes bytes left unwritten and returns boxed array
+    pub fn finish(self) -> Box<[u8; L]> {
+        let BoxedWriterAdapter { mut boxed, written } = self.writer_adapter;
+        unsafe {
+            core::ptr::write_bytes(boxed.as_mut_ptr().cast::<u8>().add(written), 0, L - written);
+            boxed.assume_init()
+        }
+    }
+
+    /// Same as [`finish`](Self::finish), but copies array to `Rc`
+    pub fn into_rc(self) -> Rc<[u8; L]> {
+        Rc::from(self.finish())
+    }
+
+    /// Same as [`finish`](Self::finish), but copies array to `Arc` to share it between threads
+    pub fn into_arc(self) -> Arc<[u8; L]> {
+        Arc::from(self.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWriter;
+
+    #[test]
+    fn boxed_unwritten_tail_is_zeroed() {
+        let frame = ConstWriter::build_boxed::<6>().write_u16_le(0xFFFF).shrink::<0>().into_rc();
+        assert_eq!(*frame, [0xFF, 0xFF, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn boxed_large_frame() {
+        // larger than test thread stack
+        let frame = ConstWriter::build_boxed::<{ 16 << 20 }>().write_u32_le(1).finish();
+        assert_eq!(frame[..5], [1, 0, 0, 0, 0]);
+    }
+}
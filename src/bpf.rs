@@ -0,0 +1,152 @@
+//! Fixed layout records shared with eBPF programs, like perf event or ring buffer records.
+//!
+//! [`bpf_record!`](crate::bpf_record) defines `#[repr(C)]` struct, which matches struct on kernel
+//! side, and implements [`ConstWritable`](crate::ConstWritable) which writes fields in native byte
+//! order at their `repr(C)` offsets. Padding is written as zeros, so written bytes are
+//! exactly what kernel reads and uninitialized padding never leaks.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//! use const_writer::bpf::PerfEventHeader;
+//!
+//! const_writer::bpf_record! {
+//!     // struct event { __u8 kind; __u64 ts; __u32 pid; };
+//!     pub struct Event {
+//!         pub header: PerfEventHeader,
+//!         pub kind: u8,
+//!         pub ts: u64,
+//!         pub pid: u32,
+//!     }
+//! }
+//!
+//! let size = core::mem::size_of::<Event>() as u16;
+//! let event = Event {
+//!     header: PerfEventHeader { kind: 9, misc: 0, size },
+//!     kind: 1,
+//!     ts: 2,
+//!     pid: 3,
+//! };
+//! let mut vec = vec![];
+//! vec.const_writer::<32>().write_value(&event);
+//! assert_eq!(vec[8..16], [1, 0, 0, 0, 0, 0, 0, 0]); // 7 bytes of padding before ts
+//! assert_eq!(vec[16..24], 2u64.to_ne_bytes());
+//! assert_eq!(vec[24..], [3u32.to_ne_bytes(), [0; 4]].concat()[..]);
+//! ```
+use crate::ConstWriterAdapter;
+
+/// Type allowed in BPF record: integer, fixed array or other record
+///
+/// # Safety
+/// `write_ne` must write exactly `size_of::<Self>()` bytes, representation of value in memory
+pub unsafe trait BpfField {
+    /// # Safety
+    /// Underlying buffer must have space for `size_of::<Self>()` bytes
+    unsafe fn write_ne<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T;
+}
+
+macro_rules! bpf_scalar {
+    ($($type:ty),*) => {
+        $(
+            unsafe impl BpfField for $type {
+                unsafe fn write_ne<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                    writer_adapter.write(&self.to_ne_bytes())
+                }
+            }
+        )*
+    }
+}
+
+bpf_scalar!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
+
+unsafe impl BpfField for bool {
+    unsafe fn write_ne<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+        writer_adapter.write(&[*self as u8])
+    }
+}
+
+/// Size of element is multiple of its alignment, so array has no padding
+unsafe impl<V: BpfField, const L: usize> BpfField for [V; L] {
+    unsafe fn write_ne<T: ConstWriterAdapter>(&self, mut writer_adapter: T) -> T {
+        for value in self {
+            writer_adapter = value.write_ne(writer_adapter);
+        }
+        writer_adapter
+    }
+}
+
+/// Defines `#[repr(C)]` struct with [`BpfField`](crate::bpf::BpfField) and
+/// [`ConstWritable`](crate::ConstWritable) impls. See [`bpf`](crate::bpf) module.
+#[macro_export]
+macro_rules! bpf_record {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $type:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(C)]
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $type),*
+        }
+
+        unsafe impl $crate::bpf::BpfField for $name {
+            #[allow(unused_mut)]
+            unsafe fn write_ne<T: $crate::ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                let mut offset = 0;
+                $(
+                    let field_offset = ::core::mem::offset_of!($name, $field);
+                    let writer_adapter = $crate::aligned::write_padding(writer_adapter, field_offset - offset);
+                    let writer_adapter = $crate::bpf::BpfField::write_ne(&self.$field, writer_adapter);
+                    offset = field_offset + ::core::mem::size_of::<$type>();
+                )*
+                $crate::aligned::write_padding(writer_adapter, ::core::mem::size_of::<Self>() - offset)
+            }
+        }
+
+        unsafe impl $crate::ConstWritable for $name {
+            const SIZE: usize = ::core::mem::size_of::<Self>();
+
+            unsafe fn write_to<T: $crate::ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                $crate::bpf::BpfField::write_ne(self, writer_adapter)
+            }
+        }
+    }
+}
+
+crate::bpf_record! {
+    /// `struct perf_event_header`, starts every record of perf buffer
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct PerfEventHeader {
+        /// `PERF_RECORD_*`, e.g. 9 for `PERF_RECORD_SAMPLE`
+        pub kind: u32,
+        pub misc: u16,
+        /// size of record including header
+        pub size: u16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConstWritable, ConstWrite};
+
+    crate::bpf_record! {
+        struct Sample {
+            flag: bool,
+            comm: [u8; 3],
+            cpu: u16,
+            value: i64,
+            tail: u8,
+        }
+    }
+
+    #[test]
+    fn bpf_record_matches_repr_c() {
+        assert_eq!(<Sample as ConstWritable>::SIZE, core::mem::size_of::<Sample>());
+        let mut buff = [0xFFu8; 24];
+        buff.const_writer::<24>().write_value(&Sample { flag: true, comm: *b"abc", cpu: 5, value: -2, tail: 7 });
+        assert_eq!(buff[..8], [1, b'a', b'b', b'c', 5u16.to_ne_bytes()[0], 5u16.to_ne_bytes()[1], 0, 0]);
+        assert_eq!(buff[8..16], (-2i64).to_ne_bytes());
+        assert_eq!(buff[16..], [7, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,114 @@
+//! Adapter which writes to region allocated from [`bumpalo::Bump`] arena.
+//!
+//! ```
+//! use bumpalo::Bump;
+//!
+//! let bump = Bump::new();
+//! let bytes = const_writer::bump::const_writer::<8>(&bump)
+//!     .write_u32_le(1)
+//!     .write_u16_be(2)
+//!     .finish();
+//! assert_eq!(bytes, &[1, 0, 0, 0, 0, 2]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+use bumpalo::Bump;
+use core::alloc::Layout;
+
+pub struct BumpWriterAdapter<'bump> {
+    bump: &'bump Bump,
+    /// start of allocated region
+    start: *mut u8,
+    /// number of bytes written
+    written: usize,
+}
+
+impl<'bump> BumpWriterAdapter<'bump> {
+    unsafe fn new<const N: usize>(bump: &'bump Bump) -> Self {
+        let start = bump.alloc_layout(Layout::array::<u8>(N).unwrap()).as_ptr();
+        Self {
+            bump,
+            start,
+            written: 0,
+        }
+    }
+}
+
+impl<'bump> crate::sealed::Sealed for BumpWriterAdapter<'bump> {}
+
+impl<'bump> core::fmt::Debug for BumpWriterAdapter<'bump> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'bump> ConstWriterAdapter for BumpWriterAdapter<'bump> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        core::ptr::copy_nonoverlapping(value.as_ptr(), self.start.add(self.written), N);
+        self.written += N;
+        self
+    }
+
+    /// Allocates new region and moves written bytes to it, previous region is left in arena.
+    unsafe fn grow<const M: usize>(mut self) -> Self {
+        let start = self.bump.alloc_layout(Layout::array::<u8>(self.written + M).unwrap()).as_ptr();
+        core::ptr::copy_nonoverlapping(self.start, start, self.written);
+        self.start = start;
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        core::ptr::write_bytes(self.start.add(self.written), crate::POISON, N);
+        self
+    }
+}
+
+impl<'bump> crate::GrowableAdapter for BumpWriterAdapter<'bump> {}
+
+impl<'bump, const N: usize> ConstWriter<BumpWriterAdapter<'bump>, {N}> {
+    /// Finishes writing and returns written bytes
+    pub fn finish(self) -> &'bump mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.writer_adapter.start, self.writer_adapter.written) }
+    }
+}
+
+/// Get [`ConstWriter`] to write `N` bytes to region allocated from `bump`
+pub fn const_writer<const N: usize>(bump: &Bump) -> ConstWriter<BumpWriterAdapter<'_>, N> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: crate::poisoned::<_, N>(BumpWriterAdapter::new::<N>(bump))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bumpalo::Bump;
+
+    #[test]
+    #[allow(deprecated)]
+    fn bump_write_grow() {
+        let bump = Bump::new();
+        let bytes = super::const_writer::<4>(&bump)
+            .write_u16_le(1)
+            .convert::<6>()
+            .write_u32_le(2)
+            .finish();
+        assert_eq!(bytes, &[1, 0, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn bump_write_grow_to() {
+        let bump = Bump::new();
+        let bytes = super::const_writer::<4>(&bump)
+            .write_u16_le(1)
+            .grow_to::<6>()
+            .write_u32_le(2)
+            .finish();
+        assert_eq!(bytes, &[1, 0, 2, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,167 @@
+//! CAN and CAN FD frames with [ISO-TP](https://en.wikipedia.org/wiki/ISO_15765-2) headers.
+//!
+//! Frame payload is written through [`ConstWriter`] over frame data, so writing more than
+//! frame holds doesn't compile.
+//!
+//! ```
+//! use const_writer::can::{isotp, ClassicFrame};
+//!
+//! // first frame of 20 byte message
+//! let frame = ClassicFrame::build::<8>(0x7E0, |w| isotp::write_first_frame_header(w, 20).write_slice::<6>(b"VIN123"));
+//! assert_eq!(frame.payload(), [0x10, 20, b'V', b'I', b'N', b'1', b'2', b'3']);
+//!
+//! let frame = ClassicFrame::build::<3>(0x7E0, |w| isotp::write_single_frame_header::<2, _, _>(w).write_u16_be(0x3E00));
+//! assert_eq!((frame.dlc(), frame.payload()), (3, &[0x02, 0x3E, 0x00][..]));
+//! ```
+//!
+//! ```compile_fail
+//! use const_writer::can::ClassicFrame;
+//!
+//! ClassicFrame::build::<12>(1, |w| w.write_slice(&[0; 12])); // classic frame holds 8 bytes
+//! ```
+use crate::{ConstWrite, ConstWriter};
+use crate::array::ArrayWriterAdapter;
+
+/// Compile time check of frame payload length
+struct Payload<const LEN: usize, const CAP: usize>;
+
+impl<const LEN: usize, const CAP: usize> Payload<LEN, CAP> {
+    const CHECK: () = assert!(
+        LEN <= CAP && matches!(LEN, 0..=8 | 12 | 16 | 20 | 24 | 32 | 48 | 64),
+        "CAN frame length isn't valid"
+    );
+}
+
+/// Frame with up to `CAP` bytes of payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanFrame<const CAP: usize> {
+    /// 11 or 29 bit identifier
+    pub id: u32,
+    data: [u8; CAP],
+    len: usize,
+}
+
+/// Classic CAN frame
+pub type ClassicFrame = CanFrame<8>;
+/// CAN FD frame
+pub type FdFrame = CanFrame<64>;
+
+impl<const CAP: usize> CanFrame<CAP> {
+    /// Builds frame with `LEN` bytes of payload written by `f`.
+    /// `LEN` must be valid CAN FD length: up to 8, 12, 16, 20, 24, 32, 48 or 64.
+    pub fn build<const LEN: usize>(
+        id: u32,
+        f: impl for<'p> FnOnce(ConstWriter<ArrayWriterAdapter<'p, CAP>, LEN>) -> ConstWriter<ArrayWriterAdapter<'p, CAP>, 0>,
+    ) -> Self {
+        let () = Payload::<LEN, CAP>::CHECK;
+        let mut data = [0u8; CAP];
+        f(data.const_writer::<LEN>());
+        Self {
+            id,
+            data,
+            len: LEN,
+        }
+    }
+
+    /// Written payload
+    pub fn payload(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+
+    /// Data length code of frame
+    pub fn dlc(&self) -> u8 {
+        match self.len {
+            0..=8 => self.len as u8,
+            12 => 9,
+            16 => 10,
+            20 => 11,
+            24 => 12,
+            32 => 13,
+            48 => 14,
+            _ => 15,
+        }
+    }
+}
+
+/// ISO-TP (ISO 15765-2) protocol control information for normal addressing.
+pub mod isotp {
+    use crate::{ConstWriter, ConstWriterAdapter};
+
+    /// Compile time check of single frame length
+    struct SingleLen<const LEN: usize>;
+
+    impl<const LEN: usize> SingleLen<LEN> {
+        const CHECK: () = assert!(LEN <= 7, "single frame carries up to 7 bytes, use first frame");
+    }
+
+    /// Flow control status
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum FlowStatus {
+        ContinueToSend = 0,
+        Wait = 1,
+        Overflow = 2,
+    }
+
+    /// Writes header of single frame carrying `LEN` bytes, which must follow it
+    pub fn write_single_frame_header<const LEN: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 1}> {
+        let () = SingleLen::<LEN>::CHECK;
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&[LEN as u8]),
+            }
+        }
+    }
+
+    /// Writes header of first frame of message with `len` bytes
+    ///
+    /// # Panics
+    /// If `len` is less than 8 or greater than 4095
+    #[track_caller]
+    pub fn write_first_frame_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, len: u16) -> ConstWriter<T, {N - 2}> {
+        assert!((8..=0xFFF).contains(&len), "first frame length must be in 8..=4095: {}", len);
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&(0x1000 | len).to_be_bytes()),
+            }
+        }
+    }
+
+    /// Writes header of consecutive frame, only low 4 bits of `seq` are used
+    pub fn write_consecutive_frame_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, seq: u8) -> ConstWriter<T, {N - 1}> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&[0x20 | (seq & 0x0F)]),
+            }
+        }
+    }
+
+    /// Writes flow control frame
+    pub fn write_flow_control<T: ConstWriterAdapter, const N: usize>(
+        writer: ConstWriter<T, {N}>,
+        status: FlowStatus,
+        block_size: u8,
+        st_min: u8,
+    ) -> ConstWriter<T, {N - 3}> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&[0x30 | status as u8, block_size, st_min]),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{isotp, FdFrame};
+
+    #[test]
+    fn fd_frame_dlc() {
+        let frame = FdFrame::build::<12>(0x18DA00F1, |w| {
+            let w = isotp::write_consecutive_frame_header(w, 0x11);
+            let w = isotp::write_flow_control(w, isotp::FlowStatus::Wait, 0, 10);
+            w.write_u64_be(u64::MAX)
+        });
+        assert_eq!(frame.dlc(), 9);
+        assert_eq!(frame.payload()[..4], [0x21, 0x31, 0, 10]);
+    }
+}
@@ -0,0 +1,106 @@
+//! [Cap'n Proto](https://capnproto.org/encoding.html) words, pointers and segment table.
+//!
+//! Everything is counted in 8 byte words, pointers are single word with bitfields packed
+//! little endian. Offsets of struct pointers are counted from end of pointer.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::capnp;
+//!
+//! // single segment message: root pointer and struct of 1 data word
+//! let mut vec = vec![];
+//! let writer = capnp::write_segment_table::<1, _, _>(vec.const_writer::<24>(), &[2]);
+//! let writer = capnp::write_struct_pointer(writer, 0, 1, 0);
+//! capnp::write_words::<1, _, _>(writer, &[42]);
+//! assert_eq!(vec[..8], [0, 0, 0, 0, 2, 0, 0, 0]);
+//! assert_eq!(vec[8..16], [0, 0, 0, 0, 1, 0, 0, 0]);
+//! assert_eq!(vec[16..], [42, 0, 0, 0, 0, 0, 0, 0]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Length of segment table of `segments` segments, padded to word
+pub const fn segment_table_len(segments: usize) -> usize {
+    (4 + 4 * segments).div_ceil(8) * 8
+}
+
+/// Compile time check of segment count
+struct Segments<const S: usize>;
+
+impl<const S: usize> Segments<S> {
+    const CHECK: () = assert!(S >= 1, "message has at least one segment");
+}
+
+/// Writes `WORDS` little endian words
+pub fn write_words<const WORDS: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, words: &[u64; WORDS]) -> ConstWriter<T, {N - 8 * WORDS}> {
+    let mut writer_adapter = writer.writer_adapter;
+    for word in words {
+        writer_adapter = unsafe { writer_adapter.write(&word.to_le_bytes()) };
+    }
+    ConstWriter { writer_adapter }
+}
+
+/// Writes segment table of stream framing, `sizes` are in words
+pub fn write_segment_table<const S: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, sizes: &[u32; S]) -> ConstWriter<T, {N - segment_table_len(S)}> {
+    let () = Segments::<S>::CHECK;
+    let mut writer_adapter = unsafe { writer.writer_adapter.write(&(S as u32 - 1).to_le_bytes()) };
+    for size in sizes {
+        writer_adapter = unsafe { writer_adapter.write(&size.to_le_bytes()) };
+    }
+    if S.is_multiple_of(2) {
+        writer_adapter = unsafe { writer_adapter.write(&[0; 4]) };
+    }
+    ConstWriter { writer_adapter }
+}
+
+/// Writes struct pointer, `offset` is signed 30 bit number of words from end of pointer
+/// to start of struct, data and pointer sections are in words
+///
+/// # Panics
+/// If `offset` doesn't fit in 30 bits
+#[track_caller]
+pub fn write_struct_pointer<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, offset: i32, data_words: u16, pointers: u16) -> ConstWriter<T, {N - 8}> {
+    assert!((-(1 << 29)..1 << 29).contains(&offset), "offset doesn't fit in 30 bits: {}", offset);
+    let lower = (offset as u32) << 2;
+    let upper = data_words as u32 | (pointers as u32) << 16;
+    write_pointer(writer, lower, upper)
+}
+
+/// Writes far pointer to landing pad at `offset` words in segment `segment`,
+/// landing pad is two words if `double_far`
+///
+/// # Panics
+/// If `offset` doesn't fit in 29 bits
+#[track_caller]
+pub fn write_far_pointer<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, double_far: bool, offset: u32, segment: u32) -> ConstWriter<T, {N - 8}> {
+    assert!(offset < 1 << 29, "offset doesn't fit in 29 bits: {}", offset);
+    let lower = 2 | (double_far as u32) << 2 | offset << 3;
+    write_pointer(writer, lower, segment)
+}
+
+fn write_pointer<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, lower: u32, upper: u32) -> ConstWriter<T, {N - 8}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&lower.to_le_bytes())
+                .write(&upper.to_le_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn capnp_negative_offset_and_far_pointer() {
+        let mut buff = [0xFFu8; 32];
+        let writer = super::write_struct_pointer(buff.const_writer::<32>(), -1, 2, 3);
+        let writer = super::write_far_pointer(writer, true, 5, 1);
+        super::write_segment_table::<2, _, _>(writer, &[0, 0]);
+        assert_eq!(buff[..8], [0xFC, 0xFF, 0xFF, 0xFF, 2, 0, 3, 0]);
+        assert_eq!(buff[8..16], [0x2E, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(buff[16..], [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
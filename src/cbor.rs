@@ -0,0 +1,97 @@
+//! Helpers for canonical [CBOR](https://www.rfc-editor.org/rfc/rfc8949) heads which argument is known
+//! at compile time, so their width is known too.
+//!
+//! ```
+//! use const_writer::{ConstWrite, cbor};
+//!
+//! let mut vec = vec![];
+//! let writer = cbor::write_array_header::<2, _, _>(vec.const_writer::<8>());
+//! let writer = cbor::write_uint::<500, _, _>(writer);
+//! cbor::write_bytes_header::<3, _, _>(writer)
+//!     .write_slice(&[1, 2, 3]);
+//!
+//! assert_eq!(vec, [0x82, 0x19, 0x01, 0xf4, 0x43, 1, 2, 3]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_ARRAY: u8 = 4;
+
+/// Size of canonical head for argument `value`
+pub const fn head_len(value: u64) -> usize {
+    if value < 24 {
+        1
+    } else if value <= u8::MAX as u64 {
+        2
+    } else if value <= u16::MAX as u64 {
+        3
+    } else if value <= u32::MAX as u64 {
+        5
+    } else {
+        9
+    }
+}
+
+/// Writes shortest head for `value`. Caller must ensure that adapter has space for `head_len(value)` bytes.
+unsafe fn write_head<T: ConstWriterAdapter>(writer_adapter: T, major: u8, value: u64) -> T {
+    let major = major << 5;
+    match head_len(value) {
+        1 => writer_adapter.write(&[major | value as u8]),
+        2 => writer_adapter.write(&[major | 24]).write(&(value as u8).to_be_bytes()),
+        3 => writer_adapter.write(&[major | 25]).write(&(value as u16).to_be_bytes()),
+        5 => writer_adapter.write(&[major | 26]).write(&(value as u32).to_be_bytes()),
+        _ => writer_adapter.write(&[major | 27]).write(&value.to_be_bytes()),
+    }
+}
+
+/// Writes unsigned integer `V`
+pub fn write_uint<const V: u64, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - head_len(V)}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: write_head(writer.writer_adapter, MAJOR_UINT, V),
+        }
+    }
+}
+
+/// Writes head of byte string with `LEN` bytes. Bytes must be written after header.
+pub fn write_bytes_header<const LEN: u64, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - head_len(LEN)}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: write_head(writer.writer_adapter, MAJOR_BYTES, LEN),
+        }
+    }
+}
+
+/// Writes head of array with `LEN` elements. Elements must be written after header.
+pub fn write_array_header<const LEN: u64, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - head_len(LEN)}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: write_head(writer.writer_adapter, MAJOR_ARRAY, LEN),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::*;
+
+    #[test]
+    fn cbor_uint_widths() {
+        let mut buff = [0u8; 20];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = write_uint::<23, _, _>(ref_buff.const_writer::<20>());
+        let writer = write_uint::<24, _, _>(writer);
+        let writer = write_uint::<65536, _, _>(writer);
+        let writer = write_uint::<{ u32::MAX as u64 + 1 }, _, _>(writer);
+        assert_eq!(writer.remaining(), 3);
+        drop(writer);
+        assert_eq!(buff[..17], [
+            0x17,
+            0x18, 0x18,
+            0x1a, 0x00, 0x01, 0x00, 0x00,
+            0x1b, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        ]);
+    }
+}
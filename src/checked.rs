@@ -0,0 +1,112 @@
+//! Adapter wrapper which independently counts written bytes and panics on overflow.
+//!
+//! Useful to verify custom adapters and code which uses `grow_to()` a lot.
+//! Checks are `debug_assert!`s, so in release build wrapper costs nothing.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//!
+//! let mut vec = vec![];
+//! vec.const_writer::<4>()
+//!     .checked()
+//!     .write_u16_le(1)
+//!     .grow_to::<6>()
+//!     .write_u32_le(2);
+//! assert_eq!(vec, [1, 0, 2, 0, 0, 0]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Wrapper which checks that inner adapter is never written past reserved space
+pub struct CheckedAdapter<T: ConstWriterAdapter> {
+    inner: T,
+    /// number of bytes inner adapter has space for
+    capacity: usize,
+    written: usize,
+}
+
+impl<T: ConstWriterAdapter> CheckedAdapter<T> {
+    /// Number of bytes written through wrapper
+    pub fn written(&self) -> usize {
+        self.written
+    }
+}
+
+impl<T: ConstWriterAdapter> crate::sealed::Sealed for CheckedAdapter<T> {}
+
+impl<T: ConstWriterAdapter> core::fmt::Debug for CheckedAdapter<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<T: ConstWriterAdapter> ConstWriterAdapter for CheckedAdapter<T> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    #[track_caller]
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        debug_assert!(
+            N <= self.capacity - self.written,
+            "write past reserved space: {} bytes written, {} reserved, {} more requested",
+            self.written,
+            self.capacity,
+            N
+        );
+        self.inner = self.inner.write_raw(value);
+        self.written += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(mut self) -> Self {
+        self.inner = self.inner.grow::<M>();
+        self.capacity = self.capacity.max(self.written + M);
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(mut self) -> Self {
+        self.inner = self.inner.poison::<N>();
+        self
+    }
+}
+
+impl<T: crate::GrowableAdapter> crate::GrowableAdapter for CheckedAdapter<T> {}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Wraps adapter into [`CheckedAdapter`], which has space for `N` bytes
+    pub fn checked(self) -> ConstWriter<CheckedAdapter<T>, {N}> {
+        ConstWriter {
+            writer_adapter: CheckedAdapter {
+                inner: self.writer_adapter,
+                capacity: N,
+                written: 0,
+            }
+        }
+    }
+}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<CheckedAdapter<T>, {N}> {
+    /// Number of bytes written since [`ConstWriter::checked`]
+    pub fn written(&self) -> usize {
+        self.writer_adapter.written()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConstWrite, ConstWriterAdapter};
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "write past reserved space: 2 bytes written, 4 reserved, 4 more requested")]
+    fn checked_write_past_reserved() {
+        let mut buff = [0u8; 8];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = ref_buff.const_writer::<4>().checked().write_u16_le(1);
+        assert_eq!(writer.written(), 2);
+        // misbehaving code bypassing const checks
+        unsafe { writer.writer_adapter.write(&[0u8; 4]); }
+    }
+}
@@ -0,0 +1,122 @@
+//! Additive checksums: Internet checksum (RFC 1071), Fletcher-16/32 and Adler-32.
+//!
+//! Checksum usually covers bytes which are written after its field, so field is reserved
+//! with [`ConstWriter::reserve_slot`](crate::ConstWriter::reserve_slot) and patched later.
+//! ```
+//! use const_writer::checksum::internet_checksum;
+//!
+//! assert_eq!(internet_checksum(&[0x00, 0x01, 0xF2, 0x03, 0xF4, 0xF5, 0xF6, 0xF7]), 0x220D);
+//! assert_eq!(const_writer::checksum::fletcher16(b"abcde"), 0xC8F0);
+//! assert_eq!(const_writer::checksum::fletcher32(b"abcde"), 0xF04FC729);
+//! assert_eq!(const_writer::checksum::adler32(b"Wikipedia"), 0x11E60398);
+//! ```
+
+/// Incremental 16 bit ones' complement sum of big endian words, used by IPv4, UDP and TCP
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InternetChecksum {
+    sum: u32,
+    /// first byte of word split between updates
+    odd: Option<u8>,
+}
+
+impl InternetChecksum {
+    pub const fn new() -> Self {
+        Self {
+            sum: 0,
+            odd: None,
+        }
+    }
+
+    /// Adds `bytes` to checksum, odd byte is carried to next update
+    pub fn update(mut self, mut bytes: &[u8]) -> Self {
+        if let (Some(high), Some((low, rest))) = (self.odd, bytes.split_first()) {
+            self.add(u16::from_be_bytes([high, *low]));
+            self.odd = None;
+            bytes = rest;
+        }
+        let mut words = bytes.chunks_exact(2);
+        for word in &mut words {
+            self.add(u16::from_be_bytes([word[0], word[1]]));
+        }
+        if let [last] = words.remainder() {
+            self.odd = Some(*last);
+        }
+        self
+    }
+
+    fn add(&mut self, word: u16) {
+        self.sum += word as u32;
+        self.sum = (self.sum & 0xFFFF) + (self.sum >> 16);
+    }
+
+    /// Complement of sum, odd trailing byte is padded with zero
+    pub fn finish(mut self) -> u16 {
+        if let Some(high) = self.odd.take() {
+            self.add(u16::from_be_bytes([high, 0]));
+        }
+        !(self.sum as u16)
+    }
+}
+
+/// Internet checksum of `bytes`
+pub fn internet_checksum(bytes: &[u8]) -> u16 {
+    InternetChecksum::new().update(bytes).finish()
+}
+
+/// Fletcher-16 of `bytes`
+pub fn fletcher16(bytes: &[u8]) -> u16 {
+    let (mut a, mut b) = (0u32, 0u32);
+    // sums stay below u32::MAX for 5802 bytes before reduction
+    for chunk in bytes.chunks(5802) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 255;
+        b %= 255;
+    }
+    (b << 8 | a) as u16
+}
+
+/// Fletcher-32 of `bytes` as little endian 16 bit words, odd trailing byte is padded with zero
+pub fn fletcher32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (0u64, 0u64);
+    for chunk in bytes.chunks(2 * 359) {
+        for word in chunk.chunks(2) {
+            a += u16::from_le_bytes([word[0], *word.get(1).unwrap_or(&0)]) as u64;
+            b += a;
+        }
+        a %= 65535;
+        b %= 65535;
+    }
+    (b << 16 | a) as u32
+}
+
+/// Adler-32 of `bytes`, same as zlib
+pub fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // largest n such that 255n(n+1)/2 + (n+1)(MOD-1) fits in u32
+    for chunk in bytes.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    b << 16 | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{internet_checksum, InternetChecksum};
+
+    #[test]
+    fn internet_checksum_odd_split() {
+        let bytes = [0x45, 0x00, 0x00, 0x1C, 0xAB, 0xCD, 0x40, 0x00, 0x40, 0x11];
+        let split = InternetChecksum::new().update(&bytes[..3]).update(&bytes[3..7]).update(&bytes[7..]).finish();
+        assert_eq!(split, internet_checksum(&bytes));
+        assert_eq!(internet_checksum(&[0xFF]), 0x00FF);
+    }
+}
@@ -0,0 +1,371 @@
+//! Checksums used by record framing and protocol helpers.
+//!
+//! Every CRC is [`Crc`] with [`Algorithm`] selected at compile time, lookup table of
+//! algorithm is computed by compiler. CRC-32 is IEEE 802.3 one, same as zlib and Ethernet.
+//! ```
+//! use const_writer::crc::{self, algorithm, Crc};
+//!
+//! assert_eq!(crc::crc32(b"123456789"), 0xCBF43926);
+//! assert_eq!(crc::crc16_mcrf4xx(b"123456789"), 0x6F91);
+//! assert_eq!(crc::crc16_modbus(b"123456789"), 0x4B37);
+//! assert_eq!(crc::crc7_mmc(b"123456789"), 0x75);
+//! assert_eq!(Crc::<algorithm::Crc8Maxim>::checksum(b"123456789"), 0xA1);
+//! ```
+//!
+//! [`ConstWriter::with_crc`] computes checksum of everything written and appends it:
+#![cfg_attr(nightly, doc = "```")]
+#![cfg_attr(not(nightly), doc = "```ignore")]
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::crc::algorithm::Crc16Modbus;
+//!
+//! let mut vec = vec![];
+//! vec.const_writer::<8>()
+//!     .with_crc::<Crc16Modbus>()
+//!     .write_slice(&[0x11, 0x06, 0, 1, 0, 3])
+//!     .finish_crc_le();
+//! assert_eq!(vec[6..], [0x9A, 0x9B]);
+//! ```
+use core::marker::PhantomData;
+
+/// CRC parameters in Rocksoft model, input and output are either both reflected or not
+pub trait Algorithm {
+    /// Smallest integer which holds checksum
+    type Value: Value;
+    /// Width in bits, 1 to 64
+    const WIDTH: u32;
+    /// Polynomial without top bit, not reflected
+    const POLY: u64;
+    /// Initial register value, not reflected
+    const INIT: u64;
+    const REFLECTED: bool;
+    const XOR_OUT: u64;
+    /// Number of bytes checksum takes in output
+    const BYTES: usize = (Self::WIDTH as usize).div_ceil(8);
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Integer type of checksum
+pub trait Value: sealed::Sealed + Copy + Eq + core::fmt::Debug {
+    #[doc(hidden)]
+    fn from_u64(value: u64) -> Self;
+}
+
+macro_rules! implement_value {
+    ($type:ty) => {
+        impl sealed::Sealed for $type {}
+
+        impl Value for $type {
+            fn from_u64(value: u64) -> Self {
+                value as $type
+            }
+        }
+    }
+}
+
+implement_value!(u8);
+implement_value!(u16);
+implement_value!(u32);
+implement_value!(u64);
+
+/// Predefined algorithms, names follow [catalogue](https://reveng.sourceforge.io/crc-catalogue/all.htm)
+pub mod algorithm {
+    use super::Algorithm;
+
+    macro_rules! algorithm {
+        ($(#[$attr:meta])* $name:ident, $value:ty, $width:expr, $poly:expr, $init:expr, $reflected:expr, $xor_out:expr) => {
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy)]
+            pub enum $name {}
+
+            impl Algorithm for $name {
+                type Value = $value;
+                const WIDTH: u32 = $width;
+                const POLY: u64 = $poly;
+                const INIT: u64 = $init;
+                const REFLECTED: bool = $reflected;
+                const XOR_OUT: u64 = $xor_out;
+            }
+        }
+    }
+
+    algorithm!(
+        /// CRC-32/ISO-HDLC, used by zlib, Ethernet and PNG
+        Crc32, u32, 32, 0x04C11DB7, 0xFFFFFFFF, true, 0xFFFFFFFF
+    );
+    algorithm!(
+        /// CRC-16/MCRF4XX, which MAVLink calls CRC-X25
+        Crc16Mcrf4xx, u16, 16, 0x1021, 0xFFFF, true, 0
+    );
+    algorithm!(
+        /// CRC-16/MODBUS
+        Crc16Modbus, u16, 16, 0x8005, 0xFFFF, true, 0
+    );
+    algorithm!(
+        /// CRC-16/IBM-3740, often called CRC-16/CCITT-FALSE
+        Crc16CcittFalse, u16, 16, 0x1021, 0xFFFF, false, 0
+    );
+    algorithm!(
+        /// CRC-16/KERMIT, true CCITT one
+        Crc16Kermit, u16, 16, 0x1021, 0, true, 0
+    );
+    algorithm!(
+        /// CRC-8/SMBUS, used by SMBus packet error checking
+        Crc8Smbus, u8, 8, 0x07, 0, false, 0
+    );
+    algorithm!(
+        /// CRC-8/MAXIM-DOW, used by 1-Wire devices
+        Crc8Maxim, u8, 8, 0x31, 0, true, 0
+    );
+    algorithm!(
+        /// CRC-7/MMC, used by SD/MMC commands
+        Crc7Mmc, u8, 7, 0x09, 0, false, 0
+    );
+}
+
+const fn reflect(value: u64, width: u32) -> u64 {
+    value.reverse_bits() >> (64 - width)
+}
+
+/// Reflected table works on register aligned to low bits, normal one on register aligned to high bits
+const fn table(width: u32, poly: u64, reflected: bool) -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc;
+        let mut bit = 0;
+        if reflected {
+            let poly = reflect(poly, width);
+            crc = i as u64;
+            while bit < 8 {
+                crc = if crc & 1 == 1 { (crc >> 1) ^ poly } else { crc >> 1 };
+                bit += 1;
+            }
+        } else {
+            let poly = poly << (64 - width);
+            crc = (i as u64) << 56;
+            while bit < 8 {
+                crc = if crc >> 63 == 1 { (crc << 1) ^ poly } else { crc << 1 };
+                bit += 1;
+            }
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+struct Table<A>(PhantomData<A>);
+
+impl<A: Algorithm> Table<A> {
+    const TABLE: [u64; 256] = table(A::WIDTH, A::POLY, A::REFLECTED);
+}
+
+/// Incremental CRC of algorithm `A`
+pub struct Crc<A: Algorithm> {
+    state: u64,
+    algorithm: PhantomData<A>,
+}
+
+impl<A: Algorithm> Clone for Crc<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Algorithm> Copy for Crc<A> {}
+
+impl<A: Algorithm> core::fmt::Debug for Crc<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Crc")
+            .field("algorithm", &core::any::type_name::<A>())
+            .field("value", &self.value())
+            .finish()
+    }
+}
+
+/// Incremental CRC-32
+pub type Crc32 = Crc<algorithm::Crc32>;
+/// Incremental CRC-16/MCRF4XX
+pub type CrcX25 = Crc<algorithm::Crc16Mcrf4xx>;
+/// Incremental CRC-16/MODBUS
+pub type CrcModbus = Crc<algorithm::Crc16Modbus>;
+
+impl<A: Algorithm> Crc<A> {
+    pub const fn new() -> Self {
+        Self {
+            state: if A::REFLECTED { reflect(A::INIT, A::WIDTH) } else { A::INIT << (64 - A::WIDTH) },
+            algorithm: PhantomData,
+        }
+    }
+
+    /// Adds `bytes` to checksum
+    pub const fn update(mut self, bytes: &[u8]) -> Self {
+        let mut i = 0;
+        while i < bytes.len() {
+            self.state = if A::REFLECTED {
+                Table::<A>::TABLE[((self.state ^ bytes[i] as u64) & 0xFF) as usize] ^ (self.state >> 8)
+            } else {
+                Table::<A>::TABLE[((self.state >> 56) ^ bytes[i] as u64) as usize] ^ (self.state << 8)
+            };
+            i += 1;
+        }
+        self
+    }
+
+    /// Checksum of all added bytes widened to `u64`, usable in const context
+    pub const fn value(&self) -> u64 {
+        let crc = if A::REFLECTED { self.state } else { self.state >> (64 - A::WIDTH) };
+        crc ^ A::XOR_OUT
+    }
+
+    /// Checksum of all added bytes
+    pub fn finish(self) -> A::Value {
+        A::Value::from_u64(self.value())
+    }
+
+    /// Checksum of `bytes`
+    pub fn checksum(bytes: &[u8]) -> A::Value {
+        Self::new().update(bytes).finish()
+    }
+}
+
+impl<A: Algorithm> Default for Crc<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CRC-32 of `bytes`
+pub const fn crc32(bytes: &[u8]) -> u32 {
+    Crc32::new().update(bytes).value() as u32
+}
+
+/// CRC-16/MCRF4XX of `bytes`
+pub const fn crc16_mcrf4xx(bytes: &[u8]) -> u16 {
+    CrcX25::new().update(bytes).value() as u16
+}
+
+/// CRC-16/MODBUS of `bytes`
+pub const fn crc16_modbus(bytes: &[u8]) -> u16 {
+    CrcModbus::new().update(bytes).value() as u16
+}
+
+/// CRC-7/MMC of `bytes`, used by SD/MMC commands
+pub const fn crc7_mmc(bytes: &[u8]) -> u8 {
+    Crc::<algorithm::Crc7Mmc>::new().update(bytes).value() as u8
+}
+
+#[cfg(nightly)]
+pub use self::adapter::CrcAdapter;
+
+#[cfg(nightly)]
+mod adapter {
+    use super::{Algorithm, Crc};
+    use crate::{ConstWriter, ConstWriterAdapter};
+
+    /// Wrapper which computes CRC of bytes written through it
+    pub struct CrcAdapter<T: ConstWriterAdapter, A: Algorithm> {
+        inner: T,
+        crc: Crc<A>,
+    }
+
+    impl<T: ConstWriterAdapter, A: Algorithm> crate::sealed::Sealed for CrcAdapter<T, A> {}
+
+    impl<T: ConstWriterAdapter, A: Algorithm> core::fmt::Debug for CrcAdapter<T, A> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            crate::debug_adapter(self, f)
+        }
+    }
+
+    impl<T: ConstWriterAdapter, A: Algorithm> ConstWriterAdapter for CrcAdapter<T, A> {
+        fn written_so_far(&self) -> usize {
+            self.inner.written_so_far()
+        }
+
+        unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+            self.crc = self.crc.update(value);
+            self.inner = self.inner.write_raw(value);
+            self
+        }
+
+        #[track_caller]
+        unsafe fn grow<const M: usize>(mut self) -> Self {
+            self.inner = self.inner.grow::<M>();
+            self
+        }
+
+        #[cfg(all(feature = "debug-poison", debug_assertions))]
+        unsafe fn poison<const N: usize>(mut self) -> Self {
+            self.inner = self.inner.poison::<N>();
+            self
+        }
+    }
+
+    impl<T: crate::GrowableAdapter, A: Algorithm> crate::GrowableAdapter for CrcAdapter<T, A> {}
+
+    impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+        /// Wraps adapter into [`CrcAdapter`], which computes CRC of algorithm `A` over following writes
+        pub fn with_crc<A: Algorithm>(self) -> ConstWriter<CrcAdapter<T, A>, {N}> {
+            ConstWriter {
+                writer_adapter: CrcAdapter {
+                    inner: self.writer_adapter,
+                    crc: Crc::new(),
+                },
+            }
+        }
+    }
+
+    impl<T: ConstWriterAdapter, A: Algorithm, const N: usize> ConstWriter<CrcAdapter<T, A>, {N}> {
+        /// Checksum of bytes written since [`ConstWriter::with_crc`]
+        pub fn crc(&self) -> A::Value {
+            self.writer_adapter.crc.finish()
+        }
+
+        /// Appends little endian checksum and unwraps adapter
+        pub fn finish_crc_le(self) -> ConstWriter<T, {N - A::BYTES}> where [(); A::BYTES]: {
+            let value = self.writer_adapter.crc.value().to_le_bytes();
+            let mut bytes = [0u8; A::BYTES];
+            bytes.copy_from_slice(&value[..A::BYTES]);
+            unsafe {
+                ConstWriter {
+                    writer_adapter: self.writer_adapter.inner.write(&bytes),
+                }
+            }
+        }
+
+        /// Appends big endian checksum and unwraps adapter
+        pub fn finish_crc_be(self) -> ConstWriter<T, {N - A::BYTES}> where [(); A::BYTES]: {
+            let value = self.writer_adapter.crc.value().to_be_bytes();
+            let mut bytes = [0u8; A::BYTES];
+            bytes.copy_from_slice(&value[8 - A::BYTES..]);
+            unsafe {
+                ConstWriter {
+                    writer_adapter: self.writer_adapter.inner.write(&bytes),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{algorithm, crc32, Crc, Crc32};
+
+    #[test]
+    fn crc32_incremental() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(Crc32::new().update(b"1234").update(b"56789").finish(), 0xCBF43926);
+    }
+
+    #[test]
+    fn crc_catalogue_check_values() {
+        assert_eq!(Crc::<algorithm::Crc16CcittFalse>::checksum(b"123456789"), 0x29B1);
+        assert_eq!(Crc::<algorithm::Crc16Kermit>::checksum(b"123456789"), 0x2189);
+        assert_eq!(Crc::<algorithm::Crc8Smbus>::checksum(b"123456789"), 0xF4);
+    }
+}
@@ -0,0 +1,157 @@
+//! Partition table entries for tools which craft disk images.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::disk::{gpt, mbr};
+//!
+//! // protective MBR entry
+//! let mut vec = vec![];
+//! mbr::write_entry(vec.const_writer::<16>(), &mbr::Entry { bootable: false, partition_type: 0xEE, first_lba: 1, sectors: u32::MAX });
+//! assert_eq!(vec, [0, 0, 2, 0, 0xEE, 0xFE, 0xFF, 0xFF, 1, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF]);
+//!
+//! let entry = gpt::PartitionEntry {
+//!     type_guid: gpt::Guid::EFI_SYSTEM,
+//!     unique_guid: gpt::Guid::new(0x01234567, 0x89AB, 0xCDEF, [0, 1, 2, 3, 4, 5, 6, 7]),
+//!     first_lba: 2048,
+//!     last_lba: 206847,
+//!     attributes: 0,
+//!     name: "EFI",
+//! };
+//! let mut vec = vec![];
+//! gpt::write_partition_entry(vec.const_writer::<128>(), &entry);
+//! assert_eq!(vec[..4], [0x28, 0x73, 0x2A, 0xC1]);
+//! assert_eq!(vec[16..24], [0x67, 0x45, 0x23, 0x01, 0xAB, 0x89, 0xEF, 0xCD]);
+//! assert_eq!(vec[56..62], [b'E', 0, b'F', 0, b'I', 0]);
+//! ```
+
+/// [GUID partition table](https://uefi.org/specs/UEFI/2.10/05_GUID_Partition_Table_Format.html) entries.
+pub mod gpt {
+    use crate::{ConstWriter, ConstWriterAdapter};
+
+    /// Length of partition name in UTF-16 code units
+    pub const NAME_LEN: usize = 36;
+
+    /// GUID, stored with first three fields little endian
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct Guid {
+        pub data1: u32,
+        pub data2: u16,
+        pub data3: u16,
+        pub data4: [u8; 8],
+    }
+
+    impl Guid {
+        /// EFI system partition, `C12A7328-F81F-11D2-BA4B-00A0C93EC93B`
+        pub const EFI_SYSTEM: Guid = Guid::new(0xC12A7328, 0xF81F, 0x11D2, [0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B]);
+        /// Linux filesystem data, `0FC63DAF-8483-4772-8E79-3D69D8477DE4`
+        pub const LINUX_FILESYSTEM: Guid = Guid::new(0x0FC63DAF, 0x8483, 0x4772, [0x8E, 0x79, 0x3D, 0x69, 0xD8, 0x47, 0x7D, 0xE4]);
+
+        pub const fn new(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Self {
+            Self { data1, data2, data3, data4 }
+        }
+
+        /// On disk representation
+        pub fn to_bytes(&self) -> [u8; 16] {
+            let mut bytes = [0u8; 16];
+            bytes[..4].copy_from_slice(&self.data1.to_le_bytes());
+            bytes[4..6].copy_from_slice(&self.data2.to_le_bytes());
+            bytes[6..8].copy_from_slice(&self.data3.to_le_bytes());
+            bytes[8..].copy_from_slice(&self.data4);
+            bytes
+        }
+    }
+
+    /// Fields of partition entry
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct PartitionEntry<'a> {
+        pub type_guid: Guid,
+        pub unique_guid: Guid,
+        pub first_lba: u64,
+        /// inclusive
+        pub last_lba: u64,
+        pub attributes: u64,
+        /// up to [`NAME_LEN`] UTF-16 code units, rest is zero filled
+        pub name: &'a str,
+    }
+
+    /// Writes 128 byte partition entry
+    ///
+    /// # Panics
+    /// If name is longer than [`NAME_LEN`] UTF-16 code units
+    #[track_caller]
+    pub fn write_partition_entry<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, entry: &PartitionEntry<'_>) -> ConstWriter<T, {N - 128}> {
+        let mut name = [0u8; 2 * NAME_LEN];
+        for (i, unit) in entry.name.encode_utf16().enumerate() {
+            assert!(i < NAME_LEN, "partition name too long: {:?}", entry.name);
+            name[2 * i..2 * i + 2].copy_from_slice(&unit.to_le_bytes());
+        }
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter
+                    .write(&entry.type_guid.to_bytes())
+                    .write(&entry.unique_guid.to_bytes())
+                    .write(&entry.first_lba.to_le_bytes())
+                    .write(&entry.last_lba.to_le_bytes())
+                    .write(&entry.attributes.to_le_bytes())
+                    .write(&name),
+            }
+        }
+    }
+}
+
+/// Master boot record partition entries.
+pub mod mbr {
+    use crate::{ConstWriter, ConstWriterAdapter};
+
+    /// Fields of partition entry, CHS addresses are derived from LBA
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Entry {
+        pub bootable: bool,
+        pub partition_type: u8,
+        pub first_lba: u32,
+        pub sectors: u32,
+    }
+
+    /// CHS address for 255 heads and 63 sectors per track, saturated to max value
+    fn chs(lba: u32) -> [u8; 3] {
+        let cylinder = lba / (255 * 63);
+        if cylinder > 1023 {
+            return [0xFE, 0xFF, 0xFF];
+        }
+        let head = (lba / 63) % 255;
+        let sector = lba % 63 + 1;
+        [head as u8, sector as u8 | ((cylinder >> 2) & 0xC0) as u8, cylinder as u8]
+    }
+
+    /// Writes 16 byte partition entry
+    pub fn write_entry<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, entry: &Entry) -> ConstWriter<T, {N - 16}> {
+        let first = chs(entry.first_lba);
+        let last = chs(entry.first_lba.saturating_add(entry.sectors.saturating_sub(1)));
+        let status = if entry.bootable { 0x80 } else { 0 };
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter
+                    .write(&[status, first[0], first[1], first[2], entry.partition_type, last[0], last[1], last[2]])
+                    .write(&entry.first_lba.to_le_bytes())
+                    .write(&entry.sectors.to_le_bytes()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mbr;
+    use crate::ConstWrite;
+
+    #[test]
+    fn mbr_entry_chs() {
+        let entry = mbr::Entry { bootable: true, partition_type: 0x83, first_lba: 2048, sectors: 2048 };
+        let mut buff = [0u8; 16];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        mbr::write_entry(ref_buff.const_writer::<16>(), &entry);
+        assert_eq!(buff[..8], [0x80, 32, 33, 0, 0x83, 65, 1, 0]);
+    }
+}
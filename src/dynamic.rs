@@ -0,0 +1,139 @@
+//! Object-safe mirror of [`ConstWriter`] with runtime length checks.
+//!
+//! Lets code which can't be generic, e.g. plugins behind `dyn` trait, write to writer
+//! reserved by caller. Core code keeps const checked writes and degrades writer with
+//! [`ConstWriter::into_dyn`] only where needed.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//! use const_writer::dynamic::DynConstWrite;
+//!
+//! fn plugin(writer: &mut dyn DynConstWrite) {
+//!     writer.write_u16_le(2);
+//!     writer.write_slice(&[3, 4]);
+//! }
+//!
+//! let mut vec = vec![];
+//! let mut writer = vec.const_writer::<6>().write_u16_le(1).into_dyn();
+//! plugin(&mut writer);
+//! assert_eq!(writer.remaining(), 0);
+//! drop(writer);
+//! assert_eq!(vec, [1, 0, 2, 0, 3, 4]);
+//! ```
+use core::convert::TryFrom;
+use crate::{ConstWriter, ConstWriterAdapter};
+
+macro_rules! implement_write {
+    ($name:ident, $type:ty, $endian:ident) => {
+        #[track_caller]
+        fn $name(&mut self, value: $type) {
+            self.write_slice(&value.$endian())
+        }
+    }
+}
+
+/// Writer with runtime checked length, can be used as trait object
+pub trait DynConstWrite {
+    /// Number of bytes left
+    fn remaining(&self) -> usize;
+
+    /// Writes `value`
+    ///
+    /// # Panics
+    /// If `value` is longer than [`remaining`](DynConstWrite::remaining)
+    fn write_slice(&mut self, value: &[u8]);
+
+    implement_write!(write_u8_le, u8, to_le_bytes);
+    implement_write!(write_u16_le, u16, to_le_bytes);
+    implement_write!(write_u32_le, u32, to_le_bytes);
+    implement_write!(write_u64_le, u64, to_le_bytes);
+    implement_write!(write_u128_le, u128, to_le_bytes);
+
+    implement_write!(write_i8_le, i8, to_le_bytes);
+    implement_write!(write_i16_le, i16, to_le_bytes);
+    implement_write!(write_i32_le, i32, to_le_bytes);
+    implement_write!(write_i64_le, i64, to_le_bytes);
+    implement_write!(write_i128_le, i128, to_le_bytes);
+
+    implement_write!(write_u8_be, u8, to_be_bytes);
+    implement_write!(write_u16_be, u16, to_be_bytes);
+    implement_write!(write_u32_be, u32, to_be_bytes);
+    implement_write!(write_u64_be, u64, to_be_bytes);
+    implement_write!(write_u128_be, u128, to_be_bytes);
+
+    implement_write!(write_i8_be, i8, to_be_bytes);
+    implement_write!(write_i16_be, i16, to_be_bytes);
+    implement_write!(write_i32_be, i32, to_be_bytes);
+    implement_write!(write_i64_be, i64, to_be_bytes);
+    implement_write!(write_i128_be, i128, to_be_bytes);
+
+    implement_write!(write_f32_le, f32, to_le_bytes);
+    implement_write!(write_f64_le, f64, to_le_bytes);
+    implement_write!(write_f32_be, f32, to_be_bytes);
+    implement_write!(write_f64_be, f64, to_be_bytes);
+}
+
+/// [`ConstWriter`] degraded to runtime checks, adapter is dropped with wrapper
+pub struct DynWriter<T: ConstWriterAdapter> {
+    /// `None` only if write panicked in the middle
+    writer_adapter: Option<T>,
+    remaining: usize,
+}
+
+impl<T: ConstWriterAdapter> DynConstWrite for DynWriter<T> {
+    fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    #[track_caller]
+    fn write_slice(&mut self, value: &[u8]) {
+        assert!(
+            value.len() <= self.remaining,
+            "dyn writer too short: {} < {}",
+            self.remaining,
+            value.len()
+        );
+        let mut writer_adapter = match self.writer_adapter.take() {
+            Some(writer_adapter) => writer_adapter,
+            None => return,
+        };
+        let mut chunks = value.chunks_exact(8);
+        for chunk in &mut chunks {
+            let chunk = <&[u8; 8]>::try_from(chunk).unwrap();
+            writer_adapter = unsafe { writer_adapter.write(chunk) };
+        }
+        for byte in chunks.remainder() {
+            writer_adapter = unsafe { writer_adapter.write(&[*byte]) };
+        }
+        self.writer_adapter = Some(writer_adapter);
+        self.remaining -= value.len();
+    }
+}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Converts writer to [`DynWriter`] with `N` bytes remaining
+    pub fn into_dyn(self) -> DynWriter<T> {
+        DynWriter {
+            writer_adapter: Some(self.writer_adapter),
+            remaining: N,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynConstWrite;
+    use crate::ConstWrite;
+
+    #[test]
+    #[should_panic(expected = "dyn writer too short: 3 < 4")]
+    fn dyn_write_too_short() {
+        let mut buff = [0u8; 12];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let mut writer = ref_buff.const_writer::<12>().into_dyn();
+        let dyn_writer: &mut dyn DynConstWrite = &mut writer;
+        dyn_writer.write_slice(&[1; 9]);
+        assert_eq!(dyn_writer.remaining(), 3);
+        dyn_writer.write_u32_be(0);
+    }
+}
@@ -0,0 +1,219 @@
+//! ELF program and section headers, 32 and 64 bit, little or big endian.
+//!
+//! Headers are described with 64 bit fields, 32 bit writers check that values fit.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::elf::{self, Endian, ProgramHeader};
+//!
+//! let load = ProgramHeader {
+//!     p_type: elf::PT_LOAD,
+//!     flags: elf::PF_R | elf::PF_X,
+//!     offset: 0,
+//!     vaddr: 0x400000,
+//!     paddr: 0x400000,
+//!     filesz: 0x1000,
+//!     memsz: 0x1000,
+//!     align: 0x1000,
+//! };
+//! let mut vec = vec![];
+//! elf::write_program_header64(vec.const_writer::<56>(), &load, Endian::Little);
+//! assert_eq!(vec[..8], [1, 0, 0, 0, 5, 0, 0, 0]);
+//! assert_eq!(vec[16..20], [0, 0, 0x40, 0]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+pub const PT_INTERP: u32 = 3;
+pub const PT_NOTE: u32 = 4;
+pub const PT_PHDR: u32 = 6;
+
+pub const PF_X: u32 = 1;
+pub const PF_W: u32 = 2;
+pub const PF_R: u32 = 4;
+
+pub const SHT_PROGBITS: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_NOBITS: u32 = 8;
+
+/// Byte order of fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Program header fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProgramHeader {
+    pub p_type: u32,
+    pub flags: u32,
+    pub offset: u64,
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub filesz: u64,
+    pub memsz: u64,
+    pub align: u64,
+}
+
+/// Section header fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SectionHeader {
+    /// offset of name in section name string table
+    pub name: u32,
+    pub sh_type: u32,
+    pub flags: u64,
+    pub addr: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub info: u32,
+    pub addralign: u64,
+    pub entsize: u64,
+}
+
+/// Fills header of `L` bytes field by field
+struct Fields<const L: usize> {
+    bytes: [u8; L],
+    position: usize,
+    endian: Endian,
+}
+
+impl<const L: usize> Fields<L> {
+    fn new(endian: Endian) -> Self {
+        Self {
+            bytes: [0; L],
+            position: 0,
+            endian,
+        }
+    }
+
+    fn put(&mut self, le: &[u8], be: &[u8]) {
+        let value = match self.endian {
+            Endian::Little => le,
+            Endian::Big => be,
+        };
+        self.bytes[self.position..self.position + value.len()].copy_from_slice(value);
+        self.position += value.len();
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.put(&value.to_le_bytes(), &value.to_be_bytes())
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.put(&value.to_le_bytes(), &value.to_be_bytes())
+    }
+
+    #[track_caller]
+    fn word32(&mut self, value: u64, field: &str) {
+        assert!(value <= u32::MAX as u64, "ELF32 {} doesn't fit in u32: {:#x}", field, value);
+        self.u32(value as u32)
+    }
+
+    fn write<T: ConstWriterAdapter, const N: usize>(self, writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - L}> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&self.bytes),
+            }
+        }
+    }
+}
+
+/// Writes 56 byte `Elf64_Phdr`
+pub fn write_program_header64<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, header: &ProgramHeader, endian: Endian) -> ConstWriter<T, {N - 56}> {
+    let mut fields = Fields::<56>::new(endian);
+    fields.u32(header.p_type);
+    fields.u32(header.flags);
+    fields.u64(header.offset);
+    fields.u64(header.vaddr);
+    fields.u64(header.paddr);
+    fields.u64(header.filesz);
+    fields.u64(header.memsz);
+    fields.u64(header.align);
+    fields.write(writer)
+}
+
+/// Writes 32 byte `Elf32_Phdr`
+///
+/// # Panics
+/// If address or size field doesn't fit in `u32`
+#[track_caller]
+pub fn write_program_header32<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, header: &ProgramHeader, endian: Endian) -> ConstWriter<T, {N - 32}> {
+    let mut fields = Fields::<32>::new(endian);
+    fields.u32(header.p_type);
+    fields.word32(header.offset, "offset");
+    fields.word32(header.vaddr, "vaddr");
+    fields.word32(header.paddr, "paddr");
+    fields.word32(header.filesz, "filesz");
+    fields.word32(header.memsz, "memsz");
+    fields.u32(header.flags);
+    fields.word32(header.align, "align");
+    fields.write(writer)
+}
+
+/// Writes 64 byte `Elf64_Shdr`
+pub fn write_section_header64<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, header: &SectionHeader, endian: Endian) -> ConstWriter<T, {N - 64}> {
+    let mut fields = Fields::<64>::new(endian);
+    fields.u32(header.name);
+    fields.u32(header.sh_type);
+    fields.u64(header.flags);
+    fields.u64(header.addr);
+    fields.u64(header.offset);
+    fields.u64(header.size);
+    fields.u32(header.link);
+    fields.u32(header.info);
+    fields.u64(header.addralign);
+    fields.u64(header.entsize);
+    fields.write(writer)
+}
+
+/// Writes 40 byte `Elf32_Shdr`
+///
+/// # Panics
+/// If address or size field doesn't fit in `u32`
+#[track_caller]
+pub fn write_section_header32<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, header: &SectionHeader, endian: Endian) -> ConstWriter<T, {N - 40}> {
+    let mut fields = Fields::<40>::new(endian);
+    fields.u32(header.name);
+    fields.u32(header.sh_type);
+    fields.word32(header.flags, "flags");
+    fields.word32(header.addr, "addr");
+    fields.word32(header.offset, "offset");
+    fields.word32(header.size, "size");
+    fields.u32(header.link);
+    fields.u32(header.info);
+    fields.word32(header.addralign, "addralign");
+    fields.word32(header.entsize, "entsize");
+    fields.write(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Endian, SectionHeader};
+    use crate::ConstWrite;
+
+    #[test]
+    fn elf_section_header32_be() {
+        let header = SectionHeader { name: 1, sh_type: super::SHT_STRTAB, size: 0x20, addralign: 1, ..Default::default() };
+        let mut buff = [0u8; 40];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_section_header32(ref_buff.const_writer::<40>(), &header, Endian::Big);
+        assert_eq!(buff[..8], [0, 0, 0, 1, 0, 0, 0, 3]);
+        assert_eq!(buff[20..24], [0, 0, 0, 0x20]);
+        assert_eq!(buff[32..36], [0, 0, 0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ELF32 vaddr doesn't fit in u32: 0x100000000")]
+    fn elf_program_header32_overflow() {
+        let header = super::ProgramHeader { vaddr: 1 << 32, ..Default::default() };
+        let mut buff = [0u8; 32];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_program_header32(ref_buff.const_writer::<32>(), &header, Endian::Little);
+    }
+}
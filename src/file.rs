@@ -0,0 +1,165 @@
+//! Record writer which builds fixed size records with [`ConstWriter`]
+//! and appends them to file in batches.
+//!
+//! ```
+//! use const_writer::file::FileRecordWriter;
+//!
+//! let mut out = FileRecordWriter::new(Vec::new(), 4096);
+//! for i in 0..3u32 {
+//!     out.record::<8>().unwrap()
+//!         .write_u32_le(i)
+//!         .write_u32_le(i * 2);
+//! }
+//! let out = out.into_inner().unwrap();
+//! assert_eq!(out.len(), 24);
+//! ```
+//!
+//! Records left in buffer are flushed on drop, like with [`std::io::BufWriter`], but errors
+//! are ignored then, so call [`FileRecordWriter::flush`] to handle them.
+use crate::ConstWriter;
+use crate::vec::VecWriterAdapter;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::fs::File;
+use std::io::{self, Write};
+
+/// Allocator of batch buffer, aligns it to block size in direct IO mode
+#[derive(Debug, Clone, Copy)]
+pub struct BlockAlloc {
+    block: usize,
+}
+
+impl BlockAlloc {
+    fn layout(&self, layout: Layout) -> Result<Layout, AllocError> {
+        layout.align_to(self.block).map_err(|_| AllocError)
+    }
+}
+
+unsafe impl Allocator for BlockAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Global.allocate(self.layout(layout)?)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // same layout was valid in `allocate`
+        Global.deallocate(ptr, self.layout(layout).unwrap_unchecked())
+    }
+}
+
+pub struct FileRecordWriter<W: Write = File> {
+    inner: W,
+    buff: Vec<u8, BlockAlloc>,
+    batch_size: usize,
+    block: usize,
+}
+
+impl<W: Write> FileRecordWriter<W> {
+    /// Creates writer which flushes records to `inner` after `batch_size` bytes accumulated
+    pub fn new(inner: W, batch_size: usize) -> Self {
+        Self::with_block(inner, batch_size, 1)
+    }
+
+    /// Creates writer for file opened with `O_DIRECT`. Batch buffer is aligned to `block` bytes
+    /// and every flushed batch is padded with zeros to multiple of `block`, so writes keep
+    /// alignment required by direct IO. Readers have to skip padding.
+    ///
+    /// # Panics
+    /// If `block` isn't power of two
+    #[track_caller]
+    pub fn with_direct_io(inner: W, batch_size: usize, block: usize) -> Self {
+        assert!(block.is_power_of_two(), "block size isn't power of two: {}", block);
+        Self::with_block(inner, batch_size, block)
+    }
+
+    fn with_block(inner: W, batch_size: usize, block: usize) -> Self {
+        Self {
+            inner,
+            buff: Vec::with_capacity_in(batch_size, BlockAlloc { block }),
+            batch_size,
+            block,
+        }
+    }
+
+    /// Get [`ConstWriter`] for next `N` byte record.
+    ///
+    /// Flushes accumulated records first if batch is full.
+    pub fn record<const N: usize>(&mut self) -> io::Result<ConstWriter<VecWriterAdapter<'_, BlockAlloc>, N>> {
+        if self.buff.len() >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(crate::ConstWrite::const_writer::<N>(&mut self.buff))
+    }
+
+    /// Inner writer, records still in buffer aren't written to it
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Number of bytes waiting to be written
+    pub fn pending(&self) -> usize {
+        self.buff.len()
+    }
+
+    /// Writes accumulated records to inner writer, padded to block size in direct IO mode
+    pub fn flush(&mut self) -> io::Result<()> {
+        let len = self.buff.len().next_multiple_of(self.block);
+        self.buff.resize(len, 0);
+        self.inner.write_all(&self.buff)?;
+        self.buff.clear();
+        self.inner.flush()
+    }
+
+    /// Flushes accumulated records and returns inner writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.flush()?;
+        let this = ManuallyDrop::new(self);
+        // fields are read once and drop of writer is skipped
+        unsafe {
+            drop(core::ptr::read(&this.buff));
+            Ok(core::ptr::read(&this.inner))
+        }
+    }
+}
+
+impl<W: Write> Drop for FileRecordWriter<W> {
+    fn drop(&mut self) {
+        // flush could panic again if inner writer panicked
+        if !std::thread::panicking() {
+            let _ = self.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileRecordWriter;
+
+    #[test]
+    fn file_records_batched() {
+        let mut out = FileRecordWriter::new(Vec::new(), 8);
+        out.record::<6>().unwrap().write_u16_le(1).write_u32_le(2);
+        out.record::<4>().unwrap().write_u32_be(3);
+        assert_eq!(out.pending(), 10);
+        assert!(out.inner.is_empty());
+
+        out.record::<1>().unwrap().write_u8_le(4);
+        assert_eq!(out.pending(), 1);
+        assert_eq!(out.inner, [1, 0, 2, 0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(out.into_inner().unwrap().len(), 11);
+    }
+
+    #[test]
+    fn file_direct_io_flushed_on_drop() {
+        let mut file = Vec::new();
+        {
+            let mut out = FileRecordWriter::with_direct_io(&mut file, 16, 8);
+            out.record::<12>().unwrap().write_u64_le(1).write_u32_le(2);
+            assert_eq!(out.buff.as_ptr() as usize % 8, 0);
+            out.flush().unwrap();
+            out.record::<2>().unwrap().write_u16_le(3);
+        }
+        assert_eq!(file.len(), 24);
+        assert_eq!(file[12..18], [0, 0, 0, 0, 3, 0]);
+    }
+}
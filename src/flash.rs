@@ -0,0 +1,124 @@
+//! Command frames of SD/MMC cards in SPI mode and SPI NOR flash chips.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::flash::{sd, spi_nor};
+//!
+//! let mut buff = [0u8; 6];
+//! sd::write_command(buff.const_writer::<6>(), sd::GO_IDLE_STATE, 0);
+//! assert_eq!(buff, [0x40, 0, 0, 0, 0, 0x95]);
+//!
+//! let mut buff = [0u8; 9];
+//! let writer = spi_nor::write_command(buff.const_writer::<9>(), spi_nor::WRITE_ENABLE);
+//! spi_nor::write_command_addr3(writer, spi_nor::PAGE_PROGRAM, 0x012345)
+//!     .write_u32_be(0xDEADBEEF);
+//! assert_eq!(buff, [0x06, 0x02, 0x01, 0x23, 0x45, 0xDE, 0xAD, 0xBE, 0xEF]);
+//! ```
+
+/// SD/MMC commands in SPI mode
+pub mod sd {
+    use crate::{ConstWriter, ConstWriterAdapter};
+    use crate::crc::crc7_mmc;
+
+    pub const GO_IDLE_STATE: u8 = 0;
+    pub const SEND_IF_COND: u8 = 8;
+    pub const SEND_CSD: u8 = 9;
+    pub const STOP_TRANSMISSION: u8 = 12;
+    pub const SET_BLOCKLEN: u8 = 16;
+    pub const READ_SINGLE_BLOCK: u8 = 17;
+    pub const READ_MULTIPLE_BLOCK: u8 = 18;
+    pub const WRITE_BLOCK: u8 = 24;
+    pub const WRITE_MULTIPLE_BLOCK: u8 = 25;
+    pub const APP_CMD: u8 = 55;
+    pub const READ_OCR: u8 = 58;
+    /// sent after [`APP_CMD`]
+    pub const SD_SEND_OP_COND: u8 = 41;
+
+    /// Writes 6 byte command frame with CRC7, only low 6 bits of `command` are used
+    pub fn write_command<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, command: u8, argument: u32) -> ConstWriter<T, {N - 6}> {
+        let argument = argument.to_be_bytes();
+        let mut frame = [0x40 | (command & 0x3F), argument[0], argument[1], argument[2], argument[3], 0];
+        frame[5] = crc7_mmc(&frame[..5]) << 1 | 1;
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&frame),
+            }
+        }
+    }
+}
+
+/// Common SPI NOR flash commands
+pub mod spi_nor {
+    use crate::{ConstWriter, ConstWriterAdapter};
+
+    pub const WRITE_ENABLE: u8 = 0x06;
+    pub const WRITE_DISABLE: u8 = 0x04;
+    pub const READ_STATUS: u8 = 0x05;
+    pub const READ_JEDEC_ID: u8 = 0x9F;
+    pub const READ: u8 = 0x03;
+    pub const FAST_READ: u8 = 0x0B;
+    pub const PAGE_PROGRAM: u8 = 0x02;
+    /// 4 KiB sector erase
+    pub const SECTOR_ERASE: u8 = 0x20;
+    /// 64 KiB block erase
+    pub const BLOCK_ERASE: u8 = 0xD8;
+    pub const CHIP_ERASE: u8 = 0xC7;
+    pub const READ_4B: u8 = 0x13;
+    pub const FAST_READ_4B: u8 = 0x0C;
+    pub const PAGE_PROGRAM_4B: u8 = 0x12;
+    pub const SECTOR_ERASE_4B: u8 = 0x21;
+
+    /// Writes command without address
+    pub fn write_command<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, command: u8) -> ConstWriter<T, {N - 1}> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&[command]),
+            }
+        }
+    }
+
+    /// Writes command with 3 byte address, high byte of `address` is ignored
+    pub fn write_command_addr3<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, command: u8, address: u32) -> ConstWriter<T, {N - 4}> {
+        let address = address.to_be_bytes();
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&[command, address[1], address[2], address[3]]),
+            }
+        }
+    }
+
+    /// Writes command with 4 byte address
+    pub fn write_command_addr4<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, command: u8, address: u32) -> ConstWriter<T, {N - 5}> {
+        let address = address.to_be_bytes();
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&[command, address[0], address[1], address[2], address[3]]),
+            }
+        }
+    }
+
+    /// Writes fast read command with 3 byte address and dummy byte
+    pub fn write_fast_read<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, address: u32) -> ConstWriter<T, {N - 5}> {
+        let address = address.to_be_bytes();
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&[FAST_READ, address[1], address[2], address[3], 0]),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sd;
+    use crate::ConstWrite;
+
+    #[test]
+    fn sd_send_if_cond_crc() {
+        let mut buff = [0u8; 6];
+        sd::write_command(buff.const_writer::<6>(), sd::SEND_IF_COND, 0x1AA);
+        assert_eq!(buff, [0x48, 0, 0, 0x01, 0xAA, 0x87]);
+    }
+}
@@ -0,0 +1,172 @@
+//! [FlatBuffers](https://flatbuffers.dev/internals/) structs, written inline without builder.
+//!
+//! Struct fields are little endian, each aligned to its own alignment, and struct size is padded
+//! to biggest field alignment. [`flatbuffers_struct!`](crate::flatbuffers_struct) computes
+//! padding at compile time and implements [`ConstWritable`](crate::ConstWritable), so struct is
+//! written with [`write_value`](crate::ConstWriter::write_value). Offsets are relative to start
+//! of writer, which can be checked with [`aligned`](crate::aligned) adapter.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//!
+//! const_writer::flatbuffers_struct! {
+//!     // struct Vec3 { x: float; y: float; z: float; }
+//!     pub struct Vec3 {
+//!         pub x: f32,
+//!         pub y: f32,
+//!         pub z: f32,
+//!     }
+//! }
+//!
+//! const_writer::flatbuffers_struct! {
+//!     // struct Hit { kind: ubyte; position: Vec3; time: ulong; }
+//!     pub struct Hit {
+//!         pub kind: u8,
+//!         pub position: Vec3,
+//!         pub time: u64,
+//!     }
+//! }
+//!
+//! let hit = Hit { kind: 1, position: Vec3 { x: 1.0, y: 2.0, z: 3.0 }, time: 7 };
+//! let mut vec = vec![];
+//! vec.const_writer::<24>().write_value(&hit);
+//! assert_eq!(vec[..4], [1, 0, 0, 0]); // 3 bytes of padding before Vec3
+//! assert_eq!(vec[4..8], 1.0f32.to_le_bytes());
+//! assert_eq!(vec[16..], [7, 0, 0, 0, 0, 0, 0, 0]);
+//! ```
+use crate::ConstWriterAdapter;
+use crate::aligned::padding;
+
+/// Type allowed in FlatBuffers struct: scalar, fixed array or other struct
+///
+/// # Safety
+/// `write_flat` must write exactly `SIZE` bytes
+pub unsafe trait FlatStruct {
+    const SIZE: usize;
+    const ALIGN: usize;
+
+    /// # Safety
+    /// Underlying buffer must have space for `SIZE` bytes
+    unsafe fn write_flat<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T;
+}
+
+macro_rules! flat_scalar {
+    ($($type:ty),*) => {
+        $(
+            unsafe impl FlatStruct for $type {
+                const SIZE: usize = core::mem::size_of::<$type>();
+                const ALIGN: usize = core::mem::size_of::<$type>();
+
+                unsafe fn write_flat<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                    writer_adapter.write(&self.to_le_bytes())
+                }
+            }
+        )*
+    }
+}
+
+flat_scalar!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+unsafe impl FlatStruct for bool {
+    const SIZE: usize = 1;
+    const ALIGN: usize = 1;
+
+    unsafe fn write_flat<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+        writer_adapter.write(&[*self as u8])
+    }
+}
+
+unsafe impl<V: FlatStruct, const L: usize> FlatStruct for [V; L] {
+    const SIZE: usize = V::SIZE * L;
+    const ALIGN: usize = V::ALIGN;
+
+    unsafe fn write_flat<T: ConstWriterAdapter>(&self, mut writer_adapter: T) -> T {
+        for value in self {
+            writer_adapter = value.write_flat(writer_adapter);
+        }
+        writer_adapter
+    }
+}
+
+/// Size and alignment of struct with fields of `(size, align)`
+#[doc(hidden)]
+pub const fn layout(fields: &[(usize, usize)]) -> (usize, usize) {
+    let mut offset = 0;
+    let mut align = 1;
+    let mut i = 0;
+    while i < fields.len() {
+        let (field_size, field_align) = fields[i];
+        offset += padding(offset, field_align) + field_size;
+        if field_align > align {
+            align = field_align;
+        }
+        i += 1;
+    }
+    (offset + padding(offset, align), align)
+}
+
+/// Defines FlatBuffers struct with [`FlatStruct`](crate::flatbuffers::FlatStruct) and
+/// [`ConstWritable`](crate::ConstWritable) impls. See [`flatbuffers`](crate::flatbuffers) module.
+#[macro_export]
+macro_rules! flatbuffers_struct {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $type:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $type),*
+        }
+
+        unsafe impl $crate::flatbuffers::FlatStruct for $name {
+            const SIZE: usize = $crate::flatbuffers::layout(&[
+                $((<$type as $crate::flatbuffers::FlatStruct>::SIZE, <$type as $crate::flatbuffers::FlatStruct>::ALIGN)),*
+            ]).0;
+            const ALIGN: usize = $crate::flatbuffers::layout(&[
+                $((<$type as $crate::flatbuffers::FlatStruct>::SIZE, <$type as $crate::flatbuffers::FlatStruct>::ALIGN)),*
+            ]).1;
+
+            unsafe fn write_flat<T: $crate::ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                let mut offset = 0;
+                $(
+                    let padding = $crate::aligned::padding(offset, <$type as $crate::flatbuffers::FlatStruct>::ALIGN);
+                    let writer_adapter = $crate::aligned::write_padding(writer_adapter, padding);
+                    let writer_adapter = $crate::flatbuffers::FlatStruct::write_flat(&self.$field, writer_adapter);
+                    offset += padding + <$type as $crate::flatbuffers::FlatStruct>::SIZE;
+                )*
+                let padding = <Self as $crate::flatbuffers::FlatStruct>::SIZE - offset;
+                $crate::aligned::write_padding(writer_adapter, padding)
+            }
+        }
+
+        unsafe impl $crate::ConstWritable for $name {
+            const SIZE: usize = <Self as $crate::flatbuffers::FlatStruct>::SIZE;
+
+            unsafe fn write_to<T: $crate::ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                $crate::flatbuffers::FlatStruct::write_flat(self, writer_adapter)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConstWritable, ConstWrite};
+
+    crate::flatbuffers_struct! {
+        struct Tail {
+            value: u32,
+            flags: [bool; 3],
+        }
+    }
+
+    #[test]
+    fn flatbuffers_trailing_padding() {
+        assert_eq!(<Tail as ConstWritable>::SIZE, 8);
+        let mut buff = [0xFFu8; 8];
+        buff.const_writer::<8>().write_value(&Tail { value: 1, flags: [true, false, true] });
+        assert_eq!(buff, [1, 0, 0, 0, 1, 0, 1, 0]);
+    }
+}
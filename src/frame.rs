@@ -0,0 +1,147 @@
+//! Length prefixed frame, where header is written after payload.
+//!
+//! Payload is written first with regular [`ConstWriter`]s, then header is written in front
+//! of it with known payload length, so no space has to be backfilled later.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//! use const_writer::frame::FrameBuilder;
+//!
+//! let mut frame = FrameBuilder::<3, 16>::new();
+//! frame.const_writer::<6>()
+//!     .write_u32_le(1)
+//!     .write_u16_le(2);
+//! frame.const_writer::<1>().write_u8_le(3);
+//! let bytes = frame.finish(|header, len| header.write_u8_le(0x7F).write_u16_be(len as u16));
+//! assert_eq!(bytes, [0x7F, 0, 7, 1, 0, 0, 0, 2, 0, 3]);
+//! ```
+use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate, ConstWriter};
+use crate::array::ArrayWriterAdapter;
+
+/// Stack buffer with space for `H` bytes of header and up to `P` bytes of payload
+#[repr(C)]
+pub struct FrameBuilder<const H: usize, const P: usize> {
+    // fields of `repr(C)` byte arrays are laid out without padding, so header and payload are contiguous
+    header: [u8; H],
+    payload: [u8; P],
+    /// number of payload bytes written
+    len: usize,
+}
+
+impl<const H: usize, const P: usize> FrameBuilder<H, P> {
+    pub fn new() -> Self {
+        Self {
+            header: [0; H],
+            payload: [0; P],
+            len: 0,
+        }
+    }
+
+    /// Number of payload bytes written
+    pub fn payload_len(&self) -> usize {
+        self.len
+    }
+
+    /// Writes header with `f`, which gets payload length, and returns whole frame
+    pub fn finish<F>(&mut self, f: F) -> &[u8]
+    where
+        F: for<'h> FnOnce(ConstWriter<ArrayWriterAdapter<'h, H>, H>, usize) -> ConstWriter<ArrayWriterAdapter<'h, H>, 0>,
+    {
+        f(self.header.const_writer::<H>(), self.len);
+        // pointer to whole struct, so it covers payload too. `header` is at offset 0 of `repr(C)` struct
+        unsafe { core::slice::from_raw_parts((self as *const Self).cast::<u8>(), H + self.len) }
+    }
+}
+
+impl<const H: usize, const P: usize> Default for FrameBuilder<H, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Appends to payload of [`FrameBuilder`]
+pub struct FramePayloadAdapter<'a, const H: usize, const P: usize> {
+    frame: &'a mut FrameBuilder<H, P>,
+    written: usize,
+}
+
+unsafe impl<'a, const H: usize, const P: usize> ConstWriterAdapterCreate<'a, FrameBuilder<H, P>> for FramePayloadAdapter<'a, H, P> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(frame: &'a mut FrameBuilder<H, P>) -> Self {
+        assert!(
+            N <= P - frame.len,
+            "frame payload too short: {} < {} ({})",
+            P - frame.len,
+            N,
+            core::any::type_name::<Self>()
+        );
+        Self {
+            frame,
+            written: 0,
+        }
+    }
+}
+
+impl<'a, const H: usize, const P: usize> crate::sealed::Sealed for FramePayloadAdapter<'a, H, P> {}
+
+impl<'a, const H: usize, const P: usize> core::fmt::Debug for FramePayloadAdapter<'a, H, P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a, const H: usize, const P: usize> ConstWriterAdapter for FramePayloadAdapter<'a, H, P> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        let ptr = self.frame.payload.as_mut_ptr().add(self.frame.len + self.written);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr, N);
+        self.written += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= P - self.frame.len - self.written,
+            "remaining frame payload too short to grow: {} < {} ({})",
+            P - self.frame.len - self.written,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        let ptr = self.frame.payload.as_mut_ptr().add(self.frame.len + self.written);
+        core::ptr::write_bytes(ptr, crate::POISON, N);
+        self
+    }
+}
+
+impl<'a, const H: usize, const P: usize> Drop for FramePayloadAdapter<'a, H, P> {
+    fn drop(&mut self) {
+        self.frame.len += self.written;
+    }
+}
+
+impl<'a, const H: usize, const P: usize> ConstWrite<'a, FramePayloadAdapter<'a, H, P>> for FrameBuilder<H, P> {}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameBuilder;
+    use crate::ConstWrite;
+
+    #[test]
+    #[should_panic(expected = "frame payload too short: 2 < 4")]
+    fn frame_payload_too_short() {
+        let mut frame = FrameBuilder::<2, 6>::new();
+        frame.const_writer::<4>().write_u32_le(1);
+        assert_eq!(frame.payload_len(), 4);
+        assert_eq!(frame.finish(|header, len| header.write_u16_le(len as u16)), [4, 0, 1, 0, 0, 0]);
+        frame.const_writer::<4>();
+    }
+}
@@ -0,0 +1,123 @@
+//! Adapter wrapper which authenticates written bytes, enabled with `hmac` feature.
+//!
+//! Any [`Mac`] from RustCrypto works, e.g. `Hmac<Sha256>`. Tag is appended in last `TAG` bytes
+//! of budget, so authenticated fixed size frame is written in one pass.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use hmac::{Hmac, Mac};
+//! use sha2::Sha256;
+//!
+//! let mac = Hmac::<Sha256>::new_from_slice(b"telemetry key").unwrap();
+//! let mut vec = vec![];
+//! vec.const_writer::<22>()
+//!     .with_hmac(mac)
+//!     .write_u16_be(7)
+//!     .write_u32_be(1234)
+//!     .finish_hmac::<16>(); // HMAC-SHA256-128
+//! assert_eq!(vec.len(), 22);
+//!
+//! let mut check = Hmac::<Sha256>::new_from_slice(b"telemetry key").unwrap();
+//! check.update(&vec[..6]);
+//! check.verify_truncated_left(&vec[6..]).unwrap();
+//! ```
+use ::hmac::Mac;
+use ::hmac::digest::typenum::Unsigned;
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Compile time check of tag length
+struct TagLen<M, const TAG: usize>(core::marker::PhantomData<M>);
+
+impl<M: Mac, const TAG: usize> TagLen<M, TAG> {
+    const CHECK: () = assert!(TAG <= M::OutputSize::USIZE, "tag is longer than MAC output");
+}
+
+/// Wrapper which feeds bytes written through it to MAC
+pub struct HmacAdapter<T: ConstWriterAdapter, M: Mac> {
+    inner: T,
+    mac: M,
+}
+
+impl<T: ConstWriterAdapter, M: Mac> crate::sealed::Sealed for HmacAdapter<T, M> {}
+
+impl<T: ConstWriterAdapter, M: Mac> core::fmt::Debug for HmacAdapter<T, M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<T: ConstWriterAdapter, M: Mac> ConstWriterAdapter for HmacAdapter<T, M> {
+    fn written_so_far(&self) -> usize {
+        self.inner.written_so_far()
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        self.mac.update(value);
+        self.inner = self.inner.write_raw(value);
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M2: usize>(mut self) -> Self {
+        self.inner = self.inner.grow::<M2>();
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(mut self) -> Self {
+        self.inner = self.inner.poison::<N>();
+        self
+    }
+}
+
+impl<T: crate::GrowableAdapter, M: Mac> crate::GrowableAdapter for HmacAdapter<T, M> {}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Wraps adapter into [`HmacAdapter`], which authenticates following writes with `mac`
+    pub fn with_hmac<M: Mac>(self, mac: M) -> ConstWriter<HmacAdapter<T, M>, {N}> {
+        ConstWriter {
+            writer_adapter: HmacAdapter {
+                inner: self.writer_adapter,
+                mac,
+            },
+        }
+    }
+}
+
+impl<T: ConstWriterAdapter, M: Mac, const N: usize> ConstWriter<HmacAdapter<T, M>, {N}> {
+    /// Appends first `TAG` bytes of MAC of everything written since [`ConstWriter::with_hmac`]
+    /// and unwraps adapter. `TAG` shorter than MAC output gives truncated tag.
+    pub fn finish_hmac<const TAG: usize>(self) -> ConstWriter<T, {N - TAG}> {
+        let () = TagLen::<M, TAG>::CHECK;
+        let HmacAdapter { inner, mac } = self.writer_adapter;
+        let output = mac.finalize().into_bytes();
+        let mut tag = [0u8; TAG];
+        tag.copy_from_slice(&output[..TAG]);
+        unsafe {
+            ConstWriter {
+                writer_adapter: inner.write(&tag),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use ::hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    #[test]
+    fn hmac_rfc4231_case2() {
+        let mac = Hmac::<Sha256>::new_from_slice(b"Jefe").unwrap();
+        let mut buff = [0u8; 60];
+        buff.const_writer::<60>()
+            .with_hmac(mac)
+            .write_slice(b"what do ya want for nothing?")
+            .finish_hmac::<32>();
+        assert_eq!(buff[28..32], [0x5B, 0xDC, 0xC1, 0x46]);
+        assert_eq!(buff[56..], [0x64, 0xEC, 0x38, 0x43]);
+    }
+}
@@ -0,0 +1,213 @@
+//! Adapter functions to write types defined with other binary format crates.
+//!
+//! Those crates don't know encoded size at compile time, so it must be specified as `M`,
+//! value is serialized to `[u8; M]` on stack and then written to [`ConstWriter`].
+//!
+//! Enabled with `binrw`, `deku` and `rkyv` features.
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Writes `M` bytes of [`binrw::BinWrite`] value.
+///
+/// Nothing is written if serialization fails.
+///
+/// # Panics
+/// If `value` wasn't serialized to exactly `M` bytes.
+#[cfg(feature = "binrw")]
+pub fn write_binrw<const M: usize, V: binrw::BinWrite, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    value: &V,
+    endian: binrw::Endian,
+    args: V::Args<'_>,
+) -> binrw::BinResult<ConstWriter<T, {N - M}>> {
+    let mut buff = [0u8; M];
+    let mut cursor = binrw::io::Cursor::new(buff.as_mut());
+    value.write_options(&mut cursor, endian, args)?;
+    assert_eq!(cursor.position() as usize, M, "binrw value size mismatch");
+
+    unsafe {
+        Ok(ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&buff),
+        })
+    }
+}
+
+/// Writes `M` bytes of [`deku::DekuContainerWrite`] value.
+///
+/// Nothing is written if serialization fails.
+///
+/// # Panics
+/// If `value` wasn't serialized to exactly `M` bytes.
+#[cfg(feature = "deku")]
+pub fn write_deku<const M: usize, V: deku::DekuContainerWrite, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    value: &V,
+) -> Result<ConstWriter<T, {N - M}>, deku::DekuError> {
+    let mut buff = [0u8; M];
+    let written = value.to_slice(&mut buff)?;
+    assert_eq!(written, M, "deku value size mismatch");
+
+    unsafe {
+        Ok(ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&buff),
+        })
+    }
+}
+
+/// [`rkyv`] writer which serializes archive to `[u8; M]` staged by [`write_rkyv`]
+#[cfg(feature = "rkyv")]
+pub struct RkyvWriter<const M: usize> {
+    buff: [u8; M],
+    len: usize,
+}
+
+/// Archive didn't fit in `M` bytes reserved by [`write_rkyv`]
+#[cfg(feature = "rkyv")]
+#[derive(Debug)]
+struct ArchiveTooLong {
+    len: usize,
+    max: usize,
+}
+
+#[cfg(feature = "rkyv")]
+impl core::fmt::Display for ArchiveTooLong {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "archive too long: {} > {}", self.len, self.max)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl core::error::Error for ArchiveTooLong {}
+
+#[cfg(feature = "rkyv")]
+impl<const M: usize> rkyv::ser::Positional for RkyvWriter<M> {
+    fn pos(&self) -> usize {
+        self.len
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const M: usize, E: rkyv::rancor::Source> rkyv::ser::Writer<E> for RkyvWriter<M> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if bytes.len() > M - self.len {
+            return Err(E::new(ArchiveTooLong { len: self.len + bytes.len(), max: M }));
+        }
+        self.buff[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+        Ok(())
+    }
+}
+
+/// Serializes [`rkyv`] archive of at most `M` bytes and writes it.
+///
+/// `scratch` is used as scratch space for serializer. Returns number of bytes of archive,
+/// archive root is positioned relative to the end of them as usual. Rest of `M` bytes is zeroed.
+///
+/// Archive positions are relative to start of writer, so archive is aligned only if writer
+/// starts at address aligned to archive alignment, e.g. with [`aligned`](crate::aligned) adapter.
+/// Nothing is written if serialization fails.
+#[cfg(feature = "rkyv")]
+pub fn write_rkyv<const M: usize, E: rkyv::rancor::Source, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    value: &impl for<'a> rkyv::Serialize<rkyv::api::low::LowSerializer<RkyvWriter<M>, rkyv::ser::allocator::SubAllocator<'a>, E>>,
+    scratch: &mut [core::mem::MaybeUninit<u8>],
+) -> Result<(ConstWriter<T, {N - M}>, usize), E> {
+    let rkyv_writer = RkyvWriter::<M> {
+        buff: [0; M],
+        len: 0,
+    };
+    let rkyv_writer = rkyv::api::low::to_bytes_in_with_alloc::<_, _, E>(
+        value,
+        rkyv_writer,
+        rkyv::ser::allocator::SubAllocator::new(scratch),
+    )?;
+
+    unsafe {
+        Ok((
+            ConstWriter {
+                writer_adapter: writer.writer_adapter.write(&rkyv_writer.buff),
+            },
+            rkyv_writer.len,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use crate::ConstWrite;
+
+    #[cfg(feature = "binrw")]
+    #[test]
+    fn binrw_write() {
+        #[derive(binrw::BinWrite)]
+        struct Header {
+            id: u16,
+            len: u32,
+        }
+
+        let mut buff = [0u8; 8];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let header = Header { id: 1, len: 2 };
+        super::write_binrw::<6, _, _, _>(ref_buff.const_writer::<8>(), &header, binrw::Endian::Big, ())
+            .unwrap()
+            .write_u16_le(3);
+        assert_eq!(buff, [0, 1, 0, 0, 0, 2, 3, 0]);
+    }
+
+    #[cfg(feature = "deku")]
+    #[test]
+    fn deku_write() {
+        #[derive(deku::DekuWrite)]
+        #[deku(endian = "little")]
+        struct Header {
+            id: u16,
+            len: u32,
+        }
+
+        let mut buff = [0u8; 6];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let header = Header { id: 1, len: 2 };
+        let _ = super::write_deku::<6, _, _, _>(ref_buff.const_writer::<6>(), &header).unwrap();
+        assert_eq!(buff, [1, 0, 2, 0, 0, 0]);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn rkyv_write() {
+        #[derive(rkyv::Archive, rkyv::Serialize)]
+        struct Header {
+            id: u16,
+            len: u32,
+        }
+
+        let mut buff = rkyv::util::Align([0u8; 16]);
+        let mut scratch = [core::mem::MaybeUninit::uninit(); 16];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let header = Header { id: 1, len: 2 };
+        let (_, len) = super::write_rkyv::<16, rkyv::rancor::Failure, _, _>(ref_buff.const_writer::<16>(), &header, &mut scratch)
+            .unwrap();
+        assert!(ref_buff.is_empty());
+        assert!(buff[len..].iter().all(|byte| *byte == 0));
+
+        let archived = unsafe { rkyv::access_unchecked::<ArchivedHeader>(&buff[..len]) };
+        assert_eq!(archived.id, 1);
+        assert_eq!(archived.len, 2);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn rkyv_too_long() {
+        #[derive(rkyv::Archive, rkyv::Serialize)]
+        struct Header {
+            id: u16,
+            len: u32,
+        }
+
+        let mut buff = [0xAAu8; 4];
+        let mut scratch = [core::mem::MaybeUninit::uninit(); 16];
+        let header = Header { id: 1, len: 2 };
+        let result = super::write_rkyv::<4, rkyv::rancor::Failure, _, _>(buff.const_writer::<4>(), &header, &mut scratch);
+        assert!(result.is_err());
+        assert_eq!(buff, [0xAA; 4]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Append-only journal of [`ConstWritable`] values.
+//!
+//! Every value is framed same as [`ConstWriter::write_record`](crate::ConstWriter::write_record):
+//! `[len u32 le][payload][crc32 u32 le]`. Records are buffered and written to file
+//! together (group commit) once batch is full or on [`Journal::commit`], which also syncs
+//! file data to storage with [`SyncData`].
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::journal::Journal;
+//!
+//! const_writer::const_layout! {
+//!     struct Transfer {
+//!         from: u32_le,
+//!         to: u32_le,
+//!         amount: u64_le,
+//!     }
+//! }
+//!
+//! let mut journal = Journal::new(Vec::new(), 4096);
+//! journal.append(&Transfer { from: 1, to: 2, amount: 100 }).unwrap();
+//! let seq = journal.append(&Transfer { from: 2, to: 3, amount: 50 }).unwrap();
+//! journal.commit().unwrap();
+//! assert_eq!(seq, 1);
+//! assert_eq!(journal.into_inner().unwrap().len(), 2 * (16 + 8));
+//! ```
+use crate::{ConstWritable, ConstWriter};
+use crate::file::FileRecordWriter;
+use std::fs::File;
+use std::io::{self, Write};
+
+/// Writer which data can be synced to storage
+pub trait SyncData: Write {
+    fn sync_data(&mut self) -> io::Result<()>;
+}
+
+impl SyncData for File {
+    fn sync_data(&mut self) -> io::Result<()> {
+        File::sync_data(self)
+    }
+}
+
+/// In memory journal, nothing to sync
+impl SyncData for Vec<u8> {
+    fn sync_data(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<W: SyncData + ?Sized> SyncData for &mut W {
+    fn sync_data(&mut self) -> io::Result<()> {
+        (**self).sync_data()
+    }
+}
+
+pub struct Journal<W: SyncData = File> {
+    records: FileRecordWriter<W>,
+    /// number of appended records
+    appended: u64,
+}
+
+impl<W: SyncData> Journal<W> {
+    /// Creates journal which writes to `inner` after `group_size` bytes of records accumulated
+    pub fn new(inner: W, group_size: usize) -> Self {
+        Self {
+            records: FileRecordWriter::new(inner, group_size),
+            appended: 0,
+        }
+    }
+
+    /// Appends record with `value`, returns its sequence number.
+    ///
+    /// Record is only buffered, it is durable after [`commit`](Journal::commit).
+    // second bound is remaining budget of record writer, which is always 0
+    #[allow(clippy::eq_op)]
+    pub fn append<T: ConstWritable>(&mut self, value: &T) -> io::Result<u64>
+    where
+        [(); T::SIZE + 8]:,
+        [(); T::SIZE + 8 - (T::SIZE + 8)]:,
+    {
+        self.records.record::<{ T::SIZE + 8 }>()?
+            .write_record::<{ T::SIZE }>(|w| unsafe { ConstWriter { writer_adapter: value.write_to(w.writer_adapter) } });
+        self.appended += 1;
+        Ok(self.appended - 1)
+    }
+
+    /// Writes buffered records to inner writer, flushes it and syncs data, after that
+    /// records are durable
+    pub fn commit(&mut self) -> io::Result<()> {
+        self.records.flush()?;
+        self.records.get_mut().sync_data()
+    }
+
+    /// Number of appended records
+    pub fn len(&self) -> u64 {
+        self.appended
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.appended == 0
+    }
+
+    /// Commits buffered records and returns inner writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.commit()?;
+        self.records.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Journal;
+
+    crate::const_layout! {
+        struct Entry {
+            key: u16_be,
+            value: u8,
+        }
+    }
+
+    #[test]
+    fn journal_group_commit() {
+        let mut journal = Journal::new(Vec::new(), 16);
+        journal.append(&Entry { key: 1, value: 2 }).unwrap();
+        journal.append(&Entry { key: 3, value: 4 }).unwrap();
+        // first group reached 22 bytes and is written before third record
+        journal.append(&Entry { key: 5, value: 6 }).unwrap();
+        assert_eq!((journal.len(), journal.records.pending()), (3, 11));
+
+        let out = journal.into_inner().unwrap();
+        assert_eq!(out.len(), 3 * 11);
+        assert_eq!(out[..7], [3, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(out[7..11], crate::crc::crc32(&[0, 1, 2]).to_le_bytes());
+    }
+}
@@ -0,0 +1,342 @@
+//! [`const_layout!`](crate::const_layout) macro which defines struct together with its
+//! [`ConstWritable`](crate::ConstWritable) and [`ConstReadable`](crate::ConstReadable) impls,
+//! so writer and reader can't get out of sync.
+//!
+//! ```
+//! use const_writer::{const_layout, ConstRead, ConstWrite};
+//!
+//! const_layout! {
+//!     #[derive(Debug, PartialEq)]
+//!     pub struct Header {
+//!         pub magic: u32_be,
+//!         pub version: u16_le,
+//!         pub flags: u8,
+//!     }
+//! }
+//!
+//! let header = Header { magic: 0xCAFEBABE, version: 2, flags: 1 };
+//!
+//! let mut vec = vec![];
+//! vec.const_writer::<7>().write_value(&header);
+//! assert_eq!(vec, [0xCA, 0xFE, 0xBA, 0xBE, 2, 0, 1]);
+//!
+//! let mut slice = &vec[..];
+//! let (read, _) = slice.const_reader::<7>().read_value::<Header>();
+//! assert_eq!(read, header);
+//! ```
+//!
+//! Struct also gets `LAYOUT` const with name, offset, width and byte order of every field,
+//! which tooling can turn into dissectors or docs:
+//! ```
+//! use const_writer::layout::{Endian, FieldLayout};
+//!
+//! const_writer::const_layout! {
+//!     struct Header {
+//!         magic: u32_be,
+//!         flags: u8,
+//!     }
+//! }
+//!
+//! assert_eq!(Header::LAYOUT[1], FieldLayout { name: "flags", kind: "u8", offset: 4, width: 1, endian: None });
+//! assert_eq!(Header::LAYOUT[0].endian, Some(Endian::Big));
+//! ```
+//!
+//! Supported field kinds are `u8`, `i8` and `{u,i}{16,32,64,128}_{le,be}`, `f{32,64}_{le,be}`.
+//!
+//! Same kinds can be used with [`const_write!`](crate::const_write) to write fields without defining struct:
+//! ```
+//! use const_writer::{const_write, ConstWrite};
+//!
+//! let magic = [0xCA, 0xFE];
+//! let mut vec = vec![];
+//! const_write!(vec.const_writer::<8>(), {
+//!     u32_le: 1,     // id
+//!     u16_be: 2,     // len
+//!     bytes: &magic,
+//! });
+//! assert_eq!(vec, [1, 0, 0, 0, 0, 2, 0xCA, 0xFE]);
+//! ```
+
+use crate::{ConstReaderAdapter, ConstWriterAdapter};
+
+/// Byte order of field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Description of [`const_layout!`](crate::const_layout) field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLayout {
+    pub name: &'static str,
+    /// field kind, e.g. `u32_be`
+    pub kind: &'static str,
+    /// offset from start of struct in bytes
+    pub offset: usize,
+    /// size in bytes
+    pub width: usize,
+    /// `None` for single byte fields
+    pub endian: Option<Endian>,
+}
+
+/// Fills offsets of consecutive fields, used by `const_layout!`
+#[doc(hidden)]
+pub const fn with_offsets<const L: usize>(mut fields: [FieldLayout; L]) -> [FieldLayout; L] {
+    let mut offset = 0;
+    let mut i = 0;
+    while i < L {
+        fields[i].offset = offset;
+        offset += fields[i].width;
+        i += 1;
+    }
+    fields
+}
+
+/// Field kind of [`const_layout!`](crate::const_layout)
+///
+/// # Safety
+/// `write` and `read` must use exactly `SIZE` bytes
+#[doc(hidden)]
+pub unsafe trait FieldKind {
+    type Value;
+    const SIZE: usize;
+    const ENDIAN: Option<crate::layout::Endian>;
+
+    unsafe fn write<T: ConstWriterAdapter>(value: &Self::Value, writer_adapter: T) -> T;
+
+    unsafe fn read<T: ConstReaderAdapter>(reader_adapter: &mut T) -> Self::Value;
+}
+
+/// Marker types for field kinds
+#[doc(hidden)]
+#[allow(non_camel_case_types)]
+pub mod kinds {
+    use super::{Endian, FieldKind};
+    use crate::{ConstReaderAdapter, ConstWriterAdapter};
+
+    macro_rules! field_kind {
+        ($name:ident, $type:ty, $to:ident, $from:ident, $endian:expr) => {
+            pub struct $name;
+
+            unsafe impl FieldKind for $name {
+                type Value = $type;
+                const SIZE: usize = core::mem::size_of::<$type>();
+                const ENDIAN: Option<Endian> = $endian;
+
+                #[cfg_attr(feature = "stats", track_caller)]
+                unsafe fn write<T: ConstWriterAdapter>(value: &$type, writer_adapter: T) -> T {
+                    writer_adapter.write(&value.$to())
+                }
+
+                unsafe fn read<T: ConstReaderAdapter>(reader_adapter: &mut T) -> $type {
+                    <$type>::$from(reader_adapter.read())
+                }
+            }
+        }
+    }
+
+    field_kind!(u8, core::primitive::u8, to_le_bytes, from_le_bytes, None);
+    field_kind!(i8, core::primitive::i8, to_le_bytes, from_le_bytes, None);
+
+    field_kind!(u16_le, u16, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(u32_le, u32, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(u64_le, u64, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(u128_le, u128, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(i16_le, i16, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(i32_le, i32, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(i64_le, i64, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(i128_le, i128, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(f32_le, f32, to_le_bytes, from_le_bytes, Some(Endian::Little));
+    field_kind!(f64_le, f64, to_le_bytes, from_le_bytes, Some(Endian::Little));
+
+    field_kind!(u16_be, u16, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(u32_be, u32, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(u64_be, u64, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(u128_be, u128, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(i16_be, i16, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(i32_be, i32, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(i64_be, i64, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(i128_be, i128, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(f32_be, f32, to_be_bytes, from_be_bytes, Some(Endian::Big));
+    field_kind!(f64_be, f64, to_be_bytes, from_be_bytes, Some(Endian::Big));
+}
+
+/// Defines struct with [`ConstWritable`](crate::ConstWritable) and [`ConstReadable`](crate::ConstReadable)
+/// impls generated from single field list, and `LAYOUT` const describing its fields. See [`layout`](crate::layout) module.
+#[macro_export]
+macro_rules! const_layout {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $kind:ident),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: <$crate::layout::kinds::$kind as $crate::layout::FieldKind>::Value),*
+        }
+
+        impl $name {
+            /// Fields in order they are written
+            #[allow(dead_code)]
+            $vis const LAYOUT: &'static [$crate::layout::FieldLayout] = &$crate::layout::with_offsets([$(
+                $crate::layout::FieldLayout {
+                    name: stringify!($field),
+                    kind: stringify!($kind),
+                    offset: 0,
+                    width: <$crate::layout::kinds::$kind as $crate::layout::FieldKind>::SIZE,
+                    endian: <$crate::layout::kinds::$kind as $crate::layout::FieldKind>::ENDIAN,
+                }
+            ),*]);
+        }
+
+        unsafe impl $crate::ConstWritable for $name {
+            const SIZE: usize = 0 $(+ <$crate::layout::kinds::$kind as $crate::layout::FieldKind>::SIZE)*;
+
+            unsafe fn write_to<T: $crate::ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                $(
+                    let writer_adapter = <$crate::layout::kinds::$kind as $crate::layout::FieldKind>::write(&self.$field, writer_adapter);
+                )*
+                writer_adapter
+            }
+        }
+
+        unsafe impl $crate::ConstReadable for $name {
+            const SIZE: usize = 0 $(+ <$crate::layout::kinds::$kind as $crate::layout::FieldKind>::SIZE)*;
+
+            unsafe fn read_from<T: $crate::ConstReaderAdapter>(reader_adapter: &mut T) -> Self {
+                $(
+                    let $field = <$crate::layout::kinds::$kind as $crate::layout::FieldKind>::read(reader_adapter);
+                )*
+                Self { $($field),* }
+            }
+        }
+    }
+}
+
+/// Writes list of fields to writer, expanding to chained calls. Evaluates to resulting writer.
+///
+/// Each field is `kind: value` where kind is one of [`const_layout!`](crate::const_layout) kinds,
+/// or `bytes: &array` for [`write_slice`](crate::ConstWriter::write_slice).
+#[macro_export]
+macro_rules! const_write {
+    ($writer:expr, { $($body:tt)* }) => {
+        $crate::const_write!(@chain $writer; $($body)*)
+    };
+    (@chain $writer:expr; ) => {
+        $writer
+    };
+    (@chain $writer:expr; bytes : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::const_write!(@chain $writer.write_slice($value); $($($rest)*)?)
+    };
+    (@chain $writer:expr; $kind:ident : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::const_write!(@chain $writer.write_field::<$crate::layout::kinds::$kind>(&$value); $($($rest)*)?)
+    };
+}
+
+/// Same as [`const_write!`](crate::const_write), but packs all fields to single stack array first
+/// and writes it with one [`write_slice`](crate::ConstWriter::write_slice).
+///
+/// Array is written with [`const_writer_exact`](crate::array::const_writer_exact), so packing has no runtime checks.
+/// Useful when optimizer doesn't merge separate writes to buffer. `bytes` fields aren't supported.
+/// ```
+/// use const_writer::{write_all, ConstWrite};
+///
+/// let mut vec = vec![];
+/// write_all!(vec.const_writer::<6>(), {
+///     u32_le: 1,
+///     u16_be: 2,
+/// });
+/// assert_eq!(vec, [1, 0, 0, 0, 0, 2]);
+/// ```
+#[macro_export]
+macro_rules! write_all {
+    ($writer:expr, { $($kind:ident : $value:expr),* $(,)? }) => {{
+        const __CONST_WRITER_WRITE_ALL_SIZE: usize = 0 $(+ <$crate::layout::kinds::$kind as $crate::layout::FieldKind>::SIZE)*;
+        let mut buff = [0u8; __CONST_WRITER_WRITE_ALL_SIZE];
+        $crate::const_write!($crate::array::const_writer_exact(&mut buff), { $($kind: $value),* });
+        $writer.write_packed(&buff)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConstRead, ConstWrite};
+
+    crate::const_layout! {
+        #[derive(Debug, PartialEq)]
+        struct Sample {
+            a: i16_be,
+            b: f32_le,
+            c: u64_le,
+        }
+    }
+
+    #[test]
+    fn layout_roundtrip() {
+        let sample = Sample { a: -2, b: 1.5, c: 7 };
+        let mut buff = [0u8; 16];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = ref_buff.const_writer::<16>()
+            .write_value(&sample);
+        assert_eq!(writer.remaining(), 2);
+        drop(writer);
+
+        let mut slice = &buff[..];
+        let (read, reader) = slice.const_reader::<14>().read_value::<Sample>();
+        assert_eq!(reader.remaining(), 0);
+        assert_eq!(read, sample);
+    }
+
+    #[test]
+    fn layout_fields() {
+        use super::{Endian, FieldLayout};
+        assert_eq!(Sample::LAYOUT, [
+            FieldLayout { name: "a", kind: "i16_be", offset: 0, width: 2, endian: Some(Endian::Big) },
+            FieldLayout { name: "b", kind: "f32_le", offset: 2, width: 4, endian: Some(Endian::Little) },
+            FieldLayout { name: "c", kind: "u64_le", offset: 6, width: 8, endian: Some(Endian::Little) },
+        ]);
+    }
+
+    #[test]
+    fn const_write_fields() {
+        let mut buff = [0u8; 8];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = crate::const_write!(ref_buff.const_writer::<8>(), {
+            i8: -1,
+            f32_be: 1.0,
+            bytes: &[7],
+        });
+        assert_eq!(writer.remaining(), 2);
+        drop(writer);
+        let unwritten = if cfg!(all(feature = "debug-poison", debug_assertions)) { 0xAA } else { 0 };
+        assert_eq!(buff, [0xff, 0x3f, 0x80, 0, 0, 7, unwritten, unwritten]);
+    }
+
+    #[test]
+    fn write_all_packed() {
+        let mut buff = [0u8; 12];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = crate::write_all!(ref_buff.const_writer::<12>(), {
+            u8: 1,
+            u16_le: 0x0302,
+            u64_be: 0x0405060708090A0B,
+        });
+        assert_eq!(writer.remaining(), 1);
+        drop(writer);
+        let unwritten = if cfg!(all(feature = "debug-poison", debug_assertions)) { 0xAA } else { 0 };
+        assert_eq!(buff, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, unwritten]);
+    }
+
+    #[test]
+    fn write_all_user_size() {
+        const SIZE: u32 = 0x01020304;
+        let mut buff = [0u8; 6];
+        crate::write_all!(buff.const_writer::<6>(), {
+            u32_be: SIZE,
+            u16_le: SIZE as u16,
+        });
+        assert_eq!(buff, [1, 2, 3, 4, 4, 3]);
+    }
+}
@@ -1,16 +1,16 @@
-#![feature(const_generics)]
-#![feature(const_evaluatable_checked)]
-#![feature(type_name_of_val)]
-#![allow(incomplete_features)]
-#![feature(test)]
+#![cfg_attr(nightly, feature(generic_const_exprs))]
+#![cfg_attr(nightly, allow(incomplete_features))]
+#![cfg_attr(all(test, nightly), feature(test))]
+#![cfg_attr(all(any(feature = "std", feature = "alloc"), nightly), feature(allocator_api))]
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 //! Provides [`ConstWriter`] abstraction to write constant amount of bytes with compile time checks
 //!
-//! Result of fun experiment with `const_generics` and `const_evaluatable_checked` features
+//! Result of fun experiment with `generic_const_exprs` feature
 //!
-//! ```
+#![cfg_attr(nightly, doc = "```")]
+#![cfg_attr(not(nightly), doc = "```ignore")]
 //! use const_writer::ConstWrite;
 //!
 //! let mut vec = vec![];
@@ -35,7 +35,8 @@
 //! ```
 //!
 //! Usage in functions
-//! ```
+#![cfg_attr(nightly, doc = "```")]
+#![cfg_attr(not(nightly), doc = "```ignore")]
 //! use const_writer::{ConstWriterAdapter, ConstWriter, ConstWrite};
 //!
 //! // write 10 bytes
@@ -56,48 +57,274 @@
 
 /// Trait for creating `ConstWriterAdapter`
 /// Creation moved to separate trait to omit lifetime parameter on ConstWriter
+///
+/// # Safety
+/// Adapter returned by `new::<N>` must be able to write `N` bytes, [`ConstWriter`] relies on it.
 pub unsafe trait ConstWriterAdapterCreate<'a, T: ?Sized> {
     /// # Safety
     /// You must ensure that underlying buffer have space for at least `N` bytes.
     unsafe fn new<const N: usize>(buff: &'a mut T) -> Self;
 }
 
+mod sealed {
+    pub trait Sealed {}
+}
+
 /// Source of all performance of crate. Provide unsafe interface to underlying buffer.
 ///
 /// Because const generics expressions in traits works really bad,
 /// this adapter doesn't has generic len param, so write is basically unchecked write to array.
 /// This adapter must be used within [`ConstWriter`] because it holds and tracks buffer length
-pub trait ConstWriterAdapter {
-    /// Write bytes and advances inner buffer
+///
+/// Trait is sealed, new buffer types can be supported by implementing [`reserve::ReserveBuffer`].
+pub trait ConstWriterAdapter: sealed::Sealed + Sized {
+    /// Write bytes and advances inner buffer. With `stats` feature write is counted
+    /// for call site, see [`stats`](crate::stats).
     ///
     /// # Safety
-    /// Unsafe because with current `const_generics` and `const_evaluatable_checked` we can't
+    /// Unsafe because with current `generic_const_exprs` we can't
     /// define trait which returns self with calculated const generic param.
     ///
     /// You should make sure that in total you advance less or equal than `N` bytes
-    unsafe fn write<const N: usize>(self, value: &[u8; N]) -> Self;
+    #[cfg_attr(feature = "stats", track_caller)]
+    unsafe fn write<const N: usize>(self, value: &[u8; N]) -> Self {
+        #[cfg(all(feature = "stats", nightly))]
+        stats::record(core::panic::Location::caller(), N);
+        self.write_raw(value)
+    }
+
+    /// Same as `write`, but never counted. Implemented by adapters, wrapper adapters
+    /// forward to it, so every write is counted once.
+    ///
+    /// # Safety
+    /// Same as `write`
+    #[doc(hidden)]
+    unsafe fn write_raw<const N: usize>(self, value: &[u8; N]) -> Self;
 
     /// Ensures that underlying buffer have space for `M` additional bytes
+    ///
+    /// # Safety
+    /// Must be called only by [`ConstWriter`], which changes its length accordingly.
+    ///
     /// # Example
     /// If 5 bytes were written to buffer, then `grow::<10>()` will ensure that
     /// underlying buffer have capacity at least 15
     unsafe fn grow<const M: usize>(self) -> Self;
+
+    /// Number of bytes written through adapter since its creation
+    fn written_so_far(&self) -> usize;
+
+    /// Fills next `N` bytes with [`POISON`] without advancing, used by `debug-poison` feature
+    #[doc(hidden)]
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self;
+}
+
+/// Adapter which can grow underlying buffer, e.g. vector.
+///
+/// Only writers over such adapters have [`ConstWriter::grow_to`], other adapters are fixed size
+/// and growing them would only move compile time check to runtime panic:
+/// ```compile_fail
+/// use const_writer::ConstWrite;
+///
+/// let mut buff = [0u8; 8];
+/// let mut slice = buff.as_mut() as &mut [u8];
+/// slice.const_writer::<4>().grow_to::<8>();
+/// ```
+pub trait GrowableAdapter: ConstWriterAdapter {}
+
+/// Shared `Debug` output of adapters
+pub(crate) fn debug_adapter<T: ConstWriterAdapter>(adapter: &T, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct(core::any::type_name::<T>())
+        .field("written_so_far", &adapter.written_so_far())
+        .finish()
+}
+
+/// Byte which fills reserved but not yet written space with `debug-poison` feature
+#[cfg(all(feature = "debug-poison", debug_assertions))]
+pub const POISON: u8 = 0xAA;
+
+#[cfg(nightly)]
+/// Poisons `N` reserved bytes of fresh adapter when `debug-poison` feature is enabled in debug build
+#[inline(always)]
+pub(crate) unsafe fn poisoned<T: ConstWriterAdapter, const N: usize>(writer_adapter: T) -> T {
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    let writer_adapter = writer_adapter.poison::<N>();
+    writer_adapter
 }
 
 pub mod slice;
+pub mod reserve;
+pub mod min;
+pub mod template;
+pub mod crc;
+pub mod checksum;
+#[cfg(nightly)]
+pub mod array;
+#[cfg(nightly)]
+pub mod ring;
+#[cfg(nightly)]
+pub mod raw;
+#[cfg(nightly)]
+pub mod aligned;
+#[cfg(nightly)]
+pub mod checked;
+#[cfg(nightly)]
+pub mod dynamic;
+#[cfg(nightly)]
+pub mod backfill;
+#[cfg(nightly)]
+pub mod frame;
+#[cfg(nightly)]
+pub mod reader;
+#[cfg(nightly)]
+pub mod layout;
+#[cfg(nightly)]
+pub mod roundtrip;
+#[cfg(all(feature = "testing", nightly))]
+pub mod testing;
+#[cfg(all(feature = "stats", nightly))]
+pub mod stats;
+#[cfg(nightly)]
+pub mod msgpack;
+#[cfg(nightly)]
+pub mod cbor;
+#[cfg(all(feature = "mavlink", nightly))]
+pub mod mavlink;
+#[cfg(all(feature = "hmac", nightly))]
+pub mod hmac;
+#[cfg(nightly)]
+pub mod can;
+#[cfg(nightly)]
+pub mod modbus;
+#[cfg(nightly)]
+pub mod rtp;
+#[cfg(nightly)]
+pub mod timestamp;
+#[cfg(nightly)]
+pub mod disk;
+#[cfg(nightly)]
+pub mod elf;
+#[cfg(nightly)]
+pub mod media;
+#[cfg(nightly)]
+pub mod tar;
+#[cfg(nightly)]
+pub mod pg;
+#[cfg(nightly)]
+pub mod ninep;
+#[cfg(nightly)]
+pub mod nbd;
+#[cfg(nightly)]
+pub mod usb;
+#[cfg(nightly)]
+pub mod ble;
+#[cfg(nightly)]
+pub mod flash;
+#[cfg(nightly)]
+pub mod quic;
+#[cfg(nightly)]
+pub mod tls;
+#[cfg(nightly)]
+pub mod nonce;
+#[cfg(nightly)]
+pub mod tsdb;
+#[cfg(nightly)]
+pub mod bitpack;
+#[cfg(nightly)]
+pub mod page;
+#[cfg(nightly)]
+pub mod bloom;
+#[cfg(nightly)]
+pub mod roaring;
+#[cfg(all(feature = "postcard-fixed", nightly))]
+pub mod postcard;
+#[cfg(nightly)]
+pub mod flatbuffers;
+#[cfg(nightly)]
+pub mod capnp;
+#[cfg(nightly)]
+pub mod avro;
+#[cfg(nightly)]
+pub mod parquet;
+#[cfg(nightly)]
+pub mod arrow;
+#[cfg(nightly)]
+pub mod bits;
+#[cfg(nightly)]
+pub mod netcode;
+#[cfg(nightly)]
+pub mod packet;
+#[cfg(nightly)]
+pub mod scatter;
+#[cfg(nightly)]
+pub mod uring;
+#[cfg(nightly)]
+pub mod netlink;
+#[cfg(nightly)]
+pub mod bpf;
+
+#[cfg(all(any(feature = "binrw", feature = "deku", feature = "rkyv"), nightly))]
+pub mod interop;
 
-#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg(all(any(feature = "std", feature = "alloc"), nightly))]
 pub mod vec;
 
+#[cfg(all(any(feature = "std", feature = "alloc"), nightly))]
+pub mod batch;
+
+#[cfg(all(any(feature = "std", feature = "alloc"), nightly))]
+pub mod string;
+
+#[cfg(all(any(feature = "std", feature = "alloc"), nightly))]
+pub mod boxed;
+
+#[cfg(all(any(feature = "std", feature = "alloc"), nightly))]
+pub mod pool;
+
+#[cfg(all(feature = "std", nightly))]
+pub mod shared;
+
+#[cfg(all(feature = "std", nightly))]
+pub mod file;
+
+#[cfg(all(feature = "std", nightly))]
+pub mod journal;
+
+#[cfg(all(feature = "bumpalo", nightly))]
+pub mod bump;
+
+#[cfg(feature = "stable")]
+pub mod stable;
+
+#[cfg(nightly)]
+pub use reader::{ConstRead, ConstReadable, ConstReader, ConstReaderAdapter};
+
 ///
 /// Writer that keeping track of space left using const_generic params.
 ///
+#[cfg(nightly)]
 pub struct ConstWriter<T: ConstWriterAdapter, const N: usize> {
     writer_adapter: T,
 }
 
+/// Shows budget left and bytes written, e.g. `ConstWriter { remaining: 4, written_so_far: 2, adapter: "..." }`
+#[cfg(nightly)]
+impl<T: ConstWriterAdapter, const N: usize> core::fmt::Debug for ConstWriter<T, {N}> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ConstWriter")
+            .field("remaining", &N)
+            .field("written_so_far", &self.writer_adapter.written_so_far())
+            .field("adapter", &core::any::type_name::<T>())
+            .finish()
+    }
+}
+
+#[cfg(nightly)]
 macro_rules! implement_write {
     ($name:ident, $type:ty, $endian:ident) => {
+        #[inline(always)]
+        #[cfg_attr(any(debug_assertions, feature = "stats"), track_caller)]
         pub fn $name(self, value: $type) ->ConstWriter<T, {N - core::mem::size_of::<$type>()}> {
             unsafe {
                 ConstWriter {
@@ -109,29 +336,127 @@ macro_rules! implement_write {
     }
 }
 
+/// Compile time comparison of writer lengths
+#[cfg(nightly)]
+struct Shrink<const N: usize, const M: usize>;
+
+#[cfg(nightly)]
+impl<const N: usize, const M: usize> Shrink<N, M> {
+    const IS_SHRINK: bool = M <= N;
+    const CHECK: () = assert!(M <= N, "can't shrink writer to bigger length");
+    const GROW_CHECK: () = assert!(M >= N, "can't grow writer to smaller length");
+}
+
+#[cfg(nightly)]
 impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
     /// Changes length of [`ConstWriter`] to `M`.
     ///
     /// If `M` <= `N` then no checks or allocation invoked
     ///
     /// If `M` > `N` there adapter ensures that underlying buffer have space for `M` more bytes.
+    /// Fixed size adapters panic at runtime if buffer is too short to grow.
+    #[deprecated(note = "use `shrink` or `grow_to`, so growing is explicit")]
+    #[track_caller]
     pub fn convert<const M: usize>(self) -> ConstWriter<T, {M}> {
-        if M <= N { // shrink
-            ConstWriter {
+        // selected at compile time, so shrinking has no branch even without optimizations
+        if Shrink::<N, M>::IS_SHRINK {
+            ConstWriter { // shrink
                 writer_adapter: self.writer_adapter,
-
             }
         } else {
             unsafe {
                 ConstWriter { // grow
-                    writer_adapter: self.writer_adapter.grow::<{M}>(),
+                    writer_adapter: poisoned::<_, M>(self.writer_adapter.grow::<{M}>()),
 
                 }
             }
         }
     }
+
+    /// Decreases length of [`ConstWriter`] to `M` without any checks.
+    ///
+    /// `M` > `N` doesn't compile:
+    /// ```compile_fail
+    /// use const_writer::ConstWrite;
+    ///
+    /// let mut vec = vec![];
+    /// vec.const_writer::<2>().shrink::<4>();
+    /// ```
+    #[inline(always)]
+    pub fn shrink<const M: usize>(self) -> ConstWriter<T, {M}> {
+        let () = Shrink::<N, M>::CHECK;
+        ConstWriter {
+            writer_adapter: self.writer_adapter,
+        }
+    }
+
+    /// Calls `f` with writer, lets helper functions be used inside method chain.
+    /// ```
+    /// use const_writer::{ConstWrite, ConstWriter, ConstWriterAdapter};
+    ///
+    /// fn tag<T: ConstWriterAdapter>(writer: ConstWriter<T, 6>) -> ConstWriter<T, 4> {
+    ///     writer.write_u16_be(0xABCD)
+    /// }
+    ///
+    /// let mut vec = vec![];
+    /// let mut sizes = vec![];
+    /// vec.const_writer::<6>()
+    ///     .then(tag)
+    ///     .tap(|written| sizes.push(written))
+    ///     .write_u32_le(1)
+    ///     .tap(|written| sizes.push(written));
+    /// assert_eq!(vec, [0xAB, 0xCD, 1, 0, 0, 0]);
+    /// assert_eq!(sizes, [2, 6]);
+    /// ```
+    #[inline(always)]
+    pub fn then<R>(self, f: impl FnOnce(Self) -> R) -> R {
+        f(self)
+    }
+
+    /// Calls `f` with number of bytes written so far and returns writer unchanged
+    #[inline(always)]
+    pub fn tap(self, f: impl FnOnce(usize)) -> Self {
+        f(self.writer_adapter.written_so_far());
+        self
+    }
+
+    /// Passes first `S` bytes of writer to `f`, which must fill them completely.
+    ///
+    /// Lets functions with fixed size writer be called on writer of any length, see [`writer_fn!`].
+    #[inline(always)]
+    pub fn write_with<const S: usize>(self, f: impl FnOnce(ConstWriter<T, S>) -> ConstWriter<T, 0>) -> ConstWriter<T, {N - S}> {
+        let () = Shrink::<N, S>::CHECK;
+        ConstWriter {
+            writer_adapter: f(ConstWriter { writer_adapter: self.writer_adapter }).writer_adapter,
+        }
+    }
+
+}
+
+#[cfg(nightly)]
+impl<T: GrowableAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Increases length of [`ConstWriter`] to `M`, adapter ensures that underlying buffer
+    /// have space for `M` more bytes.
+    ///
+    /// `M` < `N` doesn't compile:
+    /// ```compile_fail
+    /// use const_writer::ConstWrite;
+    ///
+    /// let mut vec = vec![];
+    /// vec.const_writer::<4>().grow_to::<2>();
+    /// ```
+    #[track_caller]
+    pub fn grow_to<const M: usize>(self) -> ConstWriter<T, {M}> {
+        let () = Shrink::<N, M>::GROW_CHECK;
+        unsafe {
+            ConstWriter {
+                writer_adapter: poisoned::<_, M>(self.writer_adapter.grow::<{M}>()),
+            }
+        }
+    }
 }
 
+#[cfg(nightly)]
 impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
     implement_write!(write_u8_le, u8, to_le_bytes);
     implement_write!(write_u16_le, u16, to_le_bytes);
@@ -163,13 +488,182 @@ impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
     implement_write!(write_f32_le, f32, to_le_bytes);
     implement_write!(write_f64_le, f64, to_le_bytes);
 
+    /// Writes low 3 bytes of `value` big endian, e.g. TLS handshake length
+    ///
+    /// # Panics
+    /// If `value` doesn't fit in 24 bits
+    #[inline(always)]
+    #[track_caller]
+    pub fn write_u24_be(self, value: u32) -> ConstWriter<T, {N - 3}> {
+        assert!(value <= 0xFF_FFFF, "value doesn't fit in 24 bits: {}", value);
+        let bytes = value.to_be_bytes();
+        unsafe {
+            ConstWriter {
+                writer_adapter: self.writer_adapter.write(&[bytes[1], bytes[2], bytes[3]]),
+            }
+        }
+    }
+
+    /// Writes low 3 bytes of `value` little endian
+    ///
+    /// # Panics
+    /// If `value` doesn't fit in 24 bits
+    #[inline(always)]
+    #[track_caller]
+    pub fn write_u24_le(self, value: u32) -> ConstWriter<T, {N - 3}> {
+        assert!(value <= 0xFF_FFFF, "value doesn't fit in 24 bits: {}", value);
+        let bytes = value.to_le_bytes();
+        unsafe {
+            ConstWriter {
+                writer_adapter: self.writer_adapter.write(&[bytes[0], bytes[1], bytes[2]]),
+            }
+        }
+    }
+
+    /// Writes low 6 bytes of `value` big endian, e.g. DTLS sequence number
+    ///
+    /// # Panics
+    /// If `value` doesn't fit in 48 bits
+    #[inline(always)]
+    #[track_caller]
+    pub fn write_u48_be(self, value: u64) -> ConstWriter<T, {N - 6}> {
+        assert!(value < 1 << 48, "value doesn't fit in 48 bits: {}", value);
+        let bytes = value.to_be_bytes();
+        unsafe {
+            ConstWriter {
+                writer_adapter: self.writer_adapter.write(&[bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]),
+            }
+        }
+    }
+
+    /// Writes low 6 bytes of `value` little endian
+    ///
+    /// # Panics
+    /// If `value` doesn't fit in 48 bits
+    #[inline(always)]
+    #[track_caller]
+    pub fn write_u48_le(self, value: u64) -> ConstWriter<T, {N - 6}> {
+        assert!(value < 1 << 48, "value doesn't fit in 48 bits: {}", value);
+        let bytes = value.to_le_bytes();
+        unsafe {
+            ConstWriter {
+                writer_adapter: self.writer_adapter.write(&[bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5]]),
+            }
+        }
+    }
+
     /// Helper to access const_generic param
     pub fn remaining(&self) -> usize {
         N
     }
+
+    /// Number of bytes written since writer was created
+    pub fn written_so_far(&self) -> usize {
+        self.writer_adapter.written_so_far()
+    }
+}
+
+#[cfg(nightly)]
+macro_rules! implement_write_atomic {
+    ($width:literal, $($name:ident, $atomic:ident, $type:ty, $endian:ident;)*) => {
+        $(
+            /// Writes snapshot of atomic counter, loaded with `order`
+            #[cfg(target_has_atomic = $width)]
+            #[inline(always)]
+            #[cfg_attr(any(debug_assertions, feature = "stats"), track_caller)]
+            pub fn $name(self, value: &core::sync::atomic::$atomic, order: core::sync::atomic::Ordering) -> ConstWriter<T, {N - core::mem::size_of::<$type>()}> {
+                unsafe {
+                    ConstWriter {
+                        writer_adapter: self.writer_adapter.write(&value.load(order).$endian()),
+                    }
+                }
+            }
+        )*
+    }
+}
+
+/// Atomic counters, e.g. for stats frames:
+/// ```
+/// use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+/// use const_writer::ConstWrite;
+///
+/// let requests = AtomicU64::new(3);
+/// let errors = AtomicU32::new(1);
+/// let mut vec = vec![];
+/// vec.const_writer::<12>()
+///     .write_u64_le_from(&requests, Ordering::Relaxed)
+///     .write_u32_be_from(&errors, Ordering::Relaxed);
+/// assert_eq!(vec, [3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+/// ```
+#[cfg(nightly)]
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    implement_write_atomic!("8",
+        write_u8_le_from, AtomicU8, u8, to_le_bytes;
+        write_u8_be_from, AtomicU8, u8, to_be_bytes;
+        write_i8_le_from, AtomicI8, i8, to_le_bytes;
+        write_i8_be_from, AtomicI8, i8, to_be_bytes;
+    );
+    implement_write_atomic!("16",
+        write_u16_le_from, AtomicU16, u16, to_le_bytes;
+        write_u16_be_from, AtomicU16, u16, to_be_bytes;
+        write_i16_le_from, AtomicI16, i16, to_le_bytes;
+        write_i16_be_from, AtomicI16, i16, to_be_bytes;
+    );
+    implement_write_atomic!("32",
+        write_u32_le_from, AtomicU32, u32, to_le_bytes;
+        write_u32_be_from, AtomicU32, u32, to_be_bytes;
+        write_i32_le_from, AtomicI32, i32, to_le_bytes;
+        write_i32_be_from, AtomicI32, i32, to_be_bytes;
+    );
+    implement_write_atomic!("64",
+        write_u64_le_from, AtomicU64, u64, to_le_bytes;
+        write_u64_be_from, AtomicU64, u64, to_be_bytes;
+        write_i64_le_from, AtomicI64, i64, to_le_bytes;
+        write_i64_be_from, AtomicI64, i64, to_be_bytes;
+    );
 }
 
+/// Defines function which writes fixed number of bytes to [`ConstWriter`] of any length.
+///
+/// Generates adapter param and `{N - SIZE}` bounds, body gets writer of exactly `SIZE` bytes
+/// and must fill it. `SIZE` goes after the arrow, expressions need braces.
+/// ```
+/// #![feature(generic_const_exprs)]
+/// #![allow(incomplete_features)]
+/// use const_writer::{writer_fn, ConstWrite};
+///
+/// writer_fn! {
+///     /// Writes packet header
+///     fn write_header(writer, id: u16, len: u32) -> 6 {
+///         writer.write_u16_be(id).write_u32_be(len)
+///     }
+/// }
+///
+/// let mut vec = vec![];
+/// write_header(vec.const_writer::<8>(), 1, 2).write_u16_be(3);
+/// assert_eq!(vec, [0, 1, 0, 0, 0, 2, 0, 3]);
+/// ```
+#[cfg(nightly)]
+#[macro_export]
+macro_rules! writer_fn {
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident($writer:ident $(, $arg:ident : $arg_ty:ty)* $(,)?) -> $size:tt { $($body:tt)* }
+    ) => {
+        $(#[$meta])*
+        $vis fn $name<T: $crate::ConstWriterAdapter, const N: usize>(
+            $writer: $crate::ConstWriter<T, N> $(, $arg: $arg_ty)*
+        ) -> $crate::ConstWriter<T, { N - $size }>
+        where [(); N - $size]: {
+            $writer.write_with(|$writer: $crate::ConstWriter<T, { $size }>| -> $crate::ConstWriter<T, 0> { $($body)* })
+        }
+    };
+}
+
+#[cfg(nightly)]
 impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    #[inline(always)]
+    #[cfg_attr(any(debug_assertions, feature = "stats"), track_caller)]
     pub fn write_slice<const M: usize>(self, value: &[u8; M]) -> ConstWriter<T, { N-M }> {
         unsafe {
             ConstWriter {
@@ -178,9 +672,225 @@ impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
             }
         }
     }
+
+    /// Copies exactly `M` bytes from start of `src`.
+    ///
+    /// Returns [`CapacityError`] if `src` is shorter than `M`, nothing is written then.
+    /// ```
+    /// use const_writer::ConstWrite;
+    ///
+    /// let payload = [1u8, 2, 3, 4, 5];
+    /// let mut vec = vec![];
+    /// vec.const_writer::<5>()
+    ///     .write_u8_le(0)
+    ///     .write_from_slice_runtime::<4>(&payload)
+    ///     .unwrap();
+    /// assert_eq!(vec, [0, 1, 2, 3, 4]);
+    /// ```
+    #[cfg_attr(any(debug_assertions, feature = "stats"), track_caller)]
+    pub fn write_from_slice_runtime<const M: usize>(self, src: &[u8]) -> Result<ConstWriter<T, { N - M }>, CapacityError> {
+        match src.get(..M).and_then(|value| core::convert::TryFrom::try_from(value).ok()) {
+            Some(value) => Ok(self.write_slice(value)),
+            None => Err(CapacityError { requested: M, available: src.len() }),
+        }
+    }
+
+    /// Reads exactly `M` bytes from `reader` and writes them.
+    ///
+    /// Error of `read_exact` is returned if reader ends early, read bytes are discarded then.
+    #[cfg(feature = "std")]
+    #[cfg_attr(any(debug_assertions, feature = "stats"), track_caller)]
+    pub fn write_from<const M: usize, R: std::io::Read + ?Sized>(self, reader: &mut R) -> std::io::Result<ConstWriter<T, { N - M }>> {
+        let mut value = [0u8; M];
+        reader.read_exact(&mut value)?;
+        Ok(self.write_slice(&value))
+    }
+
+    /// Writes `M` random bytes from OS CSPRNG, e.g. nonce or salt.
+    ///
+    /// Error is returned if OS generator fails, nothing is written then.
+    /// ```
+    /// use const_writer::ConstWrite;
+    ///
+    /// let mut vec = vec![];
+    /// vec.const_writer::<17>()
+    ///     .write_u8_le(1)
+    ///     .write_random::<16>()
+    ///     .unwrap();
+    /// assert_eq!(vec.len(), 17);
+    /// ```
+    #[cfg(feature = "getrandom")]
+    #[cfg_attr(any(debug_assertions, feature = "stats"), track_caller)]
+    pub fn write_random<const M: usize>(self) -> Result<ConstWriter<T, { N - M }>, getrandom::Error> {
+        let mut value = [0u8; M];
+        getrandom::getrandom(&mut value)?;
+        Ok(self.write_slice(&value))
+    }
+
+    /// Writes record `[len u32 le][payload M bytes][crc32 u32 le]`, payload is written by `f`.
+    ///
+    /// Classic write-ahead log framing, CRC-32 is computed over payload.
+    /// ```
+    /// use const_writer::ConstWrite;
+    ///
+    /// let mut log = vec![];
+    /// log.const_writer::<12>()
+    ///     .write_record::<4>(|w| w.write_u16_be(1).write_u16_be(2));
+    /// assert_eq!(log[..8], [4, 0, 0, 0, 0, 1, 0, 2]);
+    /// assert_eq!(log[8..], const_writer::crc::crc32(&[0, 1, 0, 2]).to_le_bytes());
+    /// ```
+    #[cfg_attr(any(debug_assertions, feature = "stats"), track_caller)]
+    pub fn write_record<const M: usize>(
+        self,
+        f: impl for<'p> FnOnce(ConstWriter<array::ArrayWriterAdapter<'p, M>, M>) -> ConstWriter<array::ArrayWriterAdapter<'p, M>, 0>,
+    ) -> ConstWriter<T, { N - (M + 8) }> {
+        let mut payload = [0u8; M];
+        f(payload.const_writer::<M>());
+        let crc = crc::crc32(&payload);
+        unsafe {
+            ConstWriter {
+                writer_adapter: self.writer_adapter
+                    .write(&(M as u32).to_le_bytes())
+                    .write_raw(&payload) // payload is already counted by writes inside `f`
+                    .write(&crc.to_le_bytes()),
+            }
+        }
+    }
+
+    /// Writes `value` run-length encoded as `(count, byte)` pairs.
+    ///
+    /// Worst case (no repeating bytes) doubles the size, so `2 * M` bytes are taken
+    /// from budget even if actual output is shorter.
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn write_rle<const M: usize>(self, value: &[u8; M]) -> ConstWriter<T, { N - 2 * M }> {
+        let mut writer_adapter = self.writer_adapter;
+        let mut i = 0;
+        while i < M {
+            let byte = value[i];
+            let mut count = 1;
+            while i + count < M && count < u8::MAX as usize && value[i + count] == byte {
+                count += 1;
+            }
+            writer_adapter = unsafe { writer_adapter.write(&[count as u8, byte]) };
+            i += count;
+        }
+        ConstWriter {
+            writer_adapter
+        }
+    }
+
+    /// Writes [`ConstWritable`] value
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn write_value<V: ConstWritable>(self, value: &V) -> ConstWriter<T, { N - V::SIZE }> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: value.write_to(self.writer_adapter),
+            }
+        }
+    }
+
+    /// Writes field of [`const_layout!`] kind, used by [`const_write!`]
+    #[doc(hidden)]
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn write_field<K: layout::FieldKind>(self, value: &K::Value) -> ConstWriter<T, { N - K::SIZE }> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: K::write(value, self.writer_adapter),
+            }
+        }
+    }
+
+    /// Writes fields packed by [`write_all!`], they are already counted by `stats` feature
+    #[doc(hidden)]
+    pub fn write_packed<const M: usize>(self, value: &[u8; M]) -> ConstWriter<T, { N - M }> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: self.writer_adapter.write_raw(value),
+            }
+        }
+    }
+}
+
+/// Value with statically known size, which can be written with [`ConstWriter::write_value`].
+///
+/// Usually implemented with [`const_layout!`] macro.
+///
+/// # Safety
+/// `write_to` must write at most `SIZE` bytes
+#[cfg(nightly)]
+pub unsafe trait ConstWritable {
+    /// Number of bytes written by `write_to`
+    const SIZE: usize;
+
+    /// Writes value using adapter
+    ///
+    /// # Safety
+    /// Underlying buffer must have space for `SIZE` bytes
+    unsafe fn write_to<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T;
+}
+
+/// Locks in wire size of [`ConstWritable`] type, compilation fails if it changes.
+/// ```
+/// const_writer::const_layout! {
+///     struct Header {
+///         kind: u8,
+///         len: u32_be,
+///     }
+/// }
+///
+/// const_writer::assert_writes_exactly!(Header, 5);
+/// ```
+///
+/// ```compile_fail
+/// # const_writer::const_layout! {
+/// #     struct Header {
+/// #         kind: u8,
+/// #         len: u32_be,
+/// #     }
+/// # }
+/// const_writer::assert_writes_exactly!(Header, 4);
+/// ```
+#[cfg(nightly)]
+#[macro_export]
+macro_rules! assert_writes_exactly {
+    ($type:ty, $size:expr $(,)?) => {
+        const _: () = assert!(
+            <$type as $crate::ConstWritable>::SIZE == $size,
+            concat!("wire size of `", stringify!($type), "` isn't ", stringify!($size))
+        );
+    };
+}
+
+/// Checks at compile time that const size expression fits in budget, e.g. frame of several values
+/// fits in MTU.
+/// ```
+/// use const_writer::ConstWritable;
+///
+/// const_writer::const_layout! {
+///     struct Entry {
+///         id: u64_le,
+///     }
+/// }
+///
+/// const_writer::assert_fits!(<Entry as ConstWritable>::SIZE * 4 + 2, 64);
+/// ```
+///
+/// ```compile_fail
+/// const_writer::assert_fits!(1500 + 20, 1500);
+/// ```
+#[cfg(nightly)]
+#[macro_export]
+macro_rules! assert_fits {
+    ($size:expr, $budget:expr $(,)?) => {
+        const _: () = assert!(
+            $size <= $budget,
+            concat!("`", stringify!($size), "` doesn't fit in ", stringify!($budget))
+        );
+    };
 }
 
 /// Get [`ConstWriter`] for given type
+#[cfg(nightly)]
 pub trait ConstWrite<'a, T: ConstWriterAdapter + ConstWriterAdapterCreate<'a, Self>> {
     /// Get [`ConstWriter`] to write `N` bytes.
     ///
@@ -188,12 +898,67 @@ pub trait ConstWrite<'a, T: ConstWriterAdapter + ConstWriterAdapterCreate<'a, Se
     /// can accept at least `N` bytes. And because write methods reduces `N` as they write to buffer
     /// we can be sure that code which writes more than`N` bytes wont compile
     /// (N is usize so negative value will be compile error)
+    #[track_caller]
     fn const_writer<const N: usize>(&'a mut self) -> ConstWriter<T, {N}> {
         unsafe {
             ConstWriter {
-                writer_adapter: T::new::<{ N }>(self)
+                writer_adapter: poisoned::<_, N>(T::new::<{ N }>(self))
             }
         }
     }
 }
 
+
+/// Error returned when buffer is too short for requested writer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError {
+    /// Number of bytes requested
+    pub requested: usize,
+    /// Number of bytes available in buffer
+    pub available: usize,
+}
+
+impl core::fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "buffer too short: {} < {}", self.available, self.requested)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
+
+/// Get [`ConstWriter`] for buffer of limited size without panicking
+#[cfg(nightly)]
+pub trait TryConstWrite<'a, T: ConstWriterAdapter + ConstWriterAdapterCreate<'a, Self>>: ConstWrite<'a, T> {
+    /// Number of bytes writer can be created for
+    fn available(&self) -> usize;
+
+    /// Get [`ConstWriter`] to write `N` bytes or [`CapacityError`] if buffer is too short
+    /// ```
+    /// use const_writer::{ConstWrite, TryConstWrite};
+    ///
+    /// let mut buff = [0u8; 4];
+    /// let mut slice = buff.as_mut() as &mut [u8];
+    /// let err = slice.try_const_writer::<8>().err().unwrap();
+    /// assert_eq!((err.requested, err.available), (8, 4));
+    /// slice.try_const_writer::<4>().unwrap().write_u32_le(1);
+    /// ```
+    fn try_const_writer<const N: usize>(&'a mut self) -> Result<ConstWriter<T, {N}>, CapacityError> {
+        let available = self.available();
+        if available < N {
+            return Err(CapacityError { requested: N, available });
+        }
+        Ok(self.const_writer::<N>())
+    }
+
+    /// Get [`ConstWriter`] to write exactly `N` bytes, for arrays use [`array::const_writer_exact`]
+    ///
+    /// # Panics
+    /// If buffer length isn't `N`, trailing slack usually means caller bug
+    #[track_caller]
+    fn const_writer_exact<const N: usize>(&'a mut self) -> ConstWriter<T, {N}> {
+        let available = self.available();
+        assert_eq!(available, N, "buffer length isn't exact: {} != {}", available, N);
+        self.const_writer::<N>()
+    }
+}
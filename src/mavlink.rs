@@ -0,0 +1,102 @@
+//! [MAVLink v2](https://mavlink.io/en/guide/serialization.html) frame helper, enabled with `mavlink` feature.
+//!
+//! Payload is written by closure into budget of `LEN` bytes, frame takes `LEN + 12` bytes.
+//! Signed frames and payload truncation aren't supported.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//! use const_writer::mavlink::{self, FrameHeader};
+//!
+//! let header = FrameHeader { seq: 1, sys_id: 255, comp_id: 190, msg_id: 0 };
+//! let mut vec = vec![];
+//! // HEARTBEAT, CRC_EXTRA of message is 50
+//! mavlink::write_frame::<9, _, _>(vec.const_writer::<21>(), &header, 50, |payload| payload
+//!     .write_u32_le(0) // custom_mode
+//!     .write_u8_le(6) // type
+//!     .write_u8_le(8) // autopilot
+//!     .write_u8_le(192) // base_mode
+//!     .write_u8_le(4) // system_status
+//!     .write_u8_le(3)); // mavlink_version
+//! assert_eq!(vec[..10], [0xFD, 9, 0, 0, 1, 255, 190, 0, 0, 0]);
+//! assert_eq!(vec.len(), 21);
+//! ```
+use crate::{ConstWrite, ConstWriter, ConstWriterAdapter};
+use crate::array::ArrayWriterAdapter;
+use crate::crc::CrcX25;
+
+/// Start of MAVLink v2 frame
+pub const MAGIC: u8 = 0xFD;
+
+/// Compile time check of payload length
+struct PayloadLen<const LEN: usize>;
+
+impl<const LEN: usize> PayloadLen<LEN> {
+    const CHECK: () = assert!(LEN <= 255, "MAVLink payload is up to 255 bytes");
+}
+
+/// Fields of frame header, flags are always zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHeader {
+    pub seq: u8,
+    pub sys_id: u8,
+    pub comp_id: u8,
+    /// 24 bit message id
+    pub msg_id: u32,
+}
+
+/// Writes frame with `LEN` bytes of payload written by `payload`.
+///
+/// `crc_extra` is seed byte of message from its XML definition.
+///
+/// # Panics
+/// If `msg_id` doesn't fit in 24 bits
+#[track_caller]
+pub fn write_frame<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    header: &FrameHeader,
+    crc_extra: u8,
+    payload: impl for<'p> FnOnce(ConstWriter<ArrayWriterAdapter<'p, LEN>, LEN>) -> ConstWriter<ArrayWriterAdapter<'p, LEN>, 0>,
+) -> ConstWriter<T, {N - (LEN + 12)}> {
+    let () = PayloadLen::<LEN>::CHECK;
+    assert!(header.msg_id <= 0xFF_FFFF, "message id doesn't fit in 24 bits: {}", header.msg_id);
+
+    let msg_id = header.msg_id.to_le_bytes();
+    let head = [
+        MAGIC, LEN as u8, 0, 0,
+        header.seq, header.sys_id, header.comp_id,
+        msg_id[0], msg_id[1], msg_id[2],
+    ];
+    let mut body = [0u8; LEN];
+    payload(body.const_writer::<LEN>());
+    let crc = CrcX25::new()
+        .update(&head[1..])
+        .update(&body)
+        .update(&[crc_extra])
+        .finish();
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&head)
+                .write(&body)
+                .write(&crc.to_le_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use crate::crc::crc16_mcrf4xx;
+
+    #[test]
+    fn mavlink_frame_crc() {
+        let header = super::FrameHeader { seq: 7, sys_id: 1, comp_id: 1, msg_id: 0x012345 };
+        let mut buff = [0u8; 14];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_frame::<2, _, _>(ref_buff.const_writer::<14>(), &header, 0xAB, |p| p.write_u16_be(0xBEEF));
+        assert_eq!(buff[..12], [0xFD, 2, 0, 0, 7, 1, 1, 0x45, 0x23, 0x01, 0xBE, 0xEF]);
+        let mut checked = buff[1..12].to_vec();
+        checked.push(0xAB);
+        assert_eq!(buff[12..], crc16_mcrf4xx(&checked).to_le_bytes());
+    }
+}
@@ -0,0 +1,172 @@
+//! Headers of simple media formats, so small encoders can be written with compile time budgets.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::media::{bmp, png, wav};
+//!
+//! // one second of 8 kHz mono 16 bit silence
+//! let format = wav::Format { channels: 1, sample_rate: 8000, bits_per_sample: 16 };
+//! let mut vec = vec![];
+//! wav::write_riff_header(vec.const_writer::<44>(), &format, 16000);
+//! assert_eq!(vec[..12], *b"RIFF\xA4\x3E\0\0WAVE");
+//!
+//! // 1x1 24 bit image, rows are padded to 4 bytes
+//! let mut vec = vec![];
+//! let writer = bmp::write_file_header(vec.const_writer::<58>(), 58, 54);
+//! bmp::write_info_header(writer, 1, 1, 24, 4).write_slice(&[0, 0, 0xFF, 0]);
+//! assert_eq!(vec[..6], [b'B', b'M', 58, 0, 0, 0]);
+//!
+//! let mut vec = vec![];
+//! png::write_chunk::<0, _, _>(vec.const_writer::<12>(), *b"IEND", |data| data);
+//! assert_eq!(vec, [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]);
+//! ```
+
+/// WAVE file with PCM samples
+pub mod wav {
+    use crate::{ConstWriter, ConstWriterAdapter};
+
+    /// PCM sample format
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Format {
+        pub channels: u16,
+        pub sample_rate: u32,
+        pub bits_per_sample: u16,
+    }
+
+    /// Writes 44 byte RIFF header with `fmt ` chunk and header of `data` chunk with `data_len` bytes
+    ///
+    /// # Panics
+    /// If `data_len` is too big for RIFF size field
+    #[track_caller]
+    pub fn write_riff_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, format: &Format, data_len: u32) -> ConstWriter<T, {N - 44}> {
+        assert!(data_len <= u32::MAX - 36, "WAV data too long: {}", data_len);
+        let block_align = format.channels * format.bits_per_sample.div_ceil(8);
+        let byte_rate = format.sample_rate * block_align as u32;
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter
+                    .write(b"RIFF")
+                    .write(&(36 + data_len).to_le_bytes())
+                    .write(b"WAVEfmt ")
+                    .write(&16u32.to_le_bytes())
+                    // PCM
+                    .write(&1u16.to_le_bytes())
+                    .write(&format.channels.to_le_bytes())
+                    .write(&format.sample_rate.to_le_bytes())
+                    .write(&byte_rate.to_le_bytes())
+                    .write(&block_align.to_le_bytes())
+                    .write(&format.bits_per_sample.to_le_bytes())
+                    .write(b"data")
+                    .write(&data_len.to_le_bytes()),
+            }
+        }
+    }
+}
+
+/// Uncompressed Windows bitmap
+pub mod bmp {
+    use crate::{ConstWriter, ConstWriterAdapter};
+
+    /// Writes 14 byte file header
+    pub fn write_file_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, file_size: u32, pixel_offset: u32) -> ConstWriter<T, {N - 14}> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter
+                    .write(b"BM")
+                    .write(&file_size.to_le_bytes())
+                    .write(&[0; 4])
+                    .write(&pixel_offset.to_le_bytes()),
+            }
+        }
+    }
+
+    /// Writes 40 byte `BITMAPINFOHEADER` without compression, negative `height` means top-down rows
+    pub fn write_info_header<T: ConstWriterAdapter, const N: usize>(
+        writer: ConstWriter<T, {N}>,
+        width: i32,
+        height: i32,
+        bits_per_pixel: u16,
+        image_size: u32,
+    ) -> ConstWriter<T, {N - 40}> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter
+                    .write(&40u32.to_le_bytes())
+                    .write(&width.to_le_bytes())
+                    .write(&height.to_le_bytes())
+                    // planes
+                    .write(&1u16.to_le_bytes())
+                    .write(&bits_per_pixel.to_le_bytes())
+                    // compression
+                    .write(&0u32.to_le_bytes())
+                    .write(&image_size.to_le_bytes())
+                    // resolution and palette
+                    .write(&[0; 16]),
+            }
+        }
+    }
+}
+
+/// PNG chunks
+pub mod png {
+    use crate::{ConstWrite, ConstWriter, ConstWriterAdapter};
+    use crate::array::ArrayWriterAdapter;
+    use crate::crc::Crc32;
+
+    /// Signature at start of every PNG file
+    pub const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+    /// Writes length and type of chunk with `LEN` bytes of data. Data and CRC of type
+    /// and data must follow, so returned writer has space for `LEN + 4` more bytes.
+    pub fn write_chunk_header<const LEN: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, chunk_type: [u8; 4]) -> ConstWriter<T, {N - 8}> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter
+                    .write(&(LEN as u32).to_be_bytes())
+                    .write(&chunk_type),
+            }
+        }
+    }
+
+    /// Writes whole chunk with `LEN` bytes of data written by `data`
+    pub fn write_chunk<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+        writer: ConstWriter<T, {N}>,
+        chunk_type: [u8; 4],
+        data: impl for<'d> FnOnce(ConstWriter<ArrayWriterAdapter<'d, LEN>, LEN>) -> ConstWriter<ArrayWriterAdapter<'d, LEN>, 0>,
+    ) -> ConstWriter<T, {N - (LEN + 12)}> {
+        let mut body = [0u8; LEN];
+        data(body.const_writer::<LEN>());
+        let crc = Crc32::new().update(&chunk_type).update(&body).finish();
+        unsafe {
+            ConstWriter {
+                writer_adapter: writer.writer_adapter
+                    .write(&(LEN as u32).to_be_bytes())
+                    .write(&chunk_type)
+                    .write(&body)
+                    .write(&crc.to_be_bytes()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::png;
+    use crate::ConstWrite;
+    use crate::crc::Crc32;
+
+    #[test]
+    fn png_chunk_header_crc_slot() {
+        let mut buff = [0u8; 25];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let ihdr = [0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0];
+        let crc = Crc32::new().update(b"IHDR").update(&ihdr).finish();
+        png::write_chunk_header::<13, _, _>(ref_buff.const_writer::<25>(), *b"IHDR")
+            .write_slice(&ihdr)
+            .write_u32_be(crc);
+        assert_eq!(buff[..8], [0, 0, 0, 13, b'I', b'H', b'D', b'R']);
+        assert_eq!(buff[21..], [0x3A, 0x7E, 0x9B, 0x55]);
+    }
+}
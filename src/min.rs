@@ -0,0 +1,153 @@
+//! Reduced API which needs only `min_const_generics`, so it builds on stable and older compilers.
+//!
+//! Remaining length can't be calculated in types, so caller specifies it for every write
+//! and it is checked with const assertion at compile time.
+//!
+//! ```
+//! use const_writer::min::{MinConstWrite, MinConstWriter};
+//! use const_writer::ConstWriterAdapter;
+//!
+//! fn write_header<T: ConstWriterAdapter>(writer: MinConstWriter<T, 6>) -> MinConstWriter<T, 0> {
+//!     writer.write_u16_be::<4>(1).write_u32_le(2)
+//! }
+//!
+//! let mut buff = [0u8; 6];
+//! let mut slice = buff.as_mut() as &mut [u8];
+//! write_header(slice.min_const_writer::<6>());
+//! assert_eq!(buff, [0, 1, 2, 0, 0, 0]);
+//! ```
+//!
+//! Wrong remaining length doesn't compile:
+//! ```compile_fail
+//! use const_writer::min::MinConstWrite;
+//!
+//! let mut buff = [0u8; 6];
+//! let mut slice = buff.as_mut() as &mut [u8];
+//! slice.min_const_writer::<6>().write_u32_le::<4>(1);
+//! ```
+use crate::{ConstWriterAdapter, ConstWriterAdapterCreate};
+use crate::reserve::{ReserveAdapter, ReserveBuffer};
+use crate::slice::SliceWriterAdapter;
+
+struct Rest<const N: usize, const SIZE: usize, const M: usize>;
+
+impl<const N: usize, const SIZE: usize, const M: usize> Rest<N, SIZE, M> {
+    const CHECK: () = assert!(N >= SIZE && N - SIZE == M, "remaining length doesn't match written size");
+}
+
+///
+/// Writer that keeping track of space left, remaining length is specified by caller.
+///
+pub struct MinConstWriter<T: ConstWriterAdapter, const N: usize> {
+    writer_adapter: T,
+}
+
+macro_rules! implement_write {
+    ($name:ident, $type:ty, $endian:ident) => {
+        pub fn $name<const M: usize>(self, value: $type) -> MinConstWriter<T, M> {
+            let () = Rest::<N, { core::mem::size_of::<$type>() }, M>::CHECK;
+            unsafe {
+                MinConstWriter {
+                    writer_adapter: self.writer_adapter.write(&value.$endian()),
+                }
+            }
+        }
+    }
+}
+
+impl<T: ConstWriterAdapter, const N: usize> MinConstWriter<T, N> {
+    /// Changes length of [`MinConstWriter`] to `M`, growing buffer if `M` > `N`
+    #[track_caller]
+    pub fn convert<const M: usize>(self) -> MinConstWriter<T, M> {
+        if M <= N {
+            MinConstWriter {
+                writer_adapter: self.writer_adapter,
+            }
+        } else {
+            unsafe {
+                MinConstWriter {
+                    writer_adapter: self.writer_adapter.grow::<M>(),
+                }
+            }
+        }
+    }
+
+    /// Helper to access const_generic param
+    pub fn remaining(&self) -> usize {
+        N
+    }
+
+    /// Writes `S` bytes leaving `M` remaining
+    pub fn write_slice<const S: usize, const M: usize>(self, value: &[u8; S]) -> MinConstWriter<T, M> {
+        let () = Rest::<N, S, M>::CHECK;
+        unsafe {
+            MinConstWriter {
+                writer_adapter: self.writer_adapter.write(value),
+            }
+        }
+    }
+
+    implement_write!(write_u8_le, u8, to_le_bytes);
+    implement_write!(write_u16_le, u16, to_le_bytes);
+    implement_write!(write_u32_le, u32, to_le_bytes);
+    implement_write!(write_u64_le, u64, to_le_bytes);
+    implement_write!(write_u128_le, u128, to_le_bytes);
+
+    implement_write!(write_i8_le, i8, to_le_bytes);
+    implement_write!(write_i16_le, i16, to_le_bytes);
+    implement_write!(write_i32_le, i32, to_le_bytes);
+    implement_write!(write_i64_le, i64, to_le_bytes);
+    implement_write!(write_i128_le, i128, to_le_bytes);
+
+    implement_write!(write_u8_be, u8, to_be_bytes);
+    implement_write!(write_u16_be, u16, to_be_bytes);
+    implement_write!(write_u32_be, u32, to_be_bytes);
+    implement_write!(write_u64_be, u64, to_be_bytes);
+    implement_write!(write_u128_be, u128, to_be_bytes);
+
+    implement_write!(write_i8_be, i8, to_be_bytes);
+    implement_write!(write_i16_be, i16, to_be_bytes);
+    implement_write!(write_i32_be, i32, to_be_bytes);
+    implement_write!(write_i64_be, i64, to_be_bytes);
+    implement_write!(write_i128_be, i128, to_be_bytes);
+
+    implement_write!(write_f32_le, f32, to_le_bytes);
+    implement_write!(write_f64_le, f64, to_le_bytes);
+    implement_write!(write_f32_be, f32, to_be_bytes);
+    implement_write!(write_f64_be, f64, to_be_bytes);
+}
+
+/// Get [`MinConstWriter`] for given type
+pub trait MinConstWrite<'a, T: ConstWriterAdapter + ConstWriterAdapterCreate<'a, Self>> {
+    /// Get [`MinConstWriter`] to write `N` bytes.
+    #[track_caller]
+    fn min_const_writer<const N: usize>(&'a mut self) -> MinConstWriter<T, N> {
+        unsafe {
+            MinConstWriter {
+                writer_adapter: T::new::<N>(self)
+            }
+        }
+    }
+}
+
+impl<'a, 'inner> MinConstWrite<'a, SliceWriterAdapter<'a, 'inner>> for &'inner mut [u8] {}
+
+impl<'a, B: ReserveBuffer + ?Sized> MinConstWrite<'a, ReserveAdapter<'a, B>> for B {}
+
+#[cfg(test)]
+mod tests {
+    use super::MinConstWrite;
+
+    #[test]
+    fn min_write_grow() {
+        let mut buff = [0u8; 8];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        ref_buff.min_const_writer::<2>()
+            .write_u16_le::<0>(1)
+            .convert::<6>()
+            .write_slice::<2, 4>(&[2, 3])
+            .write_f32_be::<0>(0.0);
+        assert_eq!(ref_buff.len(), 0);
+        assert_eq!(buff, [1, 0, 2, 3, 0, 0, 0, 0]);
+    }
+}
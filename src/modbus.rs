@@ -0,0 +1,151 @@
+//! [Modbus](https://modbus.org/specs.php) request PDUs with RTU and TCP framing.
+//!
+//! PDU is written by closure into budget of `LEN` bytes, RTU frame adds unit id and CRC
+//! (`LEN + 3` bytes), TCP frame adds MBAP header (`LEN + 7` bytes).
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::modbus;
+//!
+//! let mut vec = vec![];
+//! modbus::write_rtu::<5, _, _>(vec.const_writer::<8>(), 1, |pdu| modbus::write_read_holding_request(pdu, 0, 10));
+//! assert_eq!(vec, [1, 0x03, 0, 0, 0, 10, 0xC5, 0xCD]);
+//!
+//! let mut vec = vec![];
+//! modbus::write_tcp::<10, _, _>(vec.const_writer::<17>(), 0x0102, 1, |pdu| {
+//!     modbus::write_write_multiple_header::<2, _, _>(pdu, 0x10)
+//!         .write_u16_be(1)
+//!         .write_u16_be(2)
+//! });
+//! assert_eq!(vec[..7], [1, 2, 0, 0, 0, 11, 1]);
+//! assert_eq!(vec[7..], [0x10, 0, 0x10, 0, 2, 4, 0, 1, 0, 2]);
+//! ```
+use crate::{ConstWrite, ConstWriter, ConstWriterAdapter};
+use crate::array::ArrayWriterAdapter;
+use crate::crc::crc16_modbus;
+
+/// Compile time check of PDU length
+struct PduLen<const LEN: usize>;
+
+impl<const LEN: usize> PduLen<LEN> {
+    const CHECK: () = assert!(LEN >= 1 && LEN <= 253, "Modbus PDU is 1 to 253 bytes");
+}
+
+/// Compile time check of register count in write multiple registers request
+struct WriteCount<const COUNT: usize>;
+
+impl<const COUNT: usize> WriteCount<COUNT> {
+    const CHECK: () = assert!(COUNT >= 1 && COUNT <= 123, "write multiple registers takes 1 to 123 registers");
+}
+
+/// Writes RTU frame: unit id, `LEN` bytes of PDU written by `pdu` and CRC
+pub fn write_rtu<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    unit: u8,
+    pdu: impl for<'p> FnOnce(ConstWriter<ArrayWriterAdapter<'p, LEN>, LEN>) -> ConstWriter<ArrayWriterAdapter<'p, LEN>, 0>,
+) -> ConstWriter<T, {N - (LEN + 3)}> {
+    let () = PduLen::<LEN>::CHECK;
+    let mut body = [0u8; LEN];
+    pdu(body.const_writer::<LEN>());
+    let crc = crate::crc::CrcModbus::new()
+        .update(&[unit])
+        .update(&body)
+        .finish();
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&[unit])
+                .write(&body)
+                .write(&crc.to_le_bytes()),
+        }
+    }
+}
+
+/// Writes TCP frame: MBAP header and `LEN` bytes of PDU written by `pdu`
+pub fn write_tcp<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    transaction: u16,
+    unit: u8,
+    pdu: impl for<'p> FnOnce(ConstWriter<ArrayWriterAdapter<'p, LEN>, LEN>) -> ConstWriter<ArrayWriterAdapter<'p, LEN>, 0>,
+) -> ConstWriter<T, {N - (LEN + 7)}> {
+    let () = PduLen::<LEN>::CHECK;
+    let transaction = transaction.to_be_bytes();
+    // length counts unit id and PDU, protocol id is always zero
+    let len = (LEN as u16 + 1).to_be_bytes();
+    let mut body = [0u8; LEN];
+    pdu(body.const_writer::<LEN>());
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&[transaction[0], transaction[1], 0, 0, len[0], len[1], unit])
+                .write(&body),
+        }
+    }
+}
+
+fn write_request<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, function: u8, a: u16, b: u16) -> ConstWriter<T, {N - 5}> {
+    let a = a.to_be_bytes();
+    let b = b.to_be_bytes();
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[function, a[0], a[1], b[0], b[1]]),
+        }
+    }
+}
+
+/// Writes read holding registers (0x03) request
+///
+/// # Panics
+/// If `count` isn't in 1..=125
+#[track_caller]
+pub fn write_read_holding_request<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, address: u16, count: u16) -> ConstWriter<T, {N - 5}> {
+    assert!((1..=125).contains(&count), "read registers count must be in 1..=125: {}", count);
+    write_request(writer, 0x03, address, count)
+}
+
+/// Writes read input registers (0x04) request
+///
+/// # Panics
+/// If `count` isn't in 1..=125
+#[track_caller]
+pub fn write_read_input_request<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, address: u16, count: u16) -> ConstWriter<T, {N - 5}> {
+    assert!((1..=125).contains(&count), "read registers count must be in 1..=125: {}", count);
+    write_request(writer, 0x04, address, count)
+}
+
+/// Writes write single register (0x06) request
+pub fn write_write_single_request<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, address: u16, value: u16) -> ConstWriter<T, {N - 5}> {
+    write_request(writer, 0x06, address, value)
+}
+
+/// Writes header of write multiple registers (0x10) request, `COUNT` big endian registers must follow it
+pub fn write_write_multiple_header<const COUNT: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, address: u16) -> ConstWriter<T, {N - 6}> {
+    let () = WriteCount::<COUNT>::CHECK;
+    let address = address.to_be_bytes();
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[0x10, address[0], address[1], 0, COUNT as u8, 2 * COUNT as u8]),
+        }
+    }
+}
+
+/// Checks CRC of RTU frame, `frame` includes CRC
+pub fn check_rtu_crc(frame: &[u8]) -> bool {
+    frame.len() >= 4 && crc16_modbus(frame) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn modbus_rtu_write_single() {
+        let mut buff = [0u8; 8];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_rtu::<5, _, _>(ref_buff.const_writer::<8>(), 0x11, |pdu| super::write_write_single_request(pdu, 1, 3));
+        assert_eq!(buff, [0x11, 0x06, 0, 1, 0, 3, 0x9A, 0x9B]);
+        assert!(super::check_rtu_crc(&buff));
+    }
+}
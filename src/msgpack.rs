@@ -0,0 +1,91 @@
+//! Helpers for subset of [MessagePack](https://github.com/msgpack/msgpack/blob/master/spec.md)
+//! which encoded size is known at compile time.
+//!
+//! ```
+//! use const_writer::{ConstWrite, msgpack};
+//!
+//! let mut vec = vec![];
+//! let writer = msgpack::write_fixarray::<2, _, _>(vec.const_writer::<11>());
+//! let writer = msgpack::write_fixstr(writer, b"id");
+//! msgpack::write_u32(writer, 7);
+//!
+//! assert_eq!(vec, [0x92, 0xa2, b'i', b'd', 0xce, 0, 0, 0, 7]);
+//! ```
+//!
+//! ```compile_fail
+//! use const_writer::{ConstWrite, msgpack};
+//!
+//! let mut vec = vec![];
+//! msgpack::write_fixstr(vec.const_writer::<40>(), &[b'a'; 32]); // fixstr is up to 31 bytes
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Compile time check that `LEN` doesn't exceed `MAX`
+struct MaxLen<const LEN: usize, const MAX: usize>;
+
+impl<const LEN: usize, const MAX: usize> MaxLen<LEN, MAX> {
+    const CHECK: () = assert!(LEN <= MAX, "length doesn't fit in fixed format");
+}
+
+/// Writes positive (`0..=127`) or negative (`-32..=-1`) fixint
+///
+/// # Panics
+/// If `value` is less than `-32`
+pub fn write_fixint<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, value: i8) -> ConstWriter<T, {N - 1}> {
+    assert!(value >= -32, "value doesn't fit in fixint: {}", value);
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&value.to_be_bytes()),
+        }
+    }
+}
+
+/// Writes `value` as uint 32
+pub fn write_u32<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, value: u32) -> ConstWriter<T, {N - 5}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&[0xce])
+                .write(&value.to_be_bytes()),
+        }
+    }
+}
+
+/// Writes fixstr with `LEN` bytes of utf-8 data. `LEN` greater than 31 is compile error.
+pub fn write_fixstr<const LEN: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, value: &[u8; LEN]) -> ConstWriter<T, {N - 1 - LEN}> {
+    let () = MaxLen::<LEN, 31>::CHECK;
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&[0xa0 | LEN as u8])
+                .write(value),
+        }
+    }
+}
+
+/// Writes header of fixarray with `LEN` elements. `LEN` greater than 15 is compile error.
+///
+/// Elements must be written after header.
+pub fn write_fixarray<const LEN: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 1}> {
+    let () = MaxLen::<LEN, 15>::CHECK;
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[0x90 | LEN as u8]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::*;
+
+    #[test]
+    fn msgpack_fixint() {
+        let mut buff = [0u8; 2];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = write_fixint(ref_buff.const_writer::<2>(), 5);
+        write_fixint(writer, -3);
+        assert_eq!(buff, [0x05, 0xfd]);
+    }
+}
@@ -0,0 +1,86 @@
+//! [NBD](https://github.com/NetworkBlockDevice/nbd/blob/master/doc/proto.md) transmission phase
+//! request and simple reply, all fields are big endian.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::nbd::{self, Command, Request};
+//!
+//! let request = Request { flags: 0, command: Command::Read, cookie: 1, offset: 4096, length: 512 };
+//! let mut vec = vec![];
+//! nbd::write_request(vec.const_writer::<28>(), &request);
+//! assert_eq!(vec[..8], [0x25, 0x60, 0x95, 0x13, 0, 0, 0, 0]);
+//! assert_eq!(vec[16..], [0, 0, 0, 0, 0, 0, 0x10, 0, 0, 0, 2, 0]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Magic of request
+pub const REQUEST_MAGIC: u32 = 0x25609513;
+/// Magic of simple reply
+pub const SIMPLE_REPLY_MAGIC: u32 = 0x67446698;
+
+/// Request type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Read = 0,
+    Write = 1,
+    Disconnect = 2,
+    Flush = 3,
+    Trim = 4,
+    Cache = 5,
+    WriteZeroes = 6,
+    BlockStatus = 7,
+}
+
+/// Request fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Request {
+    /// command flags, e.g. `NBD_CMD_FLAG_FUA`
+    pub flags: u16,
+    pub command: Command,
+    /// echoed back by server in reply
+    pub cookie: u64,
+    pub offset: u64,
+    pub length: u32,
+}
+
+/// Writes 28 byte request, data of write command must follow it
+pub fn write_request<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, request: &Request) -> ConstWriter<T, {N - 28}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&REQUEST_MAGIC.to_be_bytes())
+                .write(&request.flags.to_be_bytes())
+                .write(&(request.command as u16).to_be_bytes())
+                .write(&request.cookie.to_be_bytes())
+                .write(&request.offset.to_be_bytes())
+                .write(&request.length.to_be_bytes()),
+        }
+    }
+}
+
+/// Writes 16 byte simple reply, `error` is zero on success, data of read reply must follow it
+pub fn write_simple_reply<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, error: u32, cookie: u64) -> ConstWriter<T, {N - 16}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&SIMPLE_REPLY_MAGIC.to_be_bytes())
+                .write(&error.to_be_bytes())
+                .write(&cookie.to_be_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn nbd_simple_reply() {
+        let mut buff = [0u8; 16];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_simple_reply(ref_buff.const_writer::<16>(), 5, 0x0102);
+        assert_eq!(buff, [0x67, 0x44, 0x66, 0x98, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 1, 2]);
+    }
+}
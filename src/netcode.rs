@@ -0,0 +1,138 @@
+//! Game state snapshots packed into bit budget of single packet.
+//!
+//! Snapshot is written with [`ConstBitWriter`] of whole packet, so layout which doesn't fit
+//! in MTU fails to compile. Entities reserve their worst case, fields which didn't change
+//! are skipped with field mask and their budget is left unused.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::netcode::{self, MTU};
+//!
+//! struct Player { id: u64, position: [f32; 3], health: u64, changed: u64 }
+//!
+//! let players = [
+//!     Player { id: 1, position: [10.0, 0.0, -5.5], health: 100, changed: 0b01 },
+//!     Player { id: 2, position: [0.0, 0.0, 0.0], health: 40, changed: 0b11 },
+//! ];
+//! let mut packet = [0u8; MTU];
+//! let len = netcode::snapshot_writer(&mut packet)
+//!     .write_bits::<6>(players.len() as u64)
+//!     // 64 players of 8 + 2 + 3 * 16 + 7 bits each fit into MTU
+//!     .write_entities::<65, 64>(players.len(), |i, w| {
+//!         let player = &players[i];
+//!         w.write_bits::<8>(player.id)
+//!             .write_bits::<2>(player.changed)
+//!             .write_masked::<48>(player.changed, 0, |w| w.write_position::<16>(player.position, -512.0, 512.0))
+//!             .write_masked::<7>(player.changed, 1, |w| w.write_bits::<7>(player.health))
+//!     })
+//!     .skip()
+//!     .finish();
+//! assert_eq!(len, 17); // 6 + 58 + 65 bits
+//! ```
+//!
+//! ```compile_fail
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::netcode::{self, MTU};
+//!
+//! let mut packet = [0u8; MTU];
+//! // 100 entities of 100 bits don't fit into 1200 bytes
+//! netcode::snapshot_writer(&mut packet).write_entities::<100, 100>(0, |_, w| w.skip());
+//! ```
+use crate::bits::ConstBitWriter;
+
+/// Common safe UDP payload size
+pub const MTU: usize = 1200;
+
+/// Compile time check of quantized width
+struct Quantized<const W: usize>;
+
+impl<const W: usize> Quantized<W> {
+    const CHECK: () = assert!(W >= 1 && W <= 32, "quantized value is 1 to 32 bits");
+}
+
+/// Get [`ConstBitWriter`] of whole packet
+pub fn snapshot_writer<const LEN: usize>(packet: &mut [u8; LEN]) -> ConstBitWriter<'_, {LEN * 8}> {
+    crate::bits::const_bit_writer::<{LEN * 8}>(packet)
+}
+
+/// Maps `value` clamped to `min..=max` to `0..2^W`
+fn quantize<const W: usize>(value: f32, min: f32, max: f32) -> u64 {
+    let () = Quantized::<W>::CHECK;
+    let steps = (1u64 << W) - 1;
+    let value = value.clamp(min, max) as f64;
+    let (min, max) = (min as f64, max as f64);
+    // in f64, steps up to 2^32 - 1 are exact and rounding can't overflow `W` bits
+    (((value - min) / (max - min) * steps as f64 + 0.5) as u64).min(steps)
+}
+
+impl<'a, const BITS: usize> ConstBitWriter<'a, BITS> {
+    /// Writes `value` quantized to `W` bits in range `min..=max`, values out of range are clamped
+    pub fn write_quantized<const W: usize>(mut self, value: f32, min: f32, max: f32) -> ConstBitWriter<'a, {BITS - W}> {
+        self.put(quantize::<W>(value, min, max), W);
+        self.cast()
+    }
+
+    /// Writes 3 coordinates quantized to `W` bits each
+    pub fn write_position<const W: usize>(mut self, position: [f32; 3], min: f32, max: f32) -> ConstBitWriter<'a, {BITS - 3 * W}> {
+        for value in position {
+            self.put(quantize::<W>(value, min, max), W);
+        }
+        self.cast()
+    }
+
+    /// Writes field with `f` if bit `field` of `mask` is set, budget of `W` bits is used anyway
+    pub fn write_masked<const W: usize>(
+        self,
+        mask: u64,
+        field: u32,
+        f: impl FnOnce(ConstBitWriter<'a, W>) -> ConstBitWriter<'a, 0>,
+    ) -> ConstBitWriter<'a, {BITS - W}> {
+        if mask >> field & 1 == 1 {
+            f(self.cast()).cast()
+        } else {
+            self.cast()
+        }
+    }
+
+    /// Writes `count` entities with `f`, reserving `ENTITY` bits for each of `MAX` entities
+    ///
+    /// # Panics
+    /// If `count` is greater than `MAX`
+    #[track_caller]
+    pub fn write_entities<const ENTITY: usize, const MAX: usize>(
+        self,
+        count: usize,
+        mut f: impl FnMut(usize, ConstBitWriter<'a, ENTITY>) -> ConstBitWriter<'a, 0>,
+    ) -> ConstBitWriter<'a, {BITS - ENTITY * MAX}> {
+        assert!(count <= MAX, "too many entities: {} > {}", count, MAX);
+        let mut writer = self.cast::<0>();
+        for i in 0..count {
+            writer = f(i, writer.cast());
+        }
+        writer.cast()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn netcode_quantized_clamps() {
+        let mut buff = [0u8; 3];
+        super::snapshot_writer(&mut buff)
+            .write_quantized::<8>(2.0, -1.0, 1.0)
+            .write_quantized::<8>(-2.0, -1.0, 1.0)
+            .write_quantized::<8>(0.0, -1.0, 1.0);
+        assert_eq!(buff, [0xFF, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn netcode_quantized_max_is_all_ones() {
+        let mut buff = [0u8; 7];
+        super::snapshot_writer(&mut buff)
+            .write_quantized::<24>(1.0, -1.0, 1.0)
+            .write_quantized::<32>(1.0, -1.0, 1.0);
+        assert_eq!(buff, [0xFF; 7]);
+    }
+}
@@ -0,0 +1,186 @@
+//! [Netlink](https://docs.kernel.org/userspace-api/netlink/intro.html) message header and attributes.
+//!
+//! Message is `[nlmsghdr][family header][attributes]`, attribute is `[len u16][type u16][payload]`
+//! padded to 4 bytes, padding isn't counted in `len`. All fields are in native byte order.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::netlink::{self, flags, attr_len, NLMSG_HDRLEN};
+//!
+//! // generic netlink CTRL_CMD_GETFAMILY request for "nl80211"
+//! const LEN: usize = NLMSG_HDRLEN + 4 + attr_len(8);
+//! let mut buff = [0u8; LEN];
+//! let writer = netlink::write_nlmsghdr(buff.const_writer::<LEN>(), LEN as u32, 0x10, flags::REQUEST, 1, 0);
+//! netlink::write_attr(writer.write_slice(&[3, 2, 0, 0]), 2, b"nl80211\0");
+//! assert_eq!(buff[..4], (LEN as u32).to_ne_bytes());
+//! assert_eq!(buff[20..22], 12u16.to_ne_bytes()); // attribute length without padding
+//! ```
+//!
+//! ```compile_fail
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::netlink;
+//!
+//! let mut vec = vec![];
+//! netlink::write_attr(vec.const_writer::<65540>(), 1, &[0; 65535]); // length doesn't fit in u16
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+use crate::aligned::{padding, write_padding};
+
+/// Length of `nlmsghdr`
+pub const NLMSG_HDRLEN: usize = 16;
+/// Length of attribute header
+pub const NLA_HDRLEN: usize = 4;
+
+/// Message types handled by netlink itself
+pub mod msg_type {
+    pub const NOOP: u16 = 1;
+    pub const ERROR: u16 = 2;
+    pub const DONE: u16 = 3;
+    pub const OVERRUN: u16 = 4;
+}
+
+/// Message flags, `NLM_F_*`
+pub mod flags {
+    pub const REQUEST: u16 = 0x01;
+    pub const MULTI: u16 = 0x02;
+    pub const ACK: u16 = 0x04;
+    pub const ECHO: u16 = 0x08;
+    // for GET requests
+    pub const ROOT: u16 = 0x100;
+    pub const MATCH: u16 = 0x200;
+    pub const DUMP: u16 = ROOT | MATCH;
+    // for NEW requests
+    pub const REPLACE: u16 = 0x100;
+    pub const EXCL: u16 = 0x200;
+    pub const CREATE: u16 = 0x400;
+    pub const APPEND: u16 = 0x800;
+}
+
+/// Attribute type flag of nested attributes
+pub const NLA_F_NESTED: u16 = 1 << 15;
+
+/// Length padded to 4 bytes, `NLMSG_ALIGN`/`NLA_ALIGN`
+pub const fn align(len: usize) -> usize {
+    len + padding(len, 4)
+}
+
+/// Length of attribute with `payload_len` bytes of payload, including padding
+pub const fn attr_len(payload_len: usize) -> usize {
+    align(NLA_HDRLEN + payload_len)
+}
+
+/// Compile time check of attribute length
+struct AttrLen<const M: usize>;
+
+impl<const M: usize> AttrLen<M> {
+    const CHECK: () = assert!(NLA_HDRLEN + M <= u16::MAX as usize, "attribute too long for u16 length");
+}
+
+/// Writes message header, `len` includes header
+pub fn write_nlmsghdr<T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    len: u32,
+    msg_type: u16,
+    flags: u16,
+    seq: u32,
+    pid: u32,
+) -> ConstWriter<T, {N - NLMSG_HDRLEN}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&len.to_ne_bytes())
+                .write(&msg_type.to_ne_bytes())
+                .write(&flags.to_ne_bytes())
+                .write(&seq.to_ne_bytes())
+                .write(&pid.to_ne_bytes()),
+        }
+    }
+}
+
+/// Writes attribute header with length of header and `payload_len`
+///
+/// # Panics
+/// If attribute length doesn't fit in `u16`
+#[track_caller]
+pub fn write_attr_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, attr_type: u16, payload_len: usize) -> ConstWriter<T, {N - NLA_HDRLEN}> {
+    let len = NLA_HDRLEN + payload_len;
+    assert!(len <= u16::MAX as usize, "attribute too long: {}", len);
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&(len as u16).to_ne_bytes())
+                .write(&attr_type.to_ne_bytes()),
+        }
+    }
+}
+
+/// Writes attribute with payload and padding to 4 bytes
+pub fn write_attr<T: ConstWriterAdapter, const N: usize, const M: usize>(writer: ConstWriter<T, {N}>, attr_type: u16, payload: &[u8; M]) -> ConstWriter<T, {N - attr_len(M)}> {
+    let () = AttrLen::<M>::CHECK;
+    unsafe {
+        let writer_adapter = writer.writer_adapter
+            .write(&((NLA_HDRLEN + M) as u16).to_ne_bytes())
+            .write(&attr_type.to_ne_bytes())
+            .write(payload);
+        ConstWriter {
+            writer_adapter: write_padding(writer_adapter, padding(M, 4)),
+        }
+    }
+}
+
+/// Writes `u8` attribute with 3 bytes of padding
+pub fn write_attr_u8<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, attr_type: u16, value: u8) -> ConstWriter<T, {N - 8}> {
+    write_attr_bytes(writer, attr_type, [value, 0, 0, 0], 1)
+}
+
+/// Writes `u16` attribute with 2 bytes of padding
+pub fn write_attr_u16<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, attr_type: u16, value: u16) -> ConstWriter<T, {N - 8}> {
+    let [a, b] = value.to_ne_bytes();
+    write_attr_bytes(writer, attr_type, [a, b, 0, 0], 2)
+}
+
+/// Writes `u32` attribute
+pub fn write_attr_u32<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, attr_type: u16, value: u32) -> ConstWriter<T, {N - 8}> {
+    write_attr_bytes(writer, attr_type, value.to_ne_bytes(), 4)
+}
+
+/// Writes header of nested attributes, which `payload_len` bytes must follow
+pub fn write_nested_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, attr_type: u16, payload_len: usize) -> ConstWriter<T, {N - NLA_HDRLEN}> {
+    write_attr_header(writer, attr_type | NLA_F_NESTED, payload_len)
+}
+
+/// Attribute with up to 4 bytes of payload, `bytes` are already padded
+fn write_attr_bytes<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, attr_type: u16, bytes: [u8; 4], len: u16) -> ConstWriter<T, {N - 8}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&(NLA_HDRLEN as u16 + len).to_ne_bytes())
+                .write(&attr_type.to_ne_bytes())
+                .write(&bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::{attr_len, write_attr, write_attr_u16, write_nested_header, NLA_F_NESTED};
+
+    #[test]
+    fn netlink_attr_padding() {
+        assert_eq!((attr_len(0), attr_len(1), attr_len(4), attr_len(5)), (4, 8, 8, 12));
+        let mut buff = [0xFFu8; 24];
+        let writer = write_nested_header(buff.const_writer::<24>(), 1, 20);
+        let writer = write_attr_u16(writer, 2, 0x0102);
+        write_attr(writer, 3, b"abcde");
+        assert_eq!(buff[2..4], (1 | NLA_F_NESTED).to_ne_bytes());
+        assert_eq!(buff[4..6], 6u16.to_ne_bytes());
+        assert_eq!((&buff[8..10], &buff[10..12]), (&0x0102u16.to_ne_bytes()[..], &[0, 0][..]));
+        assert_eq!(buff[12..14], 9u16.to_ne_bytes());
+        assert_eq!(buff[16..], *b"abcde\0\0\0");
+    }
+}
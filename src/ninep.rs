@@ -0,0 +1,118 @@
+//! [9P2000](https://9fans.github.io/plan9port/man/man9/intro.html) message headers.
+//!
+//! Header is little endian `size[4] type[1] tag[2]`, size counts whole message including itself.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::ninep;
+//!
+//! // Tclunk of fid 3
+//! let mut vec = vec![];
+//! ninep::write_message::<4, _, _>(vec.const_writer::<11>(), ninep::TCLUNK, 1, |body| body.write_u32_le(3));
+//! assert_eq!(vec, [11, 0, 0, 0, 120, 1, 0, 3, 0, 0, 0]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+pub const TVERSION: u8 = 100;
+pub const RVERSION: u8 = 101;
+pub const TAUTH: u8 = 102;
+pub const RAUTH: u8 = 103;
+pub const TATTACH: u8 = 104;
+pub const RATTACH: u8 = 105;
+pub const RERROR: u8 = 107;
+pub const TFLUSH: u8 = 108;
+pub const RFLUSH: u8 = 109;
+pub const TWALK: u8 = 110;
+pub const RWALK: u8 = 111;
+pub const TOPEN: u8 = 112;
+pub const ROPEN: u8 = 113;
+pub const TCREATE: u8 = 114;
+pub const RCREATE: u8 = 115;
+pub const TREAD: u8 = 116;
+pub const RREAD: u8 = 117;
+pub const TWRITE: u8 = 118;
+pub const RWRITE: u8 = 119;
+pub const TCLUNK: u8 = 120;
+pub const RCLUNK: u8 = 121;
+pub const TREMOVE: u8 = 122;
+pub const RREMOVE: u8 = 123;
+pub const TSTAT: u8 = 124;
+pub const RSTAT: u8 = 125;
+pub const TWSTAT: u8 = 126;
+pub const RWSTAT: u8 = 127;
+
+/// Tag of `Tversion` message
+pub const NOTAG: u16 = 0xFFFF;
+/// Fid meaning no fid, e.g. `afid` of `Tattach` without authentication
+pub const NOFID: u32 = 0xFFFF_FFFF;
+
+/// Server's unique identification of file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Qid {
+    pub qid_type: u8,
+    pub version: u32,
+    pub path: u64,
+}
+
+#[track_caller]
+fn header(message_type: u8, tag: u16, body_len: usize) -> [u8; 7] {
+    assert!(body_len <= u32::MAX as usize - 7, "9P message body too long: {}", body_len);
+    let size = (body_len as u32 + 7).to_le_bytes();
+    let tag = tag.to_le_bytes();
+    [size[0], size[1], size[2], size[3], message_type, tag[0], tag[1]]
+}
+
+/// Writes header of message with `body_len` bytes of body
+///
+/// # Panics
+/// If message size doesn't fit in `u32`
+#[track_caller]
+pub fn write_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, message_type: u8, tag: u16, body_len: usize) -> ConstWriter<T, {N - 7}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&header(message_type, tag, body_len)),
+        }
+    }
+}
+
+/// Writes message with `LEN` bytes of body written by `body`
+pub fn write_message<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    message_type: u8,
+    tag: u16,
+    body: impl FnOnce(ConstWriter<T, LEN>) -> ConstWriter<T, 0>,
+) -> ConstWriter<T, {N - (LEN + 7)}> {
+    let writer_adapter = unsafe { writer.writer_adapter.write(&header(message_type, tag, LEN)) };
+    ConstWriter {
+        writer_adapter: body(ConstWriter { writer_adapter }).writer_adapter,
+    }
+}
+
+/// Writes 13 byte qid
+pub fn write_qid<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, qid: &Qid) -> ConstWriter<T, {N - 13}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&[qid.qid_type])
+                .write(&qid.version.to_le_bytes())
+                .write(&qid.path.to_le_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn ninep_rattach_qid() {
+        let qid = super::Qid { qid_type: 0x80, version: 1, path: 2 };
+        let mut buff = [0u8; 20];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_message::<13, _, _>(ref_buff.const_writer::<20>(), super::RATTACH, 5, |body| super::write_qid(body, &qid));
+        assert_eq!(buff[..8], [20, 0, 0, 0, 105, 5, 0, 0x80]);
+        assert_eq!(buff[12..], [2, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
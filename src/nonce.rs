@@ -0,0 +1,139 @@
+//! Sequence numbers and AEAD nonces of DTLS, TLS and SRTP.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::nonce;
+//!
+//! // DTLS 1.2 record: epoch and 48 bit sequence number
+//! let mut buff = [0u8; 8];
+//! nonce::write_dtls_sequence(buff.const_writer::<8>(), 1, 0x0102_0304_0506);
+//! assert_eq!(buff, [0, 1, 1, 2, 3, 4, 5, 6]);
+//!
+//! // TLS 1.2 AES-GCM nonce: 4 byte implicit salt and 8 byte explicit counter
+//! let mut buff = [0u8; 12];
+//! nonce::write_partially_implicit::<4, 8, _, _>(buff.const_writer::<12>(), &[0xA, 0xB, 0xC, 0xD], 7);
+//! assert_eq!(buff, [0xA, 0xB, 0xC, 0xD, 0, 0, 0, 0, 0, 0, 0, 7]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Compile time check of counter length
+struct CounterLen<const EXPLICIT: usize>;
+
+impl<const EXPLICIT: usize> CounterLen<EXPLICIT> {
+    const CHECK: () = assert!(EXPLICIT >= 1 && EXPLICIT <= 8, "counter is 1 to 8 bytes");
+}
+
+/// Compile time check of nonce length
+struct NonceLen<const L: usize>;
+
+impl<const L: usize> NonceLen<L> {
+    const CHECK: () = assert!(L >= 8, "nonce must hold 64 bit sequence number");
+}
+
+/// Writes 2 byte epoch and 48 bit sequence number of DTLS 1.2 record
+///
+/// # Panics
+/// If `sequence` doesn't fit in 48 bits
+#[track_caller]
+pub fn write_dtls_sequence<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, epoch: u16, sequence: u64) -> ConstWriter<T, {N - 8}> {
+    assert!(sequence < 1 << 48, "value doesn't fit in 48 bits: {}", sequence);
+    let value = (epoch as u64) << 48 | sequence;
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&value.to_be_bytes()),
+        }
+    }
+}
+
+/// Writes nonce of fixed `implicit` part and low `EXPLICIT` bytes of `counter`, big endian
+///
+/// # Panics
+/// If `counter` doesn't fit in `EXPLICIT` bytes
+#[track_caller]
+pub fn write_partially_implicit<const IMPLICIT: usize, const EXPLICIT: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    implicit: &[u8; IMPLICIT],
+    counter: u64,
+) -> ConstWriter<T, {N - (IMPLICIT + EXPLICIT)}> {
+    let () = CounterLen::<EXPLICIT>::CHECK;
+    assert!(
+        EXPLICIT == 8 || counter >> (8 * EXPLICIT) == 0,
+        "counter doesn't fit in {} bytes: {}",
+        EXPLICIT,
+        counter
+    );
+    let counter = counter.to_be_bytes();
+    let mut explicit = [0u8; EXPLICIT];
+    explicit.copy_from_slice(&counter[8 - EXPLICIT..]);
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(implicit)
+                .write(&explicit),
+        }
+    }
+}
+
+/// Writes per-record nonce of TLS 1.3 and DTLS 1.3: `iv` XOR left padded 64 bit `sequence`
+pub fn write_xor_nonce<const L: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, iv: &[u8; L], sequence: u64) -> ConstWriter<T, {N - L}> {
+    let () = NonceLen::<L>::CHECK;
+    let mut nonce = *iv;
+    for (byte, seq) in nonce[L - 8..].iter_mut().zip(sequence.to_be_bytes()) {
+        *byte ^= seq;
+    }
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&nonce),
+        }
+    }
+}
+
+/// 48 bit SRTP packet index from rollover counter and RTP sequence number
+pub const fn srtp_index(roc: u32, sequence: u16) -> u64 {
+    (roc as u64) << 16 | sequence as u64
+}
+
+/// Writes 16 byte AES-CM IV of SRTP: `salt * 2^16 XOR ssrc * 2^64 XOR index * 2^16`
+///
+/// # Panics
+/// If `index` doesn't fit in 48 bits
+#[track_caller]
+pub fn write_srtp_iv<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, salt: &[u8; 14], ssrc: u32, index: u64) -> ConstWriter<T, {N - 16}> {
+    assert!(index < 1 << 48, "value doesn't fit in 48 bits: {}", index);
+    let mut iv = [0u8; 16];
+    iv[..14].copy_from_slice(salt);
+    for (byte, ssrc) in iv[4..8].iter_mut().zip(ssrc.to_be_bytes()) {
+        *byte ^= ssrc;
+    }
+    for (byte, index) in iv[8..14].iter_mut().zip(&index.to_be_bytes()[2..]) {
+        *byte ^= index;
+    }
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&iv),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn xor_nonce_and_srtp_iv() {
+        let mut buff = [0u8; 28];
+        let writer = super::write_xor_nonce(buff.const_writer::<28>(), &[0xFF; 12], 0x0102);
+        super::write_srtp_iv(writer, &[0; 14], 0xAABBCCDD, super::srtp_index(1, 2));
+        assert_eq!(buff[..12], [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0xFD]);
+        assert_eq!(buff[12..], [0, 0, 0, 0, 0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 1, 0, 2, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "counter doesn't fit in 2 bytes: 65536")]
+    fn partially_implicit_counter_overflow() {
+        let mut buff = [0u8; 6];
+        super::write_partially_implicit::<4, 2, _, _>(buff.const_writer::<6>(), &[0; 4], 1 << 16);
+    }
+}
@@ -0,0 +1,263 @@
+//! Packet of at most `MTU` bytes assembled from header and payload writers.
+//!
+//! Header gets budget of `H` bytes and payload of `P` bytes, `H + P` above MTU is compile
+//! error. Payload doesn't have to be filled, actual packet length is [`PacketBuilder::bytes_used`].
+//!
+//! ```
+//! use const_writer::packet::PacketBuilder;
+//!
+//! let mut packet = PacketBuilder::<1200>::new();
+//! let bytes = packet.build::<4, 1196>(
+//!     |header| header.write_u16_be(0xCAFE).write_u16_be(7),
+//!     |payload| payload.write_u32_le(42).shrink::<0>(),
+//! );
+//! assert_eq!(bytes, [0xCA, 0xFE, 0, 7, 42, 0, 0, 0]);
+//! assert_eq!(packet.bytes_used(), 8);
+//! ```
+//!
+//! Payload bigger than packet is split with [`PacketBuilder::fragments`], each fragment gets header
+//! `[id u16 be][index u16 be][count u16 be]`. Writer of each fragment packet has budget left
+//! after header and `FRAGMENT` bytes of chunk, e.g. for trailer:
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::packet::PacketBuilder;
+//!
+//! let message = [7u8; 10];
+//! let mut packet = PacketBuilder::<11>::new();
+//! let mut fragments = packet.fragments::<4>(&message, 1);
+//! let mut sent = vec![];
+//! while let Some(writer) = fragments.next() {
+//!     writer.write_u8_le(0xFF);
+//!     sent.push(fragments.packet().to_vec());
+//! }
+//! assert_eq!(sent.len(), 3);
+//! assert_eq!(sent[2], [0, 1, 0, 2, 0, 3, 7, 7, 0xFF]);
+//! ```
+//!
+//! ```compile_fail
+//! use const_writer::packet::PacketBuilder;
+//!
+//! let mut packet = PacketBuilder::<8>::new();
+//! packet.build::<4, 8>(|header| header.write_u32_le(1), |payload| payload.write_u64_le(2)); // exceeds MTU
+//! ```
+use crate::{ConstWrite, ConstWriter, ConstWriterAdapter};
+use crate::array::ArrayWriterAdapter;
+
+/// Compile time check of packet budget
+struct Budget<const H: usize, const P: usize, const MTU: usize>;
+
+impl<const H: usize, const P: usize, const MTU: usize> Budget<H, P, MTU> {
+    const CHECK: () = assert!(H + P <= MTU, "header and payload exceed MTU");
+}
+
+/// Stack buffer of single packet
+pub struct PacketBuilder<const MTU: usize> {
+    buff: [u8; MTU],
+    len: usize,
+}
+
+impl<const MTU: usize> PacketBuilder<MTU> {
+    pub fn new() -> Self {
+        Self {
+            buff: [0; MTU],
+            len: 0,
+        }
+    }
+
+    /// Writes header of `H` bytes with `header` and payload of up to `P` bytes with
+    /// `payload`, returns packet. Previous packet is overwritten.
+    pub fn build<const H: usize, const P: usize>(
+        &mut self,
+        header: impl for<'p> FnOnce(ConstWriter<ArrayWriterAdapter<'p, MTU>, H>) -> ConstWriter<ArrayWriterAdapter<'p, MTU>, 0>,
+        payload: impl for<'p> FnOnce(ConstWriter<ArrayWriterAdapter<'p, MTU>, P>) -> ConstWriter<ArrayWriterAdapter<'p, MTU>, 0>,
+    ) -> &[u8] {
+        let () = Budget::<H, P, MTU>::CHECK;
+        let writer = header(self.buff.const_writer::<H>());
+        let writer = payload(ConstWriter { writer_adapter: writer.writer_adapter });
+        self.len = writer.written_so_far();
+        self.as_bytes()
+    }
+
+    /// Length of last built packet, for send call
+    pub fn bytes_used(&self) -> usize {
+        self.len
+    }
+
+    /// Last built packet
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buff[..self.len]
+    }
+}
+
+impl<const MTU: usize> Default for PacketBuilder<MTU> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Length of fragment header
+pub const FRAGMENT_HEADER_LEN: usize = 6;
+
+/// Compile time check of fragment length
+struct FragmentLen<const FRAGMENT: usize>;
+
+impl<const FRAGMENT: usize> FragmentLen<FRAGMENT> {
+    const CHECK: () = assert!(FRAGMENT >= 1, "fragment must hold at least one byte");
+}
+
+impl<const MTU: usize> PacketBuilder<MTU> {
+    /// Splits `payload` into fragments of up to `FRAGMENT` bytes of message `id`, packet of
+    /// each fragment is built with writer from [`FragmentWriters::next`].
+    /// Empty payload gives single empty fragment.
+    ///
+    /// # Panics
+    /// If there are more than `u16::MAX` fragments
+    #[track_caller]
+    pub fn fragments<'a, const FRAGMENT: usize>(&mut self, payload: &'a [u8], id: u16) -> FragmentWriters<'_, 'a, MTU, FRAGMENT> {
+        let () = FragmentLen::<FRAGMENT>::CHECK;
+        let () = Budget::<FRAGMENT_HEADER_LEN, FRAGMENT, MTU>::CHECK;
+        let count = payload.len().div_ceil(FRAGMENT).max(1);
+        assert!(count <= u16::MAX as usize, "too many fragments: {}", count);
+        FragmentWriters {
+            builder: self,
+            payload,
+            id,
+            index: 0,
+            count: count as u16,
+        }
+    }
+}
+
+/// Writers of fragment packets over [`PacketBuilder`], see [`PacketBuilder::fragments`]
+pub struct FragmentWriters<'p, 'a, const MTU: usize, const FRAGMENT: usize> {
+    builder: &'p mut PacketBuilder<MTU>,
+    payload: &'a [u8],
+    id: u16,
+    index: u16,
+    count: u16,
+}
+
+impl<'p, 'a, const MTU: usize, const FRAGMENT: usize> FragmentWriters<'p, 'a, MTU, FRAGMENT>
+where [(); MTU - FRAGMENT_HEADER_LEN - FRAGMENT]: {
+    /// Writes header and chunk of next fragment to packet, `FRAGMENT` bytes of budget are taken
+    /// by chunk even if it's shorter. Rest of packet is left to returned writer, packet is
+    /// [`FragmentWriters::packet`] after writer is used.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<ConstWriter<FragmentWriterAdapter<'_, MTU>, {MTU - FRAGMENT_HEADER_LEN - FRAGMENT}>> {
+        if self.index == self.count {
+            return None;
+        }
+        let len = self.payload.len().min(FRAGMENT);
+        let (chunk, rest) = self.payload.split_at(len);
+        self.payload = rest;
+        let (id, index, count) = (self.id.to_be_bytes(), self.index.to_be_bytes(), self.count.to_be_bytes());
+        let header = [id[0], id[1], index[0], index[1], count[0], count[1]];
+        self.index += 1;
+
+        let builder = &mut *self.builder;
+        let mut writer_adapter = FragmentWriterAdapter {
+            buff: &mut builder.buff,
+            pos: 0,
+            len: &mut builder.len,
+        };
+        unsafe {
+            writer_adapter = writer_adapter.write(&header);
+            // only last chunk can be shorter, it's copied at once too
+            core::ptr::copy_nonoverlapping(chunk.as_ptr(), writer_adapter.buff.as_mut_ptr().add(writer_adapter.pos), chunk.len());
+            writer_adapter.pos += chunk.len();
+            *writer_adapter.len = writer_adapter.pos;
+            Some(ConstWriter {
+                writer_adapter: crate::poisoned::<_, {MTU - FRAGMENT_HEADER_LEN - FRAGMENT}>(writer_adapter),
+            })
+        }
+    }
+
+    /// Number of fragments of message
+    pub fn count(&self) -> u16 {
+        self.count
+    }
+
+    /// Packet of last fragment, including everything written by its writer
+    pub fn packet(&self) -> &[u8] {
+        self.builder.as_bytes()
+    }
+}
+
+/// Writes rest of fragment packet. Packet length is stored to [`PacketBuilder`] after every write,
+/// so adapter has no drop glue and packet can be read as soon as writer isn't used.
+pub struct FragmentWriterAdapter<'p, const MTU: usize> {
+    buff: &'p mut [u8; MTU],
+    pos: usize,
+    len: &'p mut usize,
+}
+
+impl<'p, const MTU: usize> crate::sealed::Sealed for FragmentWriterAdapter<'p, MTU> {}
+
+impl<'p, const MTU: usize> core::fmt::Debug for FragmentWriterAdapter<'p, MTU> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'p, const MTU: usize> ConstWriterAdapter for FragmentWriterAdapter<'p, MTU> {
+    fn written_so_far(&self) -> usize {
+        self.pos
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        core::ptr::copy_nonoverlapping(value.as_ptr(), self.buff.as_mut_ptr().add(self.pos), N);
+        self.pos += N;
+        *self.len = self.pos;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= MTU - self.pos,
+            "remaining packet too short to grow: {} < {} ({})",
+            MTU - self.pos,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        core::ptr::write_bytes(self.buff.as_mut_ptr().add(self.pos), crate::POISON, N);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PacketBuilder;
+
+    #[test]
+    fn packet_rebuild_shorter() {
+        let mut packet = PacketBuilder::<6>::new();
+        packet.build::<2, 4>(|h| h.write_u16_le(1), |p| p.write_u32_le(2));
+        assert_eq!(packet.bytes_used(), 6);
+        packet.build::<2, 4>(|h| h.write_u16_le(3), |p| p.shrink::<0>());
+        assert_eq!(packet.as_bytes(), [3, 0]);
+    }
+
+    #[test]
+    fn fragments_of_empty_and_exact_payload() {
+        let mut packet = PacketBuilder::<8>::new();
+        let mut empty = packet.fragments::<2>(&[], 9);
+        assert_eq!(empty.count(), 1);
+        empty.next().unwrap();
+        assert_eq!(empty.packet(), [0, 9, 0, 0, 0, 1]);
+        assert!(empty.next().is_none());
+
+        let mut exact = packet.fragments::<2>(&[1, 2, 3, 4], 9);
+        exact.next().unwrap();
+        assert_eq!(exact.packet(), [0, 9, 0, 0, 0, 2, 1, 2]);
+        exact.next().unwrap();
+        assert_eq!(exact.packet(), [0, 9, 0, 1, 0, 2, 3, 4]);
+        assert!(exact.next().is_none());
+    }
+}
@@ -0,0 +1,97 @@
+//! Column pages of dictionary encoded values.
+//!
+//! Page is `[header 12 bytes][COUNT values of WIDTH bits][crc32 u32 le]`, values are indexes
+//! into dictionary stored elsewhere, bit packed like in [`bitpack`](crate::bitpack).
+//! Header is `[width u8][0 u8][0 u16][count u32 le][dictionary_len u32 le]`, CRC-32 is
+//! computed over header and values. Page length is [`page_len`], known at compile time.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::page;
+//!
+//! // enum column of 3 variants, 2 bits per value
+//! let mut vec = vec![];
+//! page::write_page::<8, 2, _, _>(vec.const_writer::<18>(), 3, &[0, 1, 2, 2, 1, 0, 0, 1]);
+//! assert_eq!(vec.len(), page::page_len(8, 2));
+//! assert_eq!(vec[..12], [2, 0, 0, 0, 8, 0, 0, 0, 3, 0, 0, 0]);
+//! assert_eq!(vec[12..14], [0b10_10_01_00, 0b01_00_00_01]);
+//! assert!(page::check_crc(&vec));
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+use crate::bitpack::{check_packed, packed_len, write_packed};
+use crate::crc::{algorithm, crc32};
+
+/// Length of page header
+pub const HEADER_LEN: usize = 12;
+
+/// Length of page with `count` values of `width` bits
+pub const fn page_len(count: usize, width: usize) -> usize {
+    HEADER_LEN + packed_len(count, width) + 4
+}
+
+/// Writes page of dictionary indexes `values`
+///
+/// # Panics
+/// If value isn't less than `dictionary_len` or `COUNT` doesn't fit in `u32`
+#[track_caller]
+pub fn write_page<const COUNT: usize, const WIDTH: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    dictionary_len: u32,
+    values: &[u64; COUNT],
+) -> ConstWriter<T, {N - page_len(COUNT, WIDTH)}> {
+    assert!(COUNT <= u32::MAX as usize, "too many values for page: {}", COUNT);
+    let count = (COUNT as u32).to_le_bytes();
+    let dictionary = dictionary_len.to_le_bytes();
+    let header = [
+        WIDTH as u8, 0, 0, 0,
+        count[0], count[1], count[2], count[3],
+        dictionary[0], dictionary[1], dictionary[2], dictionary[3],
+    ];
+    let index = |value: u64| {
+        assert!(value < dictionary_len as u64, "value isn't in dictionary: {} >= {}", value, dictionary_len);
+        value
+    };
+    // check every index before header is written, so panic leaves no partial page
+    check_packed::<WIDTH>(values, index);
+
+    let writer_adapter = writer.with_crc::<algorithm::Crc32>().writer_adapter;
+    let writer_adapter = unsafe { write_packed::<_, WIDTH>(writer_adapter.write(&header), values, index) };
+    // only CRC is left, adapter has space for it
+    let writer = ConstWriter::<_, 4> { writer_adapter }.finish_crc_le();
+    ConstWriter {
+        writer_adapter: writer.writer_adapter,
+    }
+}
+
+/// Checks CRC-32 trailer of `page`
+pub fn check_crc(page: &[u8]) -> bool {
+    match page.len().checked_sub(4) {
+        Some(len) if len >= HEADER_LEN => crc32(&page[..len]).to_le_bytes() == page[len..],
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    #[should_panic(expected = "value isn't in dictionary: 5 >= 5")]
+    fn page_value_out_of_dictionary() {
+        let mut buff = [0u8; 18];
+        super::write_page::<2, 3, _, _>(buff.const_writer::<18>(), 5, &[4, 5]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn page_checked_before_header() {
+        let mut vec = vec![];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            super::write_page::<4, 2, _, _>(vec.const_writer::<17>(), 3, &[0, 1, 2, 3]);
+        }));
+        assert!(result.is_err());
+        assert!(vec.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+//! Fixed parts of [Parquet](https://parquet.apache.org/docs/file-format/) files and
+//! [Thrift compact](https://github.com/apache/thrift/blob/master/doc/specs/thrift-compact-protocol.md)
+//! page headers.
+//!
+//! File is `PAR1`, column chunks, footer metadata, `[metadata_len u32 le]` and `PAR1`.
+//! Integers of Thrift compact protocol are zigzag varints, so their writers reserve worst case
+//! and return actual length.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::{crc, parquet};
+//!
+//! let data = [1u8, 2, 3];
+//! let mut header = vec![];
+//! let writer = header.const_writer::<25>();
+//! let (writer, _) = parquet::write_i32_field(writer, 1, parquet::page_type::DATA_PAGE);
+//! let (writer, _) = parquet::write_i32_field(writer, 1, data.len() as i32); // uncompressed size
+//! let (writer, _) = parquet::write_i32_field(writer, 1, data.len() as i32); // compressed size
+//! let (writer, _) = parquet::write_crc_field(writer, 1, crc::crc32(&data));
+//! // data page header struct field is omitted
+//! parquet::write_struct_end(writer);
+//! assert_eq!(header[..6], [0x15, 0, 0x15, 6, 0x15, 6]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Magic at start and end of file
+pub const MAGIC: [u8; 4] = *b"PAR1";
+/// Worst case length of `i32` field with header
+pub const MAX_I32_FIELD_LEN: usize = 6;
+
+/// Types of Thrift compact fields
+pub mod field_type {
+    pub const TRUE: u8 = 1;
+    pub const FALSE: u8 = 2;
+    pub const BYTE: u8 = 3;
+    pub const I16: u8 = 4;
+    pub const I32: u8 = 5;
+    pub const I64: u8 = 6;
+    pub const DOUBLE: u8 = 7;
+    pub const BINARY: u8 = 8;
+    pub const LIST: u8 = 9;
+    pub const SET: u8 = 10;
+    pub const MAP: u8 = 11;
+    pub const STRUCT: u8 = 12;
+}
+
+/// Values of `PageHeader.type`
+pub mod page_type {
+    pub const DATA_PAGE: i32 = 0;
+    pub const INDEX_PAGE: i32 = 1;
+    pub const DICTIONARY_PAGE: i32 = 2;
+    pub const DATA_PAGE_V2: i32 = 3;
+}
+
+/// Writes `PAR1` magic
+pub fn write_magic<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 4}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&MAGIC),
+        }
+    }
+}
+
+/// Writes end of file: length of footer metadata and `PAR1` magic
+pub fn write_footer_trailer<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, metadata_len: u32) -> ConstWriter<T, {N - 8}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&metadata_len.to_le_bytes())
+                .write(&MAGIC),
+        }
+    }
+}
+
+/// Short form field header byte
+#[track_caller]
+fn field_header(delta: u8, field_type: u8) -> u8 {
+    assert!((1..=15).contains(&delta), "field id delta doesn't fit in short form: {}", delta);
+    delta << 4 | field_type
+}
+
+/// Writes `i32` field, returns adapter and written length
+#[track_caller]
+unsafe fn write_i32<T: ConstWriterAdapter>(writer_adapter: T, delta: u8, value: i32) -> (T, usize) {
+    let mut writer_adapter = writer_adapter.write(&[field_header(delta, field_type::I32)]);
+    let mut value = ((value << 1) ^ (value >> 31)) as u32;
+    let mut len = 1;
+    loop {
+        len += 1;
+        if value < 0x80 {
+            return (writer_adapter.write(&[value as u8]), len);
+        }
+        writer_adapter = writer_adapter.write(&[value as u8 | 0x80]);
+        value >>= 7;
+    }
+}
+
+/// Writes short form field header: field id `delta` from previous field and field type
+///
+/// # Panics
+/// If `delta` isn't in `1..=15`
+#[track_caller]
+pub fn write_field_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, delta: u8, field_type: u8) -> ConstWriter<T, {N - 1}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[field_header(delta, field_type)]),
+        }
+    }
+}
+
+/// Writes `i32` field, returns number of written bytes
+#[track_caller]
+pub fn write_i32_field<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, delta: u8, value: i32) -> (ConstWriter<T, {N - MAX_I32_FIELD_LEN}>, usize) {
+    let (writer_adapter, len) = unsafe { write_i32(writer.writer_adapter, delta, value) };
+    (ConstWriter { writer_adapter }, len)
+}
+
+/// Writes `PageHeader.crc` field, CRC-32 of page data is stored as `i32`
+#[track_caller]
+pub fn write_crc_field<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, delta: u8, crc: u32) -> (ConstWriter<T, {N - MAX_I32_FIELD_LEN}>, usize) {
+    let (writer_adapter, len) = unsafe { write_i32(writer.writer_adapter, delta, crc as i32) };
+    (ConstWriter { writer_adapter }, len)
+}
+
+/// Writes stop field, which ends struct
+pub fn write_struct_end<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 1}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[0]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn parquet_negative_i32_and_trailer() {
+        let mut buff = [0u8; 15];
+        let (writer, len) = super::write_i32_field(buff.const_writer::<15>(), 4, i32::MIN);
+        assert_eq!(len, 6);
+        super::write_footer_trailer(super::write_struct_end(writer), 0x0102);
+        assert_eq!(buff[..6], [0x45, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+        assert_eq!(buff[6..], [0, 2, 1, 0, 0, b'P', b'A', b'R', b'1']);
+    }
+}
@@ -0,0 +1,98 @@
+//! [PostgreSQL wire protocol](https://www.postgresql.org/docs/current/protocol-message-formats.html) messages.
+//!
+//! Message is tag byte and big endian length, which counts itself and body but not tag.
+//! With body written into budget of `LEN` bytes length is known upfront, so nothing is backfilled.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::pg;
+//!
+//! let mut vec = vec![];
+//! let writer = pg::write_message::<6, _, _>(vec.const_writer::<16>(), b'Q', |body| body.write_slice(b"SELECT"));
+//! pg::write_sync(writer).shrink::<0>();
+//! assert_eq!(vec, [b'Q', 0, 0, 0, 10, b'S', b'E', b'L', b'E', b'C', b'T', b'S', 0, 0, 0, 4]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Protocol version 3.0 sent in startup message
+pub const PROTOCOL_VERSION: u32 = 196608;
+
+#[track_caller]
+fn header(tag: u8, body_len: usize) -> [u8; 5] {
+    assert!(body_len <= i32::MAX as usize - 4, "message body too long: {}", body_len);
+    let len = (body_len as u32 + 4).to_be_bytes();
+    [tag, len[0], len[1], len[2], len[3]]
+}
+
+/// Writes tag and length of message with `body_len` bytes of body
+///
+/// # Panics
+/// If length doesn't fit in `i32`
+#[track_caller]
+pub fn write_message_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, tag: u8, body_len: usize) -> ConstWriter<T, {N - 5}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&header(tag, body_len)),
+        }
+    }
+}
+
+/// Writes message with `LEN` bytes of body written by `body`
+pub fn write_message<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    tag: u8,
+    body: impl FnOnce(ConstWriter<T, LEN>) -> ConstWriter<T, 0>,
+) -> ConstWriter<T, {N - (LEN + 5)}> {
+    let writer_adapter = unsafe { writer.writer_adapter.write(&header(tag, LEN)) };
+    ConstWriter {
+        writer_adapter: body(ConstWriter { writer_adapter }).writer_adapter,
+    }
+}
+
+/// Writes untagged startup message with `LEN` bytes of parameters written by `parameters`
+pub fn write_startup_message<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    parameters: impl FnOnce(ConstWriter<T, LEN>) -> ConstWriter<T, 0>,
+) -> ConstWriter<T, {N - (LEN + 8)}> {
+    let len = (LEN as u32 + 8).to_be_bytes();
+    let writer_adapter = unsafe {
+        writer.writer_adapter
+            .write(&len)
+            .write(&PROTOCOL_VERSION.to_be_bytes())
+    };
+    ConstWriter {
+        writer_adapter: parameters(ConstWriter { writer_adapter }).writer_adapter,
+    }
+}
+
+/// Writes `Sync` message
+pub fn write_sync<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 5}> {
+    write_message_header(writer, b'S', 0)
+}
+
+/// Writes `Flush` message
+pub fn write_flush<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 5}> {
+    write_message_header(writer, b'H', 0)
+}
+
+/// Writes `Terminate` message
+pub fn write_terminate<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>) -> ConstWriter<T, {N - 5}> {
+    write_message_header(writer, b'X', 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn pg_startup_message() {
+        let mut buff = [0u8; 23];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_startup_message::<10, _, _>(ref_buff.const_writer::<23>(), |p| p.write_slice(b"user\0pg\0\0\0"))
+            .then(super::write_terminate);
+        assert_eq!(buff[..8], [0, 0, 0, 18, 0, 3, 0, 0]);
+        assert_eq!(buff[18..], [b'X', 0, 0, 0, 4]);
+    }
+}
@@ -0,0 +1,293 @@
+//! Pool of fixed size buffers, which are handed out wrapped in [`ConstWriter`].
+//!
+//! ```
+//! use const_writer::pool::BufferPool;
+//!
+//! let pool = BufferPool::<16>::new();
+//! let packet = pool.const_writer()
+//!     .write_u32_be(1)
+//!     .write_u16_be(2)
+//!     .finish();
+//! assert_eq!(&*packet, &[0, 0, 0, 1, 0, 2]);
+//! drop(packet); // buffer is returned to pool
+//!
+//! assert_eq!(pool.available(), 1);
+//! ```
+//!
+//! [`SyncBufferPool`] keeps free buffers behind [`Mutex`](std::sync::Mutex), so it can be shared
+//! between threads, e.g. in `static`. Enabled with `std` feature.
+use crate::{ConstWriter, ConstWriterAdapter};
+
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ops::{Deref, DerefMut};
+
+/// Storage of free buffers, implemented by [`BufferPool`] and [`SyncBufferPool`]
+pub trait Pool<const CAP: usize>: crate::sealed::Sealed {
+    #[doc(hidden)]
+    fn take(&self) -> Box<[u8; CAP]>;
+    #[doc(hidden)]
+    fn release(&self, buff: Box<[u8; CAP]>);
+}
+
+/// Get [`ConstWriter`] to write up to `CAP` bytes to buffer taken from `pool`
+fn pooled_writer<const CAP: usize, P: Pool<CAP>>(pool: &P) -> ConstWriter<PoolWriterAdapter<'_, CAP, P>, CAP> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: crate::poisoned::<_, CAP>(PoolWriterAdapter {
+                buff: Some(pool.take()),
+                pool,
+                len: 0,
+            })
+        }
+    }
+}
+
+/// Pool of `CAP` byte buffers. Buffers are allocated on demand and reused after returned.
+///
+/// Pool isn't `Sync`, free list is behind `RefCell`, use pool per thread or [`SyncBufferPool`].
+pub struct BufferPool<const CAP: usize> {
+    free: RefCell<Vec<Box<[u8; CAP]>>>,
+}
+
+impl<const CAP: usize> BufferPool<CAP> {
+    pub fn new() -> Self {
+        Self {
+            free: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Number of buffers available for reuse
+    pub fn available(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    /// Get [`ConstWriter`] to write up to `CAP` bytes to pooled buffer
+    pub fn const_writer(&self) -> ConstWriter<PoolWriterAdapter<'_, CAP>, CAP> {
+        pooled_writer(self)
+    }
+}
+
+impl<const CAP: usize> crate::sealed::Sealed for BufferPool<CAP> {}
+
+impl<const CAP: usize> Pool<CAP> for BufferPool<CAP> {
+    fn take(&self) -> Box<[u8; CAP]> {
+        self.free.borrow_mut().pop().unwrap_or_else(zeroed)
+    }
+
+    fn release(&self, buff: Box<[u8; CAP]>) {
+        self.free.borrow_mut().push(buff);
+    }
+}
+
+/// Zeroed buffer allocated directly on heap, `Box::new([0; CAP])` could overflow stack for large `CAP`
+fn zeroed<const CAP: usize>() -> Box<[u8; CAP]> {
+    core::convert::TryFrom::try_from(alloc::vec![0u8; CAP].into_boxed_slice()).unwrap_or_else(|_| unreachable!())
+}
+
+impl<const CAP: usize> Default for BufferPool<CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pool of `CAP` byte buffers, which can be shared between threads
+#[cfg(feature = "std")]
+pub struct SyncBufferPool<const CAP: usize> {
+    free: std::sync::Mutex<Vec<Box<[u8; CAP]>>>,
+}
+
+#[cfg(feature = "std")]
+impl<const CAP: usize> SyncBufferPool<CAP> {
+    pub const fn new() -> Self {
+        Self {
+            free: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Box<[u8; CAP]>>> {
+        // free list stays consistent even if some thread panicked while holding lock
+        self.free.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Number of buffers available for reuse
+    pub fn available(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Get [`ConstWriter`] to write up to `CAP` bytes to pooled buffer
+    pub fn const_writer(&self) -> ConstWriter<PoolWriterAdapter<'_, CAP, Self>, CAP> {
+        pooled_writer(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const CAP: usize> crate::sealed::Sealed for SyncBufferPool<CAP> {}
+
+#[cfg(feature = "std")]
+impl<const CAP: usize> Pool<CAP> for SyncBufferPool<CAP> {
+    fn take(&self) -> Box<[u8; CAP]> {
+        // allocate outside of lock
+        let buff = self.lock().pop();
+        buff.unwrap_or_else(zeroed)
+    }
+
+    fn release(&self, buff: Box<[u8; CAP]>) {
+        self.lock().push(buff);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const CAP: usize> Default for SyncBufferPool<CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adapter over pooled buffer. Returns buffer to pool on drop, unless writer is finished.
+pub struct PoolWriterAdapter<'pool, const CAP: usize, P: Pool<CAP> = BufferPool<CAP>> {
+    pool: &'pool P,
+    buff: Option<Box<[u8; CAP]>>,
+    len: usize,
+}
+
+impl<'pool, const CAP: usize, P: Pool<CAP>> crate::sealed::Sealed for PoolWriterAdapter<'pool, CAP, P> {}
+
+impl<'pool, const CAP: usize, P: Pool<CAP>> core::fmt::Debug for PoolWriterAdapter<'pool, CAP, P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'pool, const CAP: usize, P: Pool<CAP>> ConstWriterAdapter for PoolWriterAdapter<'pool, CAP, P> {
+    fn written_so_far(&self) -> usize {
+        self.len
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        let ptr = self.buff.as_mut().unwrap_unchecked().as_mut_ptr().add(self.len);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr, N);
+        self.len += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= CAP - self.len,
+            "remaining pooled buffer too short to grow: {} < {} ({})",
+            CAP - self.len,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(mut self) -> Self {
+        core::ptr::write_bytes(self.buff.as_mut().unwrap_unchecked().as_mut_ptr().add(self.len), crate::POISON, N);
+        self
+    }
+}
+
+impl<'pool, const CAP: usize, P: Pool<CAP>> Drop for PoolWriterAdapter<'pool, CAP, P> {
+    fn drop(&mut self) {
+        if let Some(buff) = self.buff.take() {
+            self.pool.release(buff);
+        }
+    }
+}
+
+impl<'pool, const CAP: usize, P: Pool<CAP>, const N: usize> ConstWriter<PoolWriterAdapter<'pool, CAP, P>, {N}> {
+    /// Finishes writing and returns buffer with written bytes
+    pub fn finish(mut self) -> PooledBuffer<'pool, CAP, P> {
+        PooledBuffer {
+            pool: self.writer_adapter.pool,
+            buff: self.writer_adapter.buff.take(),
+            len: self.writer_adapter.len,
+        }
+    }
+}
+
+/// Written bytes of pooled buffer. Buffer is returned to pool on drop.
+pub struct PooledBuffer<'pool, const CAP: usize, P: Pool<CAP> = BufferPool<CAP>> {
+    pool: &'pool P,
+    buff: Option<Box<[u8; CAP]>>,
+    len: usize,
+}
+
+impl<'pool, const CAP: usize, P: Pool<CAP>> Deref for PooledBuffer<'pool, CAP, P> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buff.as_ref().unwrap()[..self.len]
+    }
+}
+
+impl<'pool, const CAP: usize, P: Pool<CAP>> DerefMut for PooledBuffer<'pool, CAP, P> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buff.as_mut().unwrap()[..self.len]
+    }
+}
+
+impl<'pool, const CAP: usize, P: Pool<CAP>> Drop for PooledBuffer<'pool, CAP, P> {
+    fn drop(&mut self) {
+        if let Some(buff) = self.buff.take() {
+            self.pool.release(buff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BufferPool;
+
+    #[test]
+    fn pool_reuse() {
+        let pool = BufferPool::<8>::new();
+        let first = pool.const_writer().write_u64_le(1).finish();
+        let second = pool.const_writer().write_u32_le(2).finish();
+        assert_eq!(pool.available(), 0);
+        assert_eq!(&*first, &[1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&*second, &[2, 0, 0, 0]);
+        drop(first);
+        drop(second);
+
+        // dropped unfinished writer returns buffer too
+        pool.const_writer().write_u8_le(3);
+        assert_eq!(pool.available(), 2);
+    }
+
+    #[test]
+    fn pool_large_buffer() {
+        // larger than test thread stack
+        let pool = BufferPool::<{ 16 << 20 }>::new();
+        let packet = pool.const_writer().write_u16_le(1).finish();
+        assert_eq!(&*packet, &[1, 0]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sync_pool_threads() {
+        static POOL: super::SyncBufferPool<8> = super::SyncBufferPool::new();
+        std::thread::scope(|s| {
+            for i in 0..4u32 {
+                s.spawn(move || {
+                    for _ in 0..100 {
+                        let packet = POOL.const_writer().write_u32_le(i).finish();
+                        assert_eq!(&*packet, &i.to_le_bytes());
+                    }
+                });
+            }
+        });
+        assert!((1..=4).contains(&POOL.available()));
+
+        // finished buffer can be returned from other thread
+        let packet = POOL.const_writer().write_u8_le(5).finish();
+        let available = POOL.available();
+        std::thread::spawn(move || drop(packet)).join().unwrap();
+        assert_eq!(POOL.available(), available + 1);
+    }
+}
@@ -0,0 +1,214 @@
+//! Subset of [postcard](https://docs.rs/postcard) wire format where every value has fixed size.
+//!
+//! Postcard writes integers wider than byte as varints, fixed size encoding is used for them
+//! on the other side with `#[serde(with = "postcard::fixint::le")]`. Floats, `u8`, `i8`,
+//! `bool`, arrays, tuples and structs are fixed size in postcard anyway, enum variant
+//! indexes below 128 take single byte.
+//!
+//! [`PostcardFixed`] is implemented for these types in postcard encoding, and
+//! [`postcard_fixed!`](crate::postcard_fixed) implements it and [`ConstWritable`](crate::ConstWritable)
+//! for structs, so they are written with [`write_value`](crate::ConstWriter::write_value).
+//! Other values are wrapped in [`Postcard`], so postcard encoding doesn't leak into `write_value`
+//! of plain integers.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//!
+//! // on host side:
+//! // #[derive(Deserialize)]
+//! // struct Reading {
+//! //     #[serde(with = "postcard::fixint::le")]
+//! //     sensor: u16,
+//! //     value: f32,
+//! //     valid: bool,
+//! // }
+//! struct Reading {
+//!     sensor: u16,
+//!     value: f32,
+//!     valid: bool,
+//! }
+//!
+//! const_writer::postcard_fixed!(Reading { sensor, value, valid });
+//!
+//! let reading = Reading { sensor: 0x0102, value: 1.0, valid: true };
+//! let mut vec = vec![];
+//! vec.const_writer::<7>().write_value(&reading);
+//! assert_eq!(vec, [2, 1, 0, 0, 0x80, 0x3F, 1]);
+//!
+//! vec.const_writer::<3>().write_value(&const_writer::postcard::Postcard((1u16, true)));
+//! assert_eq!(vec[7..], [1, 0, 1]);
+//! ```
+use crate::{ConstWritable, ConstWriter, ConstWriterAdapter};
+
+/// Value with fixed size postcard encoding
+///
+/// # Safety
+/// `write_postcard` must write exactly `SIZE` bytes
+pub unsafe trait PostcardFixed {
+    const SIZE: usize;
+
+    /// # Safety
+    /// Underlying buffer must have space for `SIZE` bytes
+    unsafe fn write_postcard<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T;
+}
+
+/// Writes wrapped value in postcard encoding with [`write_value`](crate::ConstWriter::write_value)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Postcard<V>(pub V);
+
+unsafe impl<V: PostcardFixed> ConstWritable for Postcard<V> {
+    const SIZE: usize = V::SIZE;
+
+    unsafe fn write_to<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+        self.0.write_postcard(writer_adapter)
+    }
+}
+
+macro_rules! postcard_fixed_int {
+    ($($type:ty),*) => {
+        $(
+            unsafe impl PostcardFixed for $type {
+                const SIZE: usize = core::mem::size_of::<$type>();
+
+                unsafe fn write_postcard<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                    writer_adapter.write(&self.to_le_bytes())
+                }
+            }
+        )*
+    }
+}
+
+postcard_fixed_int!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
+
+unsafe impl PostcardFixed for bool {
+    const SIZE: usize = 1;
+
+    unsafe fn write_postcard<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+        writer_adapter.write(&[*self as u8])
+    }
+}
+
+unsafe impl PostcardFixed for () {
+    const SIZE: usize = 0;
+
+    unsafe fn write_postcard<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+        writer_adapter
+    }
+}
+
+/// Arrays are written without length
+unsafe impl<V: PostcardFixed, const L: usize> PostcardFixed for [V; L] {
+    const SIZE: usize = V::SIZE * L;
+
+    unsafe fn write_postcard<T: ConstWriterAdapter>(&self, mut writer_adapter: T) -> T {
+        for value in self {
+            writer_adapter = value.write_postcard(writer_adapter);
+        }
+        writer_adapter
+    }
+}
+
+macro_rules! postcard_fixed_tuple {
+    ($(($($name:ident $index:tt),*)),*) => {
+        $(
+            unsafe impl<$($name: PostcardFixed),*> PostcardFixed for ($($name,)*) {
+                const SIZE: usize = 0 $(+ $name::SIZE)*;
+
+                unsafe fn write_postcard<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                    $(
+                        let writer_adapter = self.$index.write_postcard(writer_adapter);
+                    )*
+                    writer_adapter
+                }
+            }
+        )*
+    }
+}
+
+postcard_fixed_tuple!((A 0), (A 0, B 1), (A 0, B 1, C 2), (A 0, B 1, C 2, D 3));
+
+/// Implements [`PostcardFixed`](crate::postcard::PostcardFixed) and [`ConstWritable`](crate::ConstWritable)
+/// for struct, fields are listed in declaration order, like serde writes them.
+#[macro_export]
+macro_rules! postcard_fixed {
+    ($name:ident { $($field:ident),* $(,)? }) => {
+        unsafe impl $crate::postcard::PostcardFixed for $name {
+            const SIZE: usize = 0 $(+ $crate::postcard::field_size(&|value: &$name| &value.$field))*;
+
+            unsafe fn write_postcard<T: $crate::ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                $(
+                    let writer_adapter = $crate::postcard::PostcardFixed::write_postcard(&self.$field, writer_adapter);
+                )*
+                writer_adapter
+            }
+        }
+
+        unsafe impl $crate::ConstWritable for $name {
+            const SIZE: usize = <$name as $crate::postcard::PostcardFixed>::SIZE;
+
+            unsafe fn write_to<T: $crate::ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+                $crate::postcard::PostcardFixed::write_postcard(self, writer_adapter)
+            }
+        }
+    }
+}
+
+/// Size of field selected by `f`, used by [`postcard_fixed!`](crate::postcard_fixed)
+/// to get field types from names
+#[doc(hidden)]
+pub const fn field_size<S, V: PostcardFixed>(_f: &impl Fn(&S) -> &V) -> usize {
+    V::SIZE
+}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Writes enum variant index, variant fields follow it
+    ///
+    /// # Panics
+    /// If `index` doesn't fit in single byte varint
+    #[track_caller]
+    pub fn write_postcard_variant(self, index: u8) -> ConstWriter<T, {N - 1}> {
+        assert!(index < 0x80, "variant index doesn't fit in single byte: {}", index);
+        unsafe {
+            ConstWriter {
+                writer_adapter: self.writer_adapter.write(&[index]),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::Postcard;
+
+    struct Inner {
+        a: u8,
+    }
+
+    struct Outer {
+        inner: Inner,
+        b: [i16; 2],
+    }
+
+    crate::postcard_fixed!(Inner { a });
+    crate::postcard_fixed!(Outer { inner, b });
+
+    #[test]
+    fn postcard_tuple_array_variant() {
+        let mut buff = [0u8; 9];
+        buff.const_writer::<9>()
+            .write_postcard_variant(2)
+            .write_value(&Postcard((-1i8, [1u16, 2], true, ())))
+            .write_value(&Postcard(0u16));
+        assert_eq!(buff, [2, 0xFF, 1, 0, 2, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn postcard_nested_struct() {
+        let mut buff = [0u8; 5];
+        buff.const_writer::<5>().write_value(&Outer { inner: Inner { a: 7 }, b: [-1, 2] });
+        assert_eq!(buff, [7, 0xFF, 0xFF, 2, 0]);
+    }
+}
@@ -0,0 +1,94 @@
+//! [QUIC variable-length integers](https://www.rfc-editor.org/rfc/rfc9000#section-16).
+//!
+//! Encoded length depends on value, so writer reserves worst case from budget: 8 bytes,
+//! or length of `MAX` when value is known to be bounded. Actual length is returned.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//!
+//! let mut vec = vec![];
+//! let (writer, len) = vec.const_writer::<10>().write_quic_varint(15293);
+//! assert_eq!(len, 2);
+//! // stream id of at most 16383 takes up to 2 bytes
+//! let (_, len) = writer.write_quic_varint_max::<16383>(37);
+//! assert_eq!(len, 1);
+//! assert_eq!(vec, [0x7B, 0xBD, 0x25]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Largest encodable value
+pub const MAX_VARINT: u64 = (1 << 62) - 1;
+
+/// Encoded length of `value`: 1, 2, 4 or 8
+pub const fn varint_len(value: u64) -> usize {
+    if value < 1 << 6 {
+        1
+    } else if value < 1 << 14 {
+        2
+    } else if value < 1 << 30 {
+        4
+    } else {
+        8
+    }
+}
+
+/// Compile time check of bound
+struct Max<const MAX: u64>;
+
+impl<const MAX: u64> Max<MAX> {
+    const CHECK: () = assert!(MAX <= MAX_VARINT, "QUIC varint is up to 2^62 - 1");
+}
+
+/// Writes minimal encoding of `value`, returns adapter and written length
+unsafe fn write_varint<T: ConstWriterAdapter>(writer_adapter: T, value: u64) -> (T, usize) {
+    let len = varint_len(value);
+    let writer_adapter = match len {
+        1 => writer_adapter.write(&[value as u8]),
+        2 => writer_adapter.write(&(value as u16 | 0x4000).to_be_bytes()),
+        4 => writer_adapter.write(&(value as u32 | 0x8000_0000).to_be_bytes()),
+        _ => writer_adapter.write(&(value | 0xC000_0000_0000_0000).to_be_bytes()),
+    };
+    (writer_adapter, len)
+}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Writes `value` as QUIC varint, reserving 8 bytes of budget. Returns number of written bytes.
+    ///
+    /// # Panics
+    /// If `value` is greater than [`MAX_VARINT`]
+    #[track_caller]
+    pub fn write_quic_varint(self, value: u64) -> (ConstWriter<T, {N - 8}>, usize) {
+        assert!(value <= MAX_VARINT, "value doesn't fit in QUIC varint: {}", value);
+        let (writer_adapter, len) = unsafe { write_varint(self.writer_adapter, value) };
+        (ConstWriter { writer_adapter }, len)
+    }
+
+    /// Writes `value` as QUIC varint, reserving encoded length of `MAX`. Returns number of written bytes.
+    ///
+    /// # Panics
+    /// If `value` is greater than `MAX`
+    #[track_caller]
+    pub fn write_quic_varint_max<const MAX: u64>(self, value: u64) -> (ConstWriter<T, {N - varint_len(MAX)}>, usize) {
+        let () = Max::<MAX>::CHECK;
+        assert!(value <= MAX, "value is greater than bound: {} > {}", value, MAX);
+        let (writer_adapter, len) = unsafe { write_varint(self.writer_adapter, value) };
+        (ConstWriter { writer_adapter }, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn quic_varint_rfc_examples() {
+        let mut buff = [0u8; 12];
+        let (writer, len) = buff.const_writer::<12>().write_quic_varint(151_288_809_941_952_652);
+        assert_eq!(len, 8);
+        let (_, len) = writer.write_quic_varint_max::<{ (1 << 30) - 1 }>(494_878_333);
+        assert_eq!(len, 4);
+        assert_eq!(buff, [0xC2, 0x19, 0x7C, 0x5E, 0xFF, 0x14, 0xE8, 0x8C, 0x9D, 0x7F, 0x3E, 0x7D]);
+    }
+}
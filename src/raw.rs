@@ -0,0 +1,135 @@
+//! Adapter over raw pointer and length, e.g. shared memory segment mapped by other process.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//! use const_writer::raw::RawBuffer;
+//!
+//! let mut segment = [0u8; 16];
+//! let mut raw = unsafe { RawBuffer::new(segment.as_mut_ptr(), segment.len()) };
+//! raw.const_writer::<6>()
+//!     .write_u32_le(1)
+//!     .write_u16_le(2);
+//! assert_eq!(raw.written(), 6);
+//! assert_eq!(raw.remaining(), 10);
+//! assert_eq!(segment[..6], [1, 0, 0, 0, 2, 0]);
+//! ```
+use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate};
+
+/// Raw memory region, which is advanced as [`ConstWriter`](crate::ConstWriter)s write to it
+pub struct RawBuffer {
+    start: *mut u8,
+    /// number of bytes written
+    written: usize,
+    /// number of bytes left
+    len: usize,
+}
+
+impl RawBuffer {
+    /// # Safety
+    /// `ptr` must be valid for writes of `len` bytes for the whole lifetime of `RawBuffer`,
+    /// and nothing else may access this memory while it is written.
+    pub unsafe fn new(ptr: *mut u8, len: usize) -> Self {
+        Self {
+            start: ptr,
+            written: 0,
+            len
+        }
+    }
+
+    /// Number of bytes written since creation
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Number of bytes left
+    pub fn remaining(&self) -> usize {
+        self.len
+    }
+}
+
+pub struct RawWriterAdapter<'a> {
+    buff: &'a mut RawBuffer,
+    /// number of bytes written by adapter
+    written: usize,
+}
+
+unsafe impl<'a> ConstWriterAdapterCreate<'a, RawBuffer> for RawWriterAdapter<'a> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(buff: &'a mut RawBuffer) -> Self {
+        assert!(
+            buff.len >= N,
+            "raw buffer too short: {} < {} ({})",
+            buff.len,
+            N,
+            core::any::type_name::<Self>()
+        );
+        Self {
+            buff,
+            written: 0,
+        }
+    }
+}
+
+impl<'a> crate::sealed::Sealed for RawWriterAdapter<'a> {}
+
+impl<'a> core::fmt::Debug for RawWriterAdapter<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a> ConstWriterAdapter for RawWriterAdapter<'a> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        let ptr = self.buff.start.add(self.buff.written + self.written);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr, N);
+        self.written += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= self.buff.len - self.written,
+            "remaining raw buffer too short to grow: {} < {} ({})",
+            self.buff.len - self.written,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        core::ptr::write_bytes(self.buff.start.add(self.buff.written + self.written), crate::POISON, N);
+        self
+    }
+}
+
+impl<'a> Drop for RawWriterAdapter<'a> {
+    /// When dropping adapter we advancing raw buffer
+    fn drop(&mut self) {
+        self.buff.written += self.written;
+        self.buff.len -= self.written;
+    }
+}
+
+impl<'a> ConstWrite<'a, RawWriterAdapter<'a>> for RawBuffer {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::RawBuffer;
+
+    #[test]
+    #[should_panic(expected = "raw buffer too short")]
+    fn raw_too_short() {
+        let mut segment = [0u8; 4];
+        let mut raw = unsafe { RawBuffer::new(segment.as_mut_ptr(), segment.len()) };
+        raw.const_writer::<4>().write_u16_le(1);
+        raw.const_writer::<4>();
+    }
+}
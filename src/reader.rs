@@ -0,0 +1,489 @@
+//! Reading counterpart of [`ConstWriter`](crate::ConstWriter).
+//!
+//! [`ConstReader`] tracks bytes left to read with const generic param,
+//! so reading more than checked on creation is compile error.
+//!
+//! ```
+//! use const_writer::ConstRead;
+//!
+//! let data = [1u8, 0, 0, 0, 0, 2, 3];
+//! let mut slice = &data[..];
+//! let (a, reader) = slice.const_reader::<6>().read_u32_le();
+//! let (b, reader) = reader.read_u16_be();
+//! assert_eq!((a, b, reader.remaining()), (1, 2, 0));
+//! drop(reader);
+//! assert_eq!(slice, &[3]);
+//! ```
+//!
+//! ```compile_fail
+//! use const_writer::ConstRead;
+//!
+//! let data = [0u8; 16];
+//! let mut slice = &data[..];
+//! slice.const_reader::<2>().read_u32_le(); // compile error
+//! ```
+//!
+//! Besides slices readers are implemented for `VecDeque<u8>`, `io::Cursor` and
+//! `bytes::Bytes` with `bytes` feature.
+#[cfg(any(feature = "std", feature = "alloc"))]
+extern crate alloc;
+
+/// Trait for creating [`ConstReaderAdapter`]
+/// Creation moved to separate trait to omit lifetime parameter on ConstReader
+///
+/// # Safety
+/// Created adapter must be able to read at least `N` bytes.
+pub unsafe trait ConstReaderAdapterCreate<'a, T: ?Sized> {
+    /// # Safety
+    /// You must ensure that underlying buffer have at least `N` bytes to read.
+    #[track_caller]
+    unsafe fn new<const N: usize>(buff: &'a mut T) -> Self;
+}
+
+/// Unchecked interface to underlying buffer. Same as [`ConstWriterAdapter`](crate::ConstWriterAdapter)
+/// it must be used within [`ConstReader`] which tracks remaining length.
+pub trait ConstReaderAdapter {
+    /// Reads `N` bytes and advances inner buffer
+    ///
+    /// # Safety
+    /// You should make sure that in total you read less or equal than `N` bytes
+    /// requested on creation
+    unsafe fn read<const N: usize>(&mut self) -> [u8; N];
+
+    /// Reads `N` bytes starting `offset` bytes after current position without advancing
+    ///
+    /// # Safety
+    /// `offset + N` must not exceed bytes left to read
+    unsafe fn peek<const N: usize>(&self, offset: usize) -> [u8; N];
+}
+
+/// Reader that keeping track of bytes left using const_generic params.
+pub struct ConstReader<T: ConstReaderAdapter, const N: usize> {
+    reader_adapter: T,
+}
+
+/// Compile time check that peeked bytes are inside reader
+struct Peek<const N: usize, const OFFSET: usize, const SIZE: usize>;
+
+impl<const N: usize, const OFFSET: usize, const SIZE: usize> Peek<N, OFFSET, SIZE> {
+    const CHECK: () = assert!(OFFSET <= N && SIZE <= N - OFFSET, "peek past end of reader");
+}
+
+macro_rules! implement_peek {
+    ($name:ident, $type:ty, $endian:ident) => {
+        pub fn $name(&self) -> $type {
+            let () = Peek::<N, 0, { core::mem::size_of::<$type>() }>::CHECK;
+            unsafe { <$type>::$endian(self.reader_adapter.peek(0)) }
+        }
+    }
+}
+
+macro_rules! implement_read {
+    ($name:ident, $type:ty, $endian:ident) => {
+        pub fn $name(mut self) -> ($type, ConstReader<T, {N - core::mem::size_of::<$type>()}>) {
+            unsafe {
+                let value = <$type>::$endian(self.reader_adapter.read());
+                (value, ConstReader { reader_adapter: self.reader_adapter })
+            }
+        }
+    }
+}
+
+impl<T: ConstReaderAdapter, const N: usize> ConstReader<T, {N}> {
+    implement_read!(read_u8_le, u8, from_le_bytes);
+    implement_read!(read_u16_le, u16, from_le_bytes);
+    implement_read!(read_u32_le, u32, from_le_bytes);
+    implement_read!(read_u64_le, u64, from_le_bytes);
+    implement_read!(read_u128_le, u128, from_le_bytes);
+
+    implement_read!(read_i8_le, i8, from_le_bytes);
+    implement_read!(read_i16_le, i16, from_le_bytes);
+    implement_read!(read_i32_le, i32, from_le_bytes);
+    implement_read!(read_i64_le, i64, from_le_bytes);
+    implement_read!(read_i128_le, i128, from_le_bytes);
+
+    implement_read!(read_u8_be, u8, from_be_bytes);
+    implement_read!(read_u16_be, u16, from_be_bytes);
+    implement_read!(read_u32_be, u32, from_be_bytes);
+    implement_read!(read_u64_be, u64, from_be_bytes);
+    implement_read!(read_u128_be, u128, from_be_bytes);
+
+    implement_read!(read_i8_be, i8, from_be_bytes);
+    implement_read!(read_i16_be, i16, from_be_bytes);
+    implement_read!(read_i32_be, i32, from_be_bytes);
+    implement_read!(read_i64_be, i64, from_be_bytes);
+    implement_read!(read_i128_be, i128, from_be_bytes);
+
+    implement_read!(read_f32_be, f32, from_be_bytes);
+    implement_read!(read_f64_be, f64, from_be_bytes);
+
+    implement_read!(read_f32_le, f32, from_le_bytes);
+    implement_read!(read_f64_le, f64, from_le_bytes);
+
+    /// Helper to access const_generic param
+    pub fn remaining(&self) -> usize {
+        N
+    }
+
+    /// Reads `M` bytes
+    pub fn read_slice<const M: usize>(mut self) -> ([u8; M], ConstReader<T, { N - M }>) {
+        unsafe {
+            let value = self.reader_adapter.read();
+            (value, ConstReader { reader_adapter: self.reader_adapter })
+        }
+    }
+
+    /// Reads `M` bytes at `OFFSET` from current position, reader isn't advanced
+    ///
+    /// Peeking past end of reader doesn't compile:
+    /// ```compile_fail
+    /// use const_writer::ConstRead;
+    ///
+    /// let data = [0u8; 4];
+    /// let mut slice = &data[..];
+    /// slice.const_reader::<4>().read_at::<2, 4>();
+    /// ```
+    pub fn read_at<const OFFSET: usize, const M: usize>(&self) -> [u8; M] {
+        let () = Peek::<N, OFFSET, M>::CHECK;
+        unsafe { self.reader_adapter.peek(OFFSET) }
+    }
+
+    implement_peek!(peek_u8_le, u8, from_le_bytes);
+    implement_peek!(peek_u16_le, u16, from_le_bytes);
+    implement_peek!(peek_u32_le, u32, from_le_bytes);
+    implement_peek!(peek_u64_le, u64, from_le_bytes);
+
+    implement_peek!(peek_u8_be, u8, from_be_bytes);
+    implement_peek!(peek_u16_be, u16, from_be_bytes);
+    implement_peek!(peek_u32_be, u32, from_be_bytes);
+    implement_peek!(peek_u64_be, u64, from_be_bytes);
+
+    /// Reads [`ConstReadable`] value
+    pub fn read_value<V: ConstReadable>(mut self) -> (V, ConstReader<T, { N - V::SIZE }>) {
+        unsafe {
+            let value = V::read_from(&mut self.reader_adapter);
+            (value, ConstReader { reader_adapter: self.reader_adapter })
+        }
+    }
+}
+
+/// Moves `M` bytes from reader to writer, both budgets are reduced at compile time.
+/// ```
+/// use const_writer::{ConstRead, ConstWrite};
+///
+/// let data = [1u8, 2, 3, 4, 5, 6];
+/// let mut slice = &data[..];
+/// let mut vec = vec![];
+/// let reader = slice.const_reader::<6>();
+/// let (reader, writer) = const_writer::reader::copy::<4, _, _, _, _>(reader, vec.const_writer::<5>());
+/// let (last, _) = reader.read_u16_be();
+/// writer.write_u8_le(last as u8);
+/// assert_eq!(vec, [1, 2, 3, 4, 6]);
+/// ```
+#[cfg_attr(any(debug_assertions, feature = "stats"), track_caller)]
+pub fn copy<const M: usize, R: ConstReaderAdapter, const RN: usize, W: crate::ConstWriterAdapter, const WN: usize>(
+    reader: ConstReader<R, RN>,
+    writer: crate::ConstWriter<W, WN>,
+) -> (ConstReader<R, { RN - M }>, crate::ConstWriter<W, { WN - M }>) {
+    let (value, reader) = reader.read_slice::<M>();
+    (reader, writer.write_slice(&value))
+}
+
+/// Value with statically known size, which can be read with [`ConstReader::read_value`].
+///
+/// Usually implemented with [`const_layout!`](crate::const_layout) macro.
+///
+/// # Safety
+/// `read_from` must read at most `SIZE` bytes
+pub unsafe trait ConstReadable: Sized {
+    /// Number of bytes read by `read_from`
+    const SIZE: usize;
+
+    /// Reads value using adapter
+    ///
+    /// # Safety
+    /// Underlying buffer must have `SIZE` bytes to read
+    unsafe fn read_from<T: ConstReaderAdapter>(reader_adapter: &mut T) -> Self;
+}
+
+/// Get [`ConstReader`] for given type
+pub trait ConstRead<'a, T: ConstReaderAdapter + ConstReaderAdapterCreate<'a, Self>> {
+    /// Get [`ConstReader`] to read `N` bytes.
+    #[track_caller]
+    fn const_reader<const N: usize>(&'a mut self) -> ConstReader<T, {N}> {
+        unsafe {
+            ConstReader {
+                reader_adapter: T::new::<{ N }>(self)
+            }
+        }
+    }
+}
+
+/// Wrapper for `&[u8]`. Advances wrapped slice reference on drop.
+pub struct SliceReaderAdapter<'a, 'inner> {
+    /// original slice
+    slice: &'a mut &'inner [u8],
+    /// number of bytes read
+    read: usize,
+}
+
+unsafe impl<'a, 'inner> ConstReaderAdapterCreate<'a, &'inner [u8]> for SliceReaderAdapter<'a, 'inner> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(slice: &'a mut &'inner [u8]) -> Self {
+        assert!(
+            slice.len() >= N,
+            "slice too short: {} < {} ({})",
+            slice.len(),
+            N,
+            core::any::type_name::<Self>()
+        );
+        Self {
+            slice,
+            read: 0
+        }
+    }
+}
+
+impl<'a, 'inner> ConstReaderAdapter for SliceReaderAdapter<'a, 'inner> {
+    unsafe fn read<const N: usize>(&mut self) -> [u8; N] {
+        let value = self.peek(0);
+        self.read += N;
+        value
+    }
+
+    unsafe fn peek<const N: usize>(&self, offset: usize) -> [u8; N] {
+        core::ptr::read_unaligned(self.slice.as_ptr().add(self.read + offset).cast::<[u8; N]>())
+    }
+}
+
+impl<'a, 'inner> Drop for SliceReaderAdapter<'a, 'inner> {
+    /// When dropping adapter we advancing slice pointer
+    fn drop(&mut self) {
+        let slice: &'inner [u8] = self.slice;
+        *self.slice = unsafe { slice.get_unchecked(self.read..) };
+    }
+}
+
+impl<'a, 'inner> ConstRead<'a, SliceReaderAdapter<'a, 'inner>> for &'inner [u8] {}
+
+/// Reader for `VecDeque<u8>`, pops read bytes from front on drop
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub struct DequeReaderAdapter<'a> {
+    deque: &'a mut alloc::collections::VecDeque<u8>,
+    read: usize,
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+unsafe impl<'a> ConstReaderAdapterCreate<'a, alloc::collections::VecDeque<u8>> for DequeReaderAdapter<'a> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(deque: &'a mut alloc::collections::VecDeque<u8>) -> Self {
+        assert!(
+            deque.len() >= N,
+            "deque too short: {} < {} ({})",
+            deque.len(),
+            N,
+            core::any::type_name::<Self>()
+        );
+        Self {
+            deque,
+            read: 0
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'a> ConstReaderAdapter for DequeReaderAdapter<'a> {
+    unsafe fn read<const N: usize>(&mut self) -> [u8; N] {
+        let value = self.peek(0);
+        self.read += N;
+        value
+    }
+
+    unsafe fn peek<const N: usize>(&self, offset: usize) -> [u8; N] {
+        // bytes may wrap around ring buffer, so they are copied from both halves
+        let (front, back) = self.deque.as_slices();
+        let start = self.read + offset;
+        let mut value = [0u8; N];
+        if start + N <= front.len() {
+            value.copy_from_slice(front.get_unchecked(start..start + N));
+        } else {
+            for (dst, src) in value.iter_mut().zip(front.iter().chain(back).skip(start)) {
+                *dst = *src;
+            }
+        }
+        value
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'a> Drop for DequeReaderAdapter<'a> {
+    fn drop(&mut self) {
+        self.deque.drain(..self.read);
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'a> ConstRead<'a, DequeReaderAdapter<'a>> for alloc::collections::VecDeque<u8> {}
+
+/// Reader for `io::Cursor`, advances cursor position on drop
+#[cfg(feature = "std")]
+pub struct CursorReaderAdapter<'a, B: AsRef<[u8]>> {
+    cursor: &'a mut std::io::Cursor<B>,
+    /// cursor position on creation, checked to be inside buffer
+    start: usize,
+    read: usize,
+}
+
+#[cfg(feature = "std")]
+unsafe impl<'a, B: AsRef<[u8]>> ConstReaderAdapterCreate<'a, std::io::Cursor<B>> for CursorReaderAdapter<'a, B> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(cursor: &'a mut std::io::Cursor<B>) -> Self {
+        let len = cursor.get_ref().as_ref().len();
+        let start = <usize as core::convert::TryFrom<u64>>::try_from(cursor.position()).unwrap_or(usize::MAX).min(len);
+        assert!(
+            len - start >= N,
+            "cursor too short: {} < {} ({})",
+            len - start,
+            N,
+            core::any::type_name::<Self>()
+        );
+        Self {
+            cursor,
+            start,
+            read: 0
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, B: AsRef<[u8]>> ConstReaderAdapter for CursorReaderAdapter<'a, B> {
+    unsafe fn read<const N: usize>(&mut self) -> [u8; N] {
+        let value = self.peek(0);
+        self.read += N;
+        value
+    }
+
+    unsafe fn peek<const N: usize>(&self, offset: usize) -> [u8; N] {
+        let ptr = self.cursor.get_ref().as_ref().as_ptr().add(self.start + self.read + offset);
+        core::ptr::read_unaligned(ptr.cast::<[u8; N]>())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, B: AsRef<[u8]>> Drop for CursorReaderAdapter<'a, B> {
+    fn drop(&mut self) {
+        self.cursor.set_position((self.start + self.read) as u64);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, B: AsRef<[u8]>> ConstRead<'a, CursorReaderAdapter<'a, B>> for std::io::Cursor<B> {}
+
+/// Reader for `bytes::Bytes`, advances it on drop
+/// ```
+/// use const_writer::ConstRead;
+///
+/// let mut bytes = bytes::Bytes::from_static(&[0, 1, 2]);
+/// let (value, _) = bytes.const_reader::<2>().read_u16_be();
+/// assert_eq!((value, &bytes[..]), (1, &[2][..]));
+/// ```
+#[cfg(feature = "bytes")]
+pub struct BytesReaderAdapter<'a> {
+    bytes: &'a mut bytes::Bytes,
+    read: usize,
+}
+
+#[cfg(feature = "bytes")]
+unsafe impl<'a> ConstReaderAdapterCreate<'a, bytes::Bytes> for BytesReaderAdapter<'a> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(bytes: &'a mut bytes::Bytes) -> Self {
+        assert!(
+            bytes.len() >= N,
+            "bytes too short: {} < {} ({})",
+            bytes.len(),
+            N,
+            core::any::type_name::<Self>()
+        );
+        Self {
+            bytes,
+            read: 0
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<'a> ConstReaderAdapter for BytesReaderAdapter<'a> {
+    unsafe fn read<const N: usize>(&mut self) -> [u8; N] {
+        let value = self.peek(0);
+        self.read += N;
+        value
+    }
+
+    unsafe fn peek<const N: usize>(&self, offset: usize) -> [u8; N] {
+        core::ptr::read_unaligned(self.bytes.as_ptr().add(self.read + offset).cast::<[u8; N]>())
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<'a> Drop for BytesReaderAdapter<'a> {
+    fn drop(&mut self) {
+        bytes::Buf::advance(self.bytes, self.read);
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<'a> ConstRead<'a, BytesReaderAdapter<'a>> for bytes::Bytes {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConstRead, ConstWrite};
+
+    #[test]
+    fn slice_read_written() {
+        let mut buff = [0u8; 15];
+        buff.as_mut().const_writer::<15>()
+            .write_u8_le(0x01)
+            .write_u16_be(0x0203)
+            .write_u32_le(0x04050607)
+            .write_u64_be(0x08090A0B0C0D0E0F);
+
+        let mut slice = &buff[..];
+        let (a, reader) = slice.const_reader::<15>().read_u8_le();
+        let (b, reader) = reader.read_u16_be();
+        let (c, reader) = reader.read_u32_le();
+        let (d, reader) = reader.read_slice::<8>();
+        assert_eq!(reader.remaining(), 0);
+        assert_eq!((a, b, c), (0x01, 0x0203, 0x04050607));
+        assert_eq!(d, 0x08090A0B0C0D0E0Fu64.to_be_bytes());
+    }
+
+    #[test]
+    fn slice_peek_dispatch() {
+        let data = [2u8, 0, 0, 0, 7, 9];
+        let mut slice = &data[..];
+        let reader = slice.const_reader::<6>();
+        assert_eq!(reader.peek_u32_le(), 2);
+        assert_eq!(reader.read_at::<4, 2>(), [7, 9]);
+        let (kind, reader) = reader.read_u8_le();
+        assert_eq!((kind, reader.peek_u8_le()), (2, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn deque_and_cursor_read() {
+        let mut deque = std::collections::VecDeque::with_capacity(8);
+        deque.extend([0u8; 6].iter());
+        deque.drain(..6);
+        // wraps around end of ring buffer
+        deque.extend([1u8, 0, 0, 0, 2, 3].iter());
+        let (a, reader) = deque.const_reader::<5>().read_u32_le();
+        let (b, _) = reader.read_u8_le();
+        assert_eq!((a, b), (1, 2));
+        assert_eq!(deque, [3]);
+
+        let mut cursor = std::io::Cursor::new(vec![0u8, 0, 1, 2]);
+        cursor.set_position(1);
+        let (c, _) = cursor.const_reader::<2>().read_u16_be();
+        assert_eq!((c, cursor.position()), (1, 3));
+    }
+}
@@ -0,0 +1,244 @@
+//! Safe way to add new buffer type: implement [`ReserveBuffer`] and get [`ConstWrite`] for free.
+//!
+//! [`ReserveAdapter`] keeps track of written bytes, so buffer only hands out free space
+//! and commits written bytes when writer is dropped.
+//!
+#![cfg_attr(nightly, doc = "```")]
+#![cfg_attr(not(nightly), doc = "```ignore")]
+//! use core::mem::MaybeUninit;
+//! use const_writer::ConstWrite;
+//! use const_writer::reserve::ReserveBuffer;
+//!
+//! struct Log {
+//!     data: Vec<u8>,
+//!     records: usize,
+//! }
+//!
+//! impl ReserveBuffer for Log {
+//!     fn reserve(&mut self, len: usize) -> &mut [MaybeUninit<u8>] {
+//!         self.data.reserve(len);
+//!         self.data.spare_capacity_mut()
+//!     }
+//!
+//!     fn commit(&mut self, len: usize) {
+//!         // all `len` bytes are written by ConstWriter
+//!         unsafe { self.data.set_len(self.data.len() + len) };
+//!         self.records += 1;
+//!     }
+//! }
+//!
+//! let mut log = Log { data: vec![], records: 0 };
+//! log.const_writer::<4>().write_u32_le(1);
+//! log.const_writer::<2>().write_u16_be(2);
+//! assert_eq!(log.data, [1, 0, 0, 0, 0, 2]);
+//! assert_eq!(log.records, 2);
+//! ```
+use crate::{ConstWriterAdapter, ConstWriterAdapterCreate};
+#[cfg(nightly)]
+use crate::ConstWrite;
+use core::mem::MaybeUninit;
+
+/// Buffer which provides free space to [`ReserveAdapter`]
+pub trait ReserveBuffer {
+    /// Returns free space after committed bytes, at least `len` bytes long.
+    ///
+    /// Called again with larger `len` when writer grows. Returned space can move,
+    /// but bytes written to previously returned space since last commit must be kept.
+    fn reserve(&mut self, len: usize) -> &mut [MaybeUninit<u8>];
+
+    /// Marks `len` bytes at the start of reserved space as written
+    fn commit(&mut self, len: usize);
+}
+
+/// Adapter for any [`ReserveBuffer`], commits written bytes on drop.
+pub struct ReserveAdapter<'a, B: ReserveBuffer + ?Sized> {
+    buff: &'a mut B,
+    /// start of reserved space
+    ptr: *mut u8,
+    /// number of bytes written
+    written: usize,
+}
+
+impl<'a, B: ReserveBuffer + ?Sized> ReserveAdapter<'a, B> {
+    /// Same as `new`, but with length known only at runtime
+    #[track_caller]
+    pub(crate) fn with_len(buff: &'a mut B, len: usize) -> Self {
+        Self {
+            ptr: Self::reserved(buff, len),
+            buff,
+            written: 0,
+        }
+    }
+
+    #[track_caller]
+    fn reserved(buff: &mut B, len: usize) -> *mut u8 {
+        let space = buff.reserve(len);
+        assert!(
+            space.len() >= len,
+            "reserved space too short: {} < {} ({})",
+            space.len(),
+            len,
+            core::any::type_name::<Self>()
+        );
+        space.as_mut_ptr().cast::<u8>()
+    }
+}
+
+impl<'a, B: ReserveBuffer + ?Sized> crate::sealed::Sealed for ReserveAdapter<'a, B> {}
+
+unsafe impl<'a, B: ReserveBuffer + ?Sized> ConstWriterAdapterCreate<'a, B> for ReserveAdapter<'a, B> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(buff: &'a mut B) -> Self {
+        Self::with_len(buff, N)
+    }
+}
+
+impl<'a, B: ReserveBuffer + ?Sized> core::fmt::Debug for ReserveAdapter<'a, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a, B: ReserveBuffer + ?Sized> ConstWriterAdapter for ReserveAdapter<'a, B> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        core::ptr::copy_nonoverlapping(value.as_ptr(), self.ptr.add(self.written), N);
+        self.written += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(mut self) -> Self {
+        self.ptr = Self::reserved(self.buff, self.written + M);
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        core::ptr::write_bytes(self.ptr.add(self.written), crate::POISON, N);
+        self
+    }
+}
+
+impl<'a, B: ReserveBuffer + ?Sized> crate::GrowableAdapter for ReserveAdapter<'a, B> {}
+
+impl<'a, B: ReserveBuffer + ?Sized> Drop for ReserveAdapter<'a, B> {
+    /// When dropping adapter we commit written bytes
+    fn drop(&mut self) {
+        self.buff.commit(self.written);
+    }
+}
+
+#[cfg(nightly)]
+impl<'a, B: ReserveBuffer + ?Sized> ConstWrite<'a, ReserveAdapter<'a, B>> for B {}
+
+/// `BytesMut` reserves space with `BytesMut::reserve`, writer output can be frozen
+/// without copy with [`finish_freeze`](crate::ConstWriter::finish_freeze).
+/// ```
+/// use const_writer::ConstWrite;
+///
+/// let mut buff = bytes::BytesMut::new();
+/// let frame = buff.const_writer::<6>()
+///     .write_u16_be(1)
+///     .write_u32_be(2)
+///     .finish_freeze();
+/// let queued = frame.clone();
+/// assert_eq!(queued, [0, 1, 0, 0, 0, 2][..]);
+/// assert!(buff.is_empty());
+/// ```
+#[cfg(feature = "bytes")]
+impl ReserveBuffer for bytes::BytesMut {
+    fn reserve(&mut self, len: usize) -> &mut [MaybeUninit<u8>] {
+        let spare = self.capacity() - self.len();
+        if spare < len {
+            // `BytesMut::reserve` moves only `len()` bytes, but uncommitted bytes in spare space must be kept too
+            let mut grown = bytes::BytesMut::with_capacity((self.len() + len).max(self.capacity() * 2));
+            grown.extend_from_slice(self);
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.spare_capacity_mut().as_ptr(),
+                    grown.spare_capacity_mut().as_mut_ptr(),
+                    spare,
+                );
+            }
+            *self = grown;
+        }
+        self.spare_capacity_mut()
+    }
+
+    fn commit(&mut self, len: usize) {
+        // all `len` bytes are written by ConstWriter
+        unsafe { self.set_len(self.len() + len) };
+    }
+}
+
+#[cfg(all(feature = "bytes", nightly))]
+impl<'a, const N: usize> crate::ConstWriter<ReserveAdapter<'a, bytes::BytesMut>, {N}> {
+    /// Commits written bytes and splits them off `BytesMut` as `Bytes`, without copy.
+    /// Unused budget is dropped.
+    pub fn finish_freeze(self) -> bytes::Bytes {
+        let writer_adapter = core::mem::ManuallyDrop::new(self.writer_adapter);
+        // adapter isn't dropped, so buffer is committed here instead
+        let buff = unsafe { core::ptr::read(&writer_adapter.buff) };
+        buff.commit(writer_adapter.written);
+        let start = buff.len() - writer_adapter.written;
+        buff.split_off(start).freeze()
+    }
+}
+
+#[cfg(all(test, nightly))]
+mod tests {
+    use core::mem::MaybeUninit;
+    use crate::ConstWrite;
+    use super::ReserveBuffer;
+
+    struct Stack {
+        buff: [MaybeUninit<u8>; 8],
+        len: usize,
+    }
+
+    impl ReserveBuffer for Stack {
+        fn reserve(&mut self, _len: usize) -> &mut [MaybeUninit<u8>] {
+            &mut self.buff[self.len..]
+        }
+
+        fn commit(&mut self, len: usize) {
+            self.len += len;
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "reserved space too short: 2 < 4")]
+    fn reserve_too_short() {
+        let mut stack = Stack { buff: [MaybeUninit::uninit(); 8], len: 0 };
+        stack.const_writer::<6>().write_u32_le(1).write_u16_le(2);
+        assert_eq!(stack.len, 6);
+        stack.const_writer::<4>();
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn bytes_mut_freeze_keeps_previous_content() {
+        let mut buff = bytes::BytesMut::from(&b"head"[..]);
+        let frame = buff.const_writer::<8>().write_u16_le(1).finish_freeze();
+        assert_eq!(frame, [1, 0][..]);
+        assert_eq!(buff, b"head"[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn bytes_mut_grow_after_split_to() {
+        let mut buff = bytes::BytesMut::with_capacity(16);
+        buff.extend_from_slice(&[9; 8]);
+        let head = buff.split_to(8);
+        buff.const_writer::<4>()
+            .write_u32_le(0x01020304)
+            .grow_to::<12>()
+            .write_u32_le(0x05060708);
+        assert_eq!(buff, [4, 3, 2, 1, 8, 7, 6, 5][..]);
+        assert_eq!(head, [9; 8][..]);
+    }
+}
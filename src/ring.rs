@@ -0,0 +1,279 @@
+//! Single producer single consumer ring buffer with const capacity, which accepts records through [`ConstWriter`].
+//!
+//! [`RingBuffer::split`] gives [`Producer`] and [`Consumer`] handles, which can be moved to different threads.
+//! [`ConstWrite::const_writer`] of producer requires `N` contiguous free bytes, so record is never split
+//! between end and start of buffer. [`Producer::wrapping_const_writer`] only requires `N` free bytes
+//! and splits writes crossing end of buffer. Written bytes become visible to consumer when writer is dropped.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//! use const_writer::ring::RingBuffer;
+//!
+//! let mut ring = RingBuffer::<8>::new();
+//! let (mut producer, mut consumer) = ring.split();
+//! producer.const_writer::<6>().write_u32_le(1).write_u16_le(2);
+//! assert_eq!(consumer.pop::<4>(), Some([1, 0, 0, 0]));
+//!
+//! // 2 bytes left at the end, but 6 free in total
+//! producer.wrapping_const_writer::<4>().write_u32_be(0x03040506);
+//! assert_eq!(consumer.pop::<6>(), Some([2, 0, 3, 4, 5, 6]));
+//! ```
+//!
+//! ```compile_fail
+//! let ring = const_writer::ring::RingBuffer::<0>::new(); // capacity must be non-zero
+//! ```
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use crate::{ConstWriter, ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate};
+
+/// Storage of ring buffer, accessed through handles from [`RingBuffer::split`]
+pub struct RingBuffer<const CAP: usize> {
+    buff: UnsafeCell<[u8; CAP]>,
+    /// total number of read bytes, position of first byte to read is `head % CAP`
+    head: AtomicUsize,
+    /// total number of written bytes
+    tail: AtomicUsize,
+}
+
+/// Producer writes only free part of buffer and consumer only stored part, and only one of each exists
+unsafe impl<const CAP: usize> Sync for RingBuffer<CAP> {}
+
+impl<const CAP: usize> RingBuffer<CAP> {
+    const CHECK: () = assert!(CAP > 0, "ring buffer capacity must be non-zero");
+
+    pub fn new() -> Self {
+        let () = Self::CHECK;
+        Self {
+            buff: UnsafeCell::new([0; CAP]),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Splits buffer into writing and reading halves
+    pub fn split(&mut self) -> (Producer<'_, CAP>, Consumer<'_, CAP>) {
+        (Producer { ring: self }, Consumer { ring: self })
+    }
+
+    fn ptr(&self) -> *mut u8 {
+        self.buff.get().cast::<u8>()
+    }
+}
+
+impl<const CAP: usize> Default for RingBuffer<CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writing half of [`RingBuffer`]
+pub struct Producer<'r, const CAP: usize> {
+    ring: &'r RingBuffer<CAP>,
+}
+
+impl<'r, const CAP: usize> Producer<'r, CAP> {
+    /// Number of free bytes, can only grow while consumer reads
+    pub fn free(&self) -> usize {
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        CAP - tail.wrapping_sub(self.ring.head.load(Ordering::Acquire))
+    }
+
+    /// Number of free bytes which can be written without wrap-around
+    pub fn contiguous_free(&self) -> usize {
+        self.free().min(CAP - self.write_pos())
+    }
+
+    fn write_pos(&self) -> usize {
+        self.ring.tail.load(Ordering::Relaxed) % CAP
+    }
+
+    /// Get [`ConstWriter`] to write `N` bytes, which can be split between end and start of buffer.
+    ///
+    /// # Panics
+    /// If there are less than `N` free bytes
+    #[track_caller]
+    pub fn wrapping_const_writer<const N: usize>(&mut self) -> ConstWriter<RingWriterAdapter<'_, 'r, CAP>, N> {
+        assert!(
+            self.free() >= N,
+            "ring buffer too short: {} < {} ({})",
+            self.free(),
+            N,
+            core::any::type_name::<Self>()
+        );
+        unsafe {
+            ConstWriter {
+                writer_adapter: crate::poisoned::<_, N>(RingWriterAdapter {
+                    pos: self.write_pos(),
+                    written: 0,
+                    wrapping: true,
+                    producer: self,
+                })
+            }
+        }
+    }
+}
+
+/// Reading half of [`RingBuffer`]
+pub struct Consumer<'r, const CAP: usize> {
+    ring: &'r RingBuffer<CAP>,
+}
+
+impl<'r, const CAP: usize> Consumer<'r, CAP> {
+    /// Number of stored bytes, can only grow while producer writes
+    pub fn len(&self) -> usize {
+        let head = self.ring.head.load(Ordering::Relaxed);
+        self.ring.tail.load(Ordering::Acquire).wrapping_sub(head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes and returns `M` oldest bytes, if there are enough stored
+    pub fn pop<const M: usize>(&mut self) -> Option<[u8; M]> {
+        if self.len() < M {
+            return None;
+        }
+        let head = self.ring.head.load(Ordering::Relaxed);
+        let read = head % CAP;
+        let mut value = [0u8; M];
+        let first = M.min(CAP - read);
+        unsafe {
+            let ptr = self.ring.ptr();
+            core::ptr::copy_nonoverlapping(ptr.add(read), value.as_mut_ptr(), first);
+            core::ptr::copy_nonoverlapping(ptr, value.as_mut_ptr().add(first), M - first);
+        }
+        self.ring.head.store(head.wrapping_add(M), Ordering::Release);
+        Some(value)
+    }
+}
+
+pub struct RingWriterAdapter<'a, 'r, const CAP: usize> {
+    producer: &'a mut Producer<'r, CAP>,
+    /// position of next byte to write
+    pos: usize,
+    written: usize,
+    wrapping: bool,
+}
+
+unsafe impl<'a, 'r, const CAP: usize> ConstWriterAdapterCreate<'a, Producer<'r, CAP>> for RingWriterAdapter<'a, 'r, CAP> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(producer: &'a mut Producer<'r, CAP>) -> Self {
+        assert!(
+            producer.contiguous_free() >= N,
+            "ring buffer contiguous space too short: {} < {} ({})",
+            producer.contiguous_free(),
+            N,
+            core::any::type_name::<Self>()
+        );
+        Self {
+            pos: producer.write_pos(),
+            written: 0,
+            wrapping: false,
+            producer,
+        }
+    }
+}
+
+impl<'a, 'r, const CAP: usize> crate::sealed::Sealed for RingWriterAdapter<'a, 'r, CAP> {}
+
+impl<'a, 'r, const CAP: usize> core::fmt::Debug for RingWriterAdapter<'a, 'r, CAP> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a, 'r, const CAP: usize> ConstWriterAdapter for RingWriterAdapter<'a, 'r, CAP> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        let ptr = self.producer.ring.ptr();
+        let first = N.min(CAP - self.pos);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr.add(self.pos), first);
+        core::ptr::copy_nonoverlapping(value.as_ptr().add(first), ptr, N - first);
+        self.pos = (self.pos + N) % CAP;
+        self.written += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        let available = if self.wrapping {
+            self.producer.free() - self.written
+        } else {
+            self.producer.contiguous_free() - self.written
+        };
+        assert!(
+            M <= available,
+            "remaining ring buffer too short to grow: {} < {} ({})",
+            available,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        let ptr = self.producer.ring.ptr();
+        let first = N.min(CAP - self.pos);
+        core::ptr::write_bytes(ptr.add(self.pos), crate::POISON, first);
+        core::ptr::write_bytes(ptr, crate::POISON, N - first);
+        self
+    }
+}
+
+impl<'a, 'r, const CAP: usize> Drop for RingWriterAdapter<'a, 'r, CAP> {
+    /// When dropping adapter we publish written bytes to consumer
+    fn drop(&mut self) {
+        let tail = self.producer.ring.tail.load(Ordering::Relaxed);
+        self.producer.ring.tail.store(tail.wrapping_add(self.written), Ordering::Release);
+    }
+}
+
+impl<'a, 'r, const CAP: usize> ConstWrite<'a, RingWriterAdapter<'a, 'r, CAP>> for Producer<'r, CAP> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::RingBuffer;
+
+    #[test]
+    #[should_panic(expected = "contiguous space too short")]
+    fn ring_contiguous_required() {
+        let mut ring = RingBuffer::<8>::new();
+        let (mut producer, mut consumer) = ring.split();
+        producer.const_writer::<6>().write_u32_le(1).write_u16_le(2);
+        consumer.pop::<4>();
+        assert_eq!(producer.free(), 6);
+        producer.const_writer::<4>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn ring_spsc_threads() {
+        let mut ring = RingBuffer::<16>::new();
+        let (mut producer, mut consumer) = ring.split();
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..1000u32 {
+                    while producer.free() < 4 {
+                        std::hint::spin_loop();
+                    }
+                    producer.wrapping_const_writer::<4>().write_u32_le(i);
+                }
+            });
+            for i in 0..1000u32 {
+                let value = loop {
+                    if let Some(value) = consumer.pop::<4>() {
+                        break value;
+                    }
+                    std::hint::spin_loop();
+                };
+                assert_eq!(u32::from_le_bytes(value), i);
+            }
+        });
+    }
+}
@@ -0,0 +1,142 @@
+//! Containers of [Roaring bitmap format](https://github.com/RoaringBitmap/RoaringFormatSpec).
+//!
+//! Container holds low 16 bits of values sharing high 16 bits (key). Array containers of
+//! up to [`ARRAY_MAX`] values are sorted `u16`, bitmap containers are 1024 `u64` words,
+//! both little endian.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::roaring;
+//!
+//! let mut vec = vec![];
+//! let writer = roaring::write_key_cardinality(vec.const_writer::<10>(), 1, 3);
+//! roaring::write_array_container::<3, _, _>(writer, &[5, 7, 0xFFFF]);
+//! assert_eq!(vec, [1, 0, 2, 0, 5, 0, 7, 0, 0xFF, 0xFF]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Maximum number of values in array container, bigger containers are bitmaps
+pub const ARRAY_MAX: usize = 4096;
+/// Length of bitmap container
+pub const BITMAP_LEN: usize = 8192;
+
+/// Compile time check of array container length
+struct ArrayLen<const COUNT: usize>;
+
+impl<const COUNT: usize> ArrayLen<COUNT> {
+    const CHECK: () = assert!(COUNT >= 1 && COUNT <= ARRAY_MAX, "array container holds 1 to 4096 values");
+}
+
+/// Writes key and cardinality of container as `u16` pair, cardinality is stored minus one
+///
+/// # Panics
+/// If `cardinality` isn't in `1..=65536`
+#[track_caller]
+pub fn write_key_cardinality<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, key: u16, cardinality: u32) -> ConstWriter<T, {N - 4}> {
+    assert!((1..=65536).contains(&cardinality), "container cardinality out of range: {}", cardinality);
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&key.to_le_bytes())
+                .write(&((cardinality - 1) as u16).to_le_bytes()),
+        }
+    }
+}
+
+/// Writes array container of `values`
+///
+/// # Panics
+/// If `values` aren't strictly increasing
+#[track_caller]
+pub fn write_array_container<const COUNT: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, values: &[u16; COUNT]) -> ConstWriter<T, {N - 2 * COUNT}> {
+    let () = ArrayLen::<COUNT>::CHECK;
+    for pair in values.windows(2) {
+        assert!(pair[0] < pair[1], "array container isn't sorted: {} after {}", pair[1], pair[0]);
+    }
+    let mut writer_adapter = writer.writer_adapter;
+    for value in values.iter() {
+        writer_adapter = unsafe { writer_adapter.write(&value.to_le_bytes()) };
+    }
+    ConstWriter { writer_adapter }
+}
+
+/// Writes bitmap container
+pub fn write_bitmap_container<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, bitmap: &Bitmap) -> ConstWriter<T, {N - BITMAP_LEN}> {
+    let mut writer_adapter = writer.writer_adapter;
+    for word in bitmap.words.iter() {
+        writer_adapter = unsafe { writer_adapter.write(&word.to_le_bytes()) };
+    }
+    ConstWriter { writer_adapter }
+}
+
+/// Bits of bitmap container
+#[derive(Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    words: [u64; 1024],
+}
+
+impl Bitmap {
+    pub const fn new() -> Self {
+        Self { words: [0; 1024] }
+    }
+
+    pub fn insert(&mut self, value: u16) {
+        self.words[value as usize / 64] |= 1 << (value % 64);
+    }
+
+    pub fn contains(&self, value: u16) -> bool {
+        self.words[value as usize / 64] & 1 << (value % 64) != 0
+    }
+
+    /// Number of set bits
+    pub fn cardinality(&self) -> u32 {
+        self.words.iter().map(|word| word.count_ones()).sum()
+    }
+}
+
+impl Default for Bitmap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for Bitmap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Bitmap")
+            .field("cardinality", &self.cardinality())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::Bitmap;
+
+    #[test]
+    fn roaring_bitmap_container() {
+        let mut bitmap = Bitmap::new();
+        for value in (0..=u16::MAX).step_by(2) {
+            bitmap.insert(value);
+        }
+        assert_eq!(bitmap.cardinality(), 32768);
+        let mut buff = [0u8; 8196];
+        let writer = super::write_key_cardinality(buff.const_writer::<8196>(), 0, bitmap.cardinality());
+        super::write_bitmap_container(writer, &bitmap);
+        assert_eq!(buff[..6], [0, 0, 0xFF, 0x7F, 0x55, 0x55]);
+        assert!(buff[4..].iter().all(|byte| *byte == 0x55));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn roaring_array_checked_before_write() {
+        let mut buff = [0xAAu8; 6];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            super::write_array_container::<3, _, _>(buff.const_writer::<6>(), &[1, 5, 5]);
+        }));
+        assert!(result.is_err());
+        assert_eq!(buff, [0xAA; 6]);
+    }
+}
@@ -0,0 +1,156 @@
+//! Conformance check for types implementing both [`ConstWritable`] and [`ConstReadable`].
+//!
+//! Value is written, read back and compared, so format crates get one-liner test
+//! for every type. Types defined with [`const_layout!`](crate::const_layout) get both impls.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! const_writer::const_layout! {
+//!     #[derive(Debug, PartialEq)]
+//!     struct Header {
+//!         kind: u8,
+//!         len: u32_be,
+//!     }
+//! }
+//!
+//! const_writer::roundtrip::assert_roundtrip(&Header { kind: 1, len: 2 });
+//! ```
+//!
+//! There is no `#[derive(ConstReadable)]`, crate has no proc macros, use `const_layout!` instead.
+use core::fmt::Debug;
+use crate::{ConstReadable, ConstWritable, ConstWriterAdapter};
+use crate::reader::{ConstReaderAdapterCreate, SliceReaderAdapter};
+
+/// Adapter which checks every write against buffer, so value overrunning its `SIZE`
+/// panics in release builds too instead of writing past buffer
+struct BoundedAdapter<'a> {
+    buff: &'a mut [u8],
+    written: usize,
+}
+
+impl<'a> crate::sealed::Sealed for BoundedAdapter<'a> {}
+
+impl<'a> core::fmt::Debug for BoundedAdapter<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a> ConstWriterAdapter for BoundedAdapter<'a> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    #[track_caller]
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        assert!(
+            N <= self.buff.len() - self.written,
+            "value overran its SIZE: {} > {}",
+            self.written + N,
+            self.buff.len()
+        );
+        self.buff[self.written..self.written + N].copy_from_slice(value);
+        self.written += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= self.buff.len() - self.written,
+            "value overran its SIZE: {} > {}",
+            self.written + M,
+            self.buff.len()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        self
+    }
+}
+
+/// Writes `value`, reads it back and asserts that it is equal to original.
+///
+/// # Panics
+/// If sizes of impls differ, write doesn't fill `SIZE` bytes or overruns it, or read value isn't equal
+#[track_caller]
+pub fn assert_roundtrip<V>(value: &V)
+where
+    V: ConstWritable + ConstReadable + PartialEq + Debug,
+    [(); <V as ConstWritable>::SIZE]:,
+{
+    assert_eq!(
+        <V as ConstWritable>::SIZE,
+        <V as ConstReadable>::SIZE,
+        "ConstWritable and ConstReadable sizes differ"
+    );
+    let mut buff = [0u8; <V as ConstWritable>::SIZE];
+    // write through adapter directly, every write is bounds checked
+    let writer_adapter = unsafe { value.write_to(BoundedAdapter { buff: &mut buff, written: 0 }) };
+    assert_eq!(writer_adapter.written, <V as ConstWritable>::SIZE, "value didn't fill its SIZE");
+
+    let mut read = &buff[..];
+    let read_value = unsafe {
+        let mut reader_adapter = SliceReaderAdapter::new::<{ <V as ConstWritable>::SIZE }>(&mut read);
+        V::read_from(&mut reader_adapter)
+    };
+    assert_eq!(&read_value, value, "read value differs from written");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConstReadable, ConstReaderAdapter, ConstWritable, ConstWriterAdapter};
+
+    #[derive(Debug, PartialEq)]
+    struct Swapped(u16);
+
+    unsafe impl ConstWritable for Swapped {
+        const SIZE: usize = 2;
+
+        unsafe fn write_to<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+            writer_adapter.write(&self.0.to_le_bytes())
+        }
+    }
+
+    unsafe impl ConstReadable for Swapped {
+        const SIZE: usize = 2;
+
+        unsafe fn read_from<T: ConstReaderAdapter>(reader_adapter: &mut T) -> Self {
+            Self(u16::from_be_bytes(reader_adapter.read()))
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Overrun(u16);
+
+    unsafe impl ConstWritable for Overrun {
+        const SIZE: usize = 2;
+
+        unsafe fn write_to<T: ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+            writer_adapter.write(&(self.0 as u32).to_le_bytes())
+        }
+    }
+
+    unsafe impl ConstReadable for Overrun {
+        const SIZE: usize = 2;
+
+        unsafe fn read_from<T: ConstReaderAdapter>(reader_adapter: &mut T) -> Self {
+            Self(u16::from_le_bytes(reader_adapter.read()))
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "value overran its SIZE: 4 > 2")]
+    fn roundtrip_overrun() {
+        super::assert_roundtrip(&Overrun(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "read value differs from written")]
+    fn roundtrip_endian_mismatch() {
+        super::assert_roundtrip(&Swapped(0x0102));
+    }
+}
@@ -0,0 +1,80 @@
+//! [RTP](https://www.rfc-editor.org/rfc/rfc3550#section-5.1) fixed header.
+//!
+//! Header with `CSRC_COUNT` contributing sources takes `12 + 4 * CSRC_COUNT` bytes,
+//! fields are masked to their widths, so writing header never panics.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::rtp::{self, Header};
+//!
+//! let header = Header { marker: true, payload_type: 96, sequence: 1, timestamp: 3000, ssrc: 0xDEADBEEF, ..Header::default() };
+//! let mut vec = vec![];
+//! rtp::write_header::<1, _, _>(vec.const_writer::<16>(), &header, &[7]);
+//! assert_eq!(vec, [0x81, 0xE0, 0, 1, 0, 0, 0x0B, 0xB8, 0xDE, 0xAD, 0xBE, 0xEF, 0, 0, 0, 7]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// RTP version written to header
+pub const VERSION: u8 = 2;
+
+/// Compile time check of CSRC count
+struct CsrcCount<const CSRC_COUNT: usize>;
+
+impl<const CSRC_COUNT: usize> CsrcCount<CSRC_COUNT> {
+    const CHECK: () = assert!(CSRC_COUNT <= 15, "RTP header has up to 15 CSRCs");
+}
+
+/// Fields of fixed header, version is always [`VERSION`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Header {
+    pub padding: bool,
+    pub extension: bool,
+    pub marker: bool,
+    /// 7 bit payload type, higher bit is ignored
+    pub payload_type: u8,
+    pub sequence: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+}
+
+/// Writes header followed by `CSRC_COUNT` contributing sources
+pub fn write_header<const CSRC_COUNT: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    header: &Header,
+    csrcs: &[u32; CSRC_COUNT],
+) -> ConstWriter<T, {N - (12 + 4 * CSRC_COUNT)}> {
+    let () = CsrcCount::<CSRC_COUNT>::CHECK;
+    let sequence = header.sequence.to_be_bytes();
+    let timestamp = header.timestamp.to_be_bytes();
+    let ssrc = header.ssrc.to_be_bytes();
+    let fixed = [
+        VERSION << 6 | (header.padding as u8) << 5 | (header.extension as u8) << 4 | CSRC_COUNT as u8,
+        (header.marker as u8) << 7 | (header.payload_type & 0x7F),
+        sequence[0], sequence[1],
+        timestamp[0], timestamp[1], timestamp[2], timestamp[3],
+        ssrc[0], ssrc[1], ssrc[2], ssrc[3],
+    ];
+    let mut writer_adapter = unsafe { writer.writer_adapter.write(&fixed) };
+    for csrc in csrcs {
+        writer_adapter = unsafe { writer_adapter.write(&csrc.to_be_bytes()) };
+    }
+    ConstWriter {
+        writer_adapter,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn rtp_header_bits() {
+        let header = super::Header { padding: true, extension: true, payload_type: 0xFF, ..Default::default() };
+        let mut buff = [0u8; 12];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_header::<0, _, _>(ref_buff.const_writer::<12>(), &header, &[]);
+        assert_eq!(buff[..2], [0xB0, 0x7F]);
+    }
+}
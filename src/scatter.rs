@@ -0,0 +1,170 @@
+//! Adapter over list of segments, like iovec or io_uring registered buffers.
+//!
+//! Memory comes in fixed chunks, writer gets total budget checked against space left in all
+//! segments, and every write is split across segment boundaries.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//! use const_writer::scatter::ScatterBuffer;
+//!
+//! let (mut a, mut b) = ([0u8; 3], [0u8; 4]);
+//! let mut segments = [&mut a[..], &mut b[..]];
+//! let mut scatter = ScatterBuffer::new(&mut segments);
+//! scatter.const_writer::<6>()
+//!     .write_u16_be(1)
+//!     .write_u32_be(2);
+//! assert_eq!(scatter.written(), 6);
+//! assert_eq!(scatter.remaining(), 1);
+//! assert_eq!((a, b), ([0, 1, 0], [0, 0, 2, 0]));
+//! ```
+use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate};
+
+/// List of segments filled one after another
+pub struct ScatterBuffer<'s, 'seg> {
+    segments: &'s mut [&'seg mut [u8]],
+    /// current segment and offset in it
+    segment: usize,
+    offset: usize,
+    written: usize,
+    remaining: usize,
+}
+
+impl<'s, 'seg> ScatterBuffer<'s, 'seg> {
+    pub fn new(segments: &'s mut [&'seg mut [u8]]) -> Self {
+        let remaining = segments.iter().map(|s| s.len()).sum();
+        Self {
+            segments,
+            segment: 0,
+            offset: 0,
+            written: 0,
+            remaining,
+        }
+    }
+
+    /// Number of bytes written to all segments
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Number of bytes left in all segments
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Index of segment and offset in it where next byte is written
+    pub fn position(&self) -> (usize, usize) {
+        (self.segment, self.offset)
+    }
+
+    /// Copies `value` starting from current position, caller checks that it fits
+    unsafe fn put(&mut self, mut value: &[u8]) {
+        while !value.is_empty() {
+            let segment = self.segments.get_unchecked_mut(self.segment);
+            let len = value.len().min(segment.len() - self.offset);
+            segment.get_unchecked_mut(self.offset..self.offset + len)
+                .copy_from_slice(value.get_unchecked(..len));
+            value = value.get_unchecked(len..);
+            self.offset += len;
+            if self.offset == segment.len() {
+                self.segment += 1;
+                self.offset = 0;
+            }
+        }
+    }
+}
+
+/// Writes to [`ScatterBuffer`], advances it on every write
+pub struct ScatterWriterAdapter<'a, 's, 'seg> {
+    buff: &'a mut ScatterBuffer<'s, 'seg>,
+    written: usize,
+}
+
+unsafe impl<'a, 's, 'seg> ConstWriterAdapterCreate<'a, ScatterBuffer<'s, 'seg>> for ScatterWriterAdapter<'a, 's, 'seg> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(buff: &'a mut ScatterBuffer<'s, 'seg>) -> Self {
+        assert!(
+            N <= buff.remaining,
+            "segments too short: {} < {} ({})",
+            buff.remaining,
+            N,
+            core::any::type_name::<Self>()
+        );
+        Self {
+            buff,
+            written: 0,
+        }
+    }
+}
+
+impl<'a, 's, 'seg> crate::sealed::Sealed for ScatterWriterAdapter<'a, 's, 'seg> {}
+
+impl<'a, 's, 'seg> core::fmt::Debug for ScatterWriterAdapter<'a, 's, 'seg> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a, 's, 'seg> ConstWriterAdapter for ScatterWriterAdapter<'a, 's, 'seg> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        self.buff.put(value);
+        self.buff.written += N;
+        self.buff.remaining -= N;
+        self.written += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= self.buff.remaining,
+            "remaining segments too short to grow: {} < {} ({})",
+            self.buff.remaining,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        // poison without advancing
+        let (segment, offset) = self.buff.position();
+        self.buff.put(&[crate::POISON; N]);
+        self.buff.segment = segment;
+        self.buff.offset = offset;
+        self
+    }
+}
+
+impl<'a, 's, 'seg> ConstWrite<'a, ScatterWriterAdapter<'a, 's, 'seg>> for ScatterBuffer<'s, 'seg> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::ScatterBuffer;
+
+    #[test]
+    fn scatter_skips_empty_segments() {
+        let (mut a, mut b, mut c) = ([0u8; 1], [0u8; 0], [0u8; 8]);
+        let mut segments = [&mut a[..], &mut b[..], &mut c[..]];
+        let mut scatter = ScatterBuffer::new(&mut segments);
+        scatter.const_writer::<4>().write_u32_le(0x04030201);
+        scatter.const_writer::<2>().write_u16_le(0x0605);
+        assert_eq!(scatter.position(), (2, 5));
+        assert_eq!((a, c), ([1], [2, 3, 4, 5, 6, 0, 0, 0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "segments too short: 3 < 4")]
+    fn scatter_too_short() {
+        let (mut a, mut b) = ([0u8; 2], [0u8; 3]);
+        let mut segments = [&mut a[..], &mut b[..]];
+        let mut scatter = ScatterBuffer::new(&mut segments);
+        scatter.const_writer::<2>().write_u16_le(1);
+        scatter.const_writer::<4>();
+    }
+}
@@ -0,0 +1,124 @@
+//! Adapter over `Arc<Mutex<Vec<u8>>>`, so several threads can append records into one buffer.
+//!
+//! Lock is taken on writer creation and released when writer is dropped,
+//! so record is always appended as a whole.
+//!
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use const_writer::ConstWrite;
+//!
+//! let log = Arc::new(Mutex::new(Vec::new()));
+//! let handles: Vec<_> = (0..4u32).map(|i| {
+//!     let mut log = log.clone();
+//!     std::thread::spawn(move || {
+//!         log.const_writer::<8>()
+//!             .write_u32_le(i)
+//!             .write_u32_le(i * 10);
+//!     })
+//! }).collect();
+//! handles.into_iter().for_each(|h| h.join().unwrap());
+//!
+//! assert_eq!(log.lock().unwrap().len(), 32);
+//! ```
+use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+pub struct SharedVecAdapter<'a> {
+    guard: MutexGuard<'a, Vec<u8>>,
+    /// offset of next byte to write from start of vector buffer
+    pos: usize,
+}
+
+unsafe impl<'a> ConstWriterAdapterCreate<'a, Arc<Mutex<Vec<u8>>>> for SharedVecAdapter<'a> {
+    /// # Panics
+    /// If mutex is poisoned
+    #[track_caller]
+    unsafe fn new<const N: usize>(buff: &'a mut Arc<Mutex<Vec<u8>>>) -> Self {
+        let mut guard = buff.lock().expect("shared buffer mutex is poisoned");
+        guard.reserve(N);
+        Self {
+            pos: guard.len(),
+            guard,
+        }
+    }
+}
+
+impl<'a> crate::sealed::Sealed for SharedVecAdapter<'a> {}
+
+impl<'a> core::fmt::Debug for SharedVecAdapter<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a> ConstWriterAdapter for SharedVecAdapter<'a> {
+    fn written_so_far(&self) -> usize {
+        self.pos - self.guard.len()
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        debug_assert!(
+            self.pos + N <= self.guard.capacity(),
+            "vector capacity changed during write: {} < {}",
+            self.guard.capacity(),
+            self.pos + N
+        );
+        let ptr = self.guard.as_mut_ptr().add(self.pos);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr, N);
+        self.pos += N;
+        self
+    }
+
+    unsafe fn grow<const M: usize>(mut self) -> Self {
+        let additional = self.pos - self.guard.len() + M;
+        self.guard.reserve(additional);
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(mut self) -> Self {
+        core::ptr::write_bytes(self.guard.as_mut_ptr().add(self.pos), crate::POISON, N);
+        self
+    }
+}
+
+impl<'a> Drop for SharedVecAdapter<'a> {
+    /// When dropping adapter we advancing vector and releasing lock
+    fn drop(&mut self) {
+        unsafe {
+            let pos = self.pos;
+            self.guard.set_len(pos);
+        }
+    }
+}
+
+impl<'a> crate::GrowableAdapter for SharedVecAdapter<'a> {}
+
+impl<'a> ConstWrite<'a, SharedVecAdapter<'a>> for Arc<Mutex<Vec<u8>>> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    #[allow(deprecated)]
+    fn shared_write_grow() {
+        let mut shared = Arc::new(Mutex::new(vec![9u8]));
+        shared.const_writer::<2>()
+            .write_u16_le(1)
+            .convert::<4>()
+            .write_u32_be(2);
+        assert_eq!(*shared.lock().unwrap(), [9, 1, 0, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn shared_write_grow_to() {
+        let mut shared = Arc::new(Mutex::new(vec![9u8]));
+        shared.const_writer::<2>()
+            .write_u16_le(1)
+            .grow_to::<4>()
+            .write_u32_be(2);
+        assert_eq!(*shared.lock().unwrap(), [9, 1, 0, 0, 0, 0, 2]);
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate};
+use crate::{ConstWriterAdapter, ConstWriterAdapterCreate};
+#[cfg(nightly)]
+use crate::{ConstWrite, TryConstWrite};
+
+/// [`ConstWriter`](crate::ConstWriter) over slice, to shorten signatures
+#[cfg(nightly)]
+pub type SliceWriter<'a, 'inner, const N: usize> = crate::ConstWriter<SliceWriterAdapter<'a, 'inner>, N>;
 
 /// Wrapper for `&mut [u8]`. Advances wrapped slice reference on drop.
 /// pub user is not intended
@@ -21,62 +27,190 @@ use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate};
 pub struct SliceWriterAdapter<'a, 'inner> {
     /// original slice
     slice: &'a mut &'inner mut [u8],
-    /// ptr to slice data
-    ptr: *mut u8
-
+    /// number of bytes written
+    written: usize,
 }
 
 unsafe impl<'a, 'inner> ConstWriterAdapterCreate<'a, &'inner mut [u8]> for SliceWriterAdapter<'a, 'inner> {
+    #[track_caller]
     unsafe fn new<const N: usize>(slice: &'a mut &'inner mut [u8]) -> Self {
+        Self::with_len(slice, N)
+    }
+}
+
+impl<'a, 'inner> SliceWriterAdapter<'a, 'inner> {
+    /// Same as `new`, but with length known only at runtime
+    #[track_caller]
+    pub(crate) unsafe fn with_len(slice: &'a mut &'inner mut [u8], len: usize) -> Self {
         assert!(
-            slice.len() >= N,
-            "slice too short: {} < {}",
+            slice.len() >= len,
+            "slice too short: {} < {} ({})",
             slice.len(),
-            N
+            len,
+            core::any::type_name::<Self>()
         );
-        let ptr = core::mem::transmute::<_, *mut u8>(slice.as_mut_ptr());
         Self {
             slice,
-            ptr
+            written: 0
         }
     }
 }
 
+impl<'a, 'inner> crate::sealed::Sealed for SliceWriterAdapter<'a, 'inner> {}
+
+impl<'a, 'inner> core::fmt::Debug for SliceWriterAdapter<'a, 'inner> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
 impl<'a, 'inner> ConstWriterAdapter for SliceWriterAdapter<'a, 'inner> {
+    fn written_so_far(&self) -> usize {
+        self.written
+    }
+
     // Because we have exclusive access to slice pointer we can wait with it's modification until adapter is dropped
-    unsafe fn write<const N: usize>(mut self, value: &[u8; N]) -> Self {
-        core::ptr::copy_nonoverlapping(value.as_ptr(), self.ptr, N);
-        self.ptr = self.ptr.add(N);
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        let ptr = self.slice.as_mut_ptr().add(self.written);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr, N);
+        self.written += N;
         self
     }
 
+    #[track_caller]
     unsafe fn grow<const M: usize>(self) -> Self {
-        let diff = self.ptr.offset_from(self.slice.as_ptr()) as usize;
         assert!(
-            M <= self.slice.len() - diff,
-            "remaining slice too short to grow: {} < {}",
-            self.slice.len() - diff,
-            M
+            M <= self.slice.len() - self.written,
+            "remaining slice too short to grow: {} < {} ({})",
+            self.slice.len() - self.written,
+            M,
+            core::any::type_name::<Self>()
         );
         self
     }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        core::ptr::write_bytes(self.slice.as_mut_ptr().add(self.written), crate::POISON, N);
+        self
+    }
 }
 
 impl<'a, 'inner> Drop for SliceWriterAdapter<'a, 'inner> {
     /// When dropping adapter we advancing slice pointer
     fn drop(&mut self) {
-        unsafe {
-            let diff = self.ptr.offset_from(self.slice.as_ptr()) as usize;
-            *self.slice = core::slice::from_raw_parts_mut(self.ptr, self.slice.len() - diff);
-        }
+        let slice = core::mem::take(self.slice);
+        *self.slice = unsafe { slice.get_unchecked_mut(self.written..) };
     }
 }
 
+#[cfg(nightly)]
 impl<'a, 'inner> ConstWrite<'a, SliceWriterAdapter<'a, 'inner>> for &'inner mut [u8] {}
 
+#[cfg(nightly)]
+impl<'a, 'inner> TryConstWrite<'a, SliceWriterAdapter<'a, 'inner>> for &'inner mut [u8] {
+    fn available(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Splits `CHUNKS` chunks of `CHUNK_SIZE` bytes from the start of slice and advances it.
+///
+/// Chunks are independent, so they can be written from different threads.
+///
+/// # Panics
+/// If slice is shorter than `CHUNKS * CHUNK_SIZE`
+#[track_caller]
+pub fn split_chunks<'inner, const CHUNKS: usize, const CHUNK_SIZE: usize>(slice: &mut &'inner mut [u8]) -> [&'inner mut [u8]; CHUNKS] {
+    assert!(
+        slice.len() >= CHUNKS * CHUNK_SIZE,
+        "slice too short: {} < {}",
+        slice.len(),
+        CHUNKS * CHUNK_SIZE
+    );
+    let mut rest = core::mem::take(slice);
+    let chunks = core::array::from_fn(|_| {
+        let (chunk, tail) = core::mem::take(&mut rest).split_at_mut(CHUNK_SIZE);
+        rest = tail;
+        chunk
+    });
+    *slice = rest;
+    chunks
+}
+
+/// Splits `CHUNKS` chunks of `CHUNK_SIZE` bytes from the start of slice and advances it,
+/// returns [`ConstWriter`](crate::ConstWriter) for every chunk.
+///
+/// Writers are over arrays, so writing needs no runtime checks, and they are `Send` to be moved to other threads.
+/// ```
+/// let mut buff = [0u8; 32];
+/// let mut slice = buff.as_mut() as &mut [u8];
+/// let writers = const_writer::slice::par_split::<4, 8>(&mut slice);
+/// std::thread::scope(|scope| {
+///     for (i, writer) in IntoIterator::into_iter(writers).enumerate() {
+///         scope.spawn(move || writer.write_u64_le(i as u64));
+///     }
+/// });
+/// assert!(slice.is_empty());
+/// assert_eq!(buff[24], 3);
+/// ```
+///
+/// # Panics
+/// If slice is shorter than `CHUNKS * CHUNK_SIZE`
+#[cfg(nightly)]
+#[track_caller]
+pub fn par_split<'inner, const CHUNKS: usize, const CHUNK_SIZE: usize>(slice: &mut &'inner mut [u8]) -> [ChunkWriter<'inner, CHUNK_SIZE>; CHUNKS] {
+    split_chunks::<CHUNKS, CHUNK_SIZE>(slice).map(|chunk| {
+        match core::convert::TryFrom::try_from(chunk) {
+            Ok(chunk) => crate::array::const_writer_exact(chunk),
+            Err(_) => unreachable!("chunk of exactly CHUNK_SIZE bytes"),
+        }
+    })
+}
+
+/// Writer of one chunk from [`par_split`]
+#[cfg(nightly)]
+pub type ChunkWriter<'inner, const CHUNK_SIZE: usize> = crate::ConstWriter<crate::array::ArrayWriterAdapter<'inner, CHUNK_SIZE>, CHUNK_SIZE>;
+
+/// Writes `CHUNKS` chunks of `CHUNK_SIZE` bytes in parallel. At most
+/// [`available_parallelism`](std::thread::available_parallelism) scoped threads are spawned,
+/// each writes a contiguous range of chunks.
+///
+/// `f` receives chunk index and [`ConstWriter`](crate::ConstWriter) for that chunk from [`par_split`].
+/// ```
+/// let mut buff = [0u8; 32];
+/// let mut slice = buff.as_mut() as &mut [u8];
+/// const_writer::slice::par_fill::<4, 8, _>(&mut slice, |i, writer| {
+///     writer.write_u64_le(i as u64);
+/// });
+/// assert!(slice.is_empty());
+/// assert_eq!(buff[24], 3);
+/// ```
+#[cfg(all(feature = "std", nightly))]
+pub fn par_fill<const CHUNKS: usize, const CHUNK_SIZE: usize, F>(slice: &mut &mut [u8], f: F)
+    where F: for<'inner> Fn(usize, ChunkWriter<'inner, CHUNK_SIZE>) + Sync {
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).min(CHUNKS).max(1);
+    let per_worker = CHUNKS.div_ceil(workers);
+    let mut writers = IntoIterator::into_iter(par_split::<CHUNKS, CHUNK_SIZE>(slice)).enumerate();
+    std::thread::scope(|scope| {
+        let f = &f;
+        loop {
+            let range: Vec<_> = writers.by_ref().take(per_worker).collect();
+            if range.is_empty() {
+                break;
+            }
+            scope.spawn(move || {
+                for (i, writer) in range {
+                    f(i, writer);
+                }
+            });
+        }
+    });
+}
+
 
 
-#[cfg(test)]
+#[cfg(all(test, nightly))]
 mod tests {
     extern crate test;
     use test::Bencher;
@@ -93,6 +227,111 @@ mod tests {
         assert_eq!(buff, [34, 0, 0, 0, 3, 0, 4, 0, 5, 0]);
     }
 
+    crate::writer_fn! {
+        fn write_pair(writer, a: u8, b: u16) -> 3 {
+            writer.write_u8_le(a).write_u16_le(b)
+        }
+    }
+
+    #[test]
+    fn slice_writer_fn() {
+        let mut buff = [0u8; 8];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer: super::SliceWriter<'_, '_, 7> = ref_buff.const_writer::<7>();
+        write_pair(write_pair(writer, 1, 2), 3, 4).write_u8_le(5);
+        assert_eq!(buff, [1, 2, 0, 3, 4, 0, 5, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn slice_par_fill_many_chunks() {
+        let mut buff = vec![0u8; 4 * 1000];
+        let mut slice = buff.as_mut_slice();
+        let threads = std::sync::Mutex::new(std::collections::HashSet::new());
+        super::par_fill::<1000, 4, _>(&mut slice, |i, writer| {
+            threads.lock().unwrap().insert(std::thread::current().id());
+            writer.write_u32_le(i as u32);
+        });
+        assert!(slice.is_empty());
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+        assert!(threads.into_inner().unwrap().len() <= workers);
+        for (i, chunk) in buff.chunks(4).enumerate() {
+            assert_eq!(chunk, (i as u32).to_le_bytes());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn slice_write_from_reader() {
+        let mut buff = [0u8; 6];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let mut source = &[1u8, 2, 3, 4, 5][..];
+        let writer = ref_buff.const_writer::<6>()
+            .write_from::<3, _>(&mut source)
+            .unwrap();
+        let err = writer.write_from::<3, _>(&mut source).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(ref_buff.len(), 3);
+        assert_eq!(buff[..3], [1, 2, 3]);
+    }
+
+    #[test]
+    fn slice_write_rle() {
+        let mut buff = [0u8; 12];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        ref_buff.const_writer::<12>()
+            .write_rle(&[7, 7, 7, 1, 2, 2]);
+        assert_eq!(ref_buff.len(), 6);
+        assert_eq!(buff[..6], [3, 7, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "slice too short: 2 < 4 (const_writer::slice::SliceWriterAdapter")]
+    fn slice_too_short_message() {
+        let mut buff = [0u8; 2];
+        buff.as_mut().const_writer::<4>();
+    }
+
+    #[test]
+    #[should_panic(expected = "buffer length isn't exact: 6 != 4")]
+    fn slice_const_writer_exact() {
+        use crate::TryConstWrite;
+        let mut buff = [0u8; 6];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        ref_buff.const_writer_exact::<4>();
+    }
+
+    #[test]
+    fn slice_split_chunks() {
+        let mut buff = [0u8; 10];
+        let mut slice = buff.as_mut() as &mut [u8];
+        let [mut a, mut b] = super::split_chunks::<2, 4>(&mut slice);
+        b.const_writer::<4>().write_u32_le(2);
+        a.const_writer::<4>().write_u32_le(1);
+        assert_eq!(slice.len(), 2);
+        assert_eq!(buff, [1, 0, 0, 0, 2, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    fn slice_debug_poison() {
+        let mut buff = [0u8; 8];
+        buff.as_mut().const_writer::<6>().write_u32_le(1);
+        assert_eq!(buff, [1, 0, 0, 0, 0xAA, 0xAA, 0, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn slice_writer_send() {
+        let mut buff = [0u8; 4];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = ref_buff.const_writer::<4>();
+        std::thread::scope(|scope| {
+            scope.spawn(move || writer.write_u32_le(1));
+        });
+        assert_eq!(buff, [1, 0, 0, 0]);
+    }
+
     #[bench]
     fn bench_const_writer_le(b: &mut Bencher) {
         let mut buff = [0u8; 32];
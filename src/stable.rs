@@ -0,0 +1,196 @@
+//! API for stable Rust, enabled with `stable` feature.
+//!
+//! Length is encoded with [`typenum`] type instead of const generic param, so writer methods
+//! have same names and code switches to nightly API by replacing `U10` with `10`.
+//! Growing writer with `convert` requires const generics and isn't available.
+//!
+//! Feature only adds this module, nightly API is still there when compiler is nightly,
+//! so enabling it in one dependent doesn't break others.
+//!
+//! ```
+//! use const_writer::stable::ConstWrite;
+//! use const_writer::stable::typenum::U10;
+//!
+//! let mut vec = vec![];
+//! let writer = vec.const_writer::<U10>() // reserve 10 bytes in vec
+//!     .write_u32_le(12)
+//!     .write_u32_le(34);
+//! assert_eq!(writer.remaining(), 2);
+//! drop(writer);
+//! assert_eq!(vec, [12, 0, 0, 0, 34, 0, 0, 0]);
+//! ```
+//!
+//! Writing more than reserved doesn't compile:
+//! ```compile_fail
+//! use const_writer::stable::ConstWrite;
+//! use const_writer::stable::typenum::U2;
+//!
+//! let mut vec = vec![];
+//! vec.const_writer::<U2>().write_u32_le(1);
+//! ```
+use crate::ConstWriterAdapter;
+use crate::reserve::{ReserveAdapter, ReserveBuffer};
+use crate::slice::SliceWriterAdapter;
+use core::marker::PhantomData;
+use core::ops::Sub;
+use typenum::{Diff, IsLessOrEqual, True, Unsigned, U1, U2, U4, U8, U16};
+
+pub use typenum;
+
+///
+/// Writer that keeping track of space left using `typenum` param.
+///
+pub struct ConstWriter<T: ConstWriterAdapter, N: Unsigned> {
+    writer_adapter: T,
+    len: PhantomData<N>,
+}
+
+macro_rules! implement_write {
+    ($name:ident, $type:ty, $size:ty, $endian:ident) => {
+        pub fn $name(self, value: $type) -> ConstWriter<T, Diff<N, $size>>
+            where N: Sub<$size>, Diff<N, $size>: Unsigned {
+            unsafe {
+                ConstWriter {
+                    writer_adapter: self.writer_adapter.write(&value.$endian()),
+                    len: PhantomData,
+                }
+            }
+        }
+    }
+}
+
+impl<T: ConstWriterAdapter, N: Unsigned> ConstWriter<T, N> {
+    /// Shrinks [`ConstWriter`] to `M` bytes, no checks invoked
+    pub fn convert<M: Unsigned + IsLessOrEqual<N, Output = True>>(self) -> ConstWriter<T, M> {
+        ConstWriter {
+            writer_adapter: self.writer_adapter,
+            len: PhantomData,
+        }
+    }
+
+    /// Helper to access `typenum` param
+    pub fn remaining(&self) -> usize {
+        N::USIZE
+    }
+
+    implement_write!(write_u8_le, u8, U1, to_le_bytes);
+    implement_write!(write_u16_le, u16, U2, to_le_bytes);
+    implement_write!(write_u32_le, u32, U4, to_le_bytes);
+    implement_write!(write_u64_le, u64, U8, to_le_bytes);
+    implement_write!(write_u128_le, u128, U16, to_le_bytes);
+
+    implement_write!(write_i8_le, i8, U1, to_le_bytes);
+    implement_write!(write_i16_le, i16, U2, to_le_bytes);
+    implement_write!(write_i32_le, i32, U4, to_le_bytes);
+    implement_write!(write_i64_le, i64, U8, to_le_bytes);
+    implement_write!(write_i128_le, i128, U16, to_le_bytes);
+
+    implement_write!(write_u8_be, u8, U1, to_be_bytes);
+    implement_write!(write_u16_be, u16, U2, to_be_bytes);
+    implement_write!(write_u32_be, u32, U4, to_be_bytes);
+    implement_write!(write_u64_be, u64, U8, to_be_bytes);
+    implement_write!(write_u128_be, u128, U16, to_be_bytes);
+
+    implement_write!(write_i8_be, i8, U1, to_be_bytes);
+    implement_write!(write_i16_be, i16, U2, to_be_bytes);
+    implement_write!(write_i32_be, i32, U4, to_be_bytes);
+    implement_write!(write_i64_be, i64, U8, to_be_bytes);
+    implement_write!(write_i128_be, i128, U16, to_be_bytes);
+
+    implement_write!(write_f32_le, f32, U4, to_le_bytes);
+    implement_write!(write_f64_le, f64, U8, to_le_bytes);
+    implement_write!(write_f32_be, f32, U4, to_be_bytes);
+    implement_write!(write_f64_be, f64, U8, to_be_bytes);
+}
+
+/// Get [`ConstWriter`] for given type
+pub trait ConstWrite<'a, T: ConstWriterAdapter> {
+    /// Get [`ConstWriter`] to write `N` bytes.
+    fn const_writer<N: Unsigned>(&'a mut self) -> ConstWriter<T, N>;
+}
+
+impl<'a, 'inner> ConstWrite<'a, SliceWriterAdapter<'a, 'inner>> for &'inner mut [u8] {
+    #[track_caller]
+    fn const_writer<N: Unsigned>(&'a mut self) -> ConstWriter<SliceWriterAdapter<'a, 'inner>, N> {
+        unsafe {
+            ConstWriter {
+                writer_adapter: SliceWriterAdapter::with_len(self, N::USIZE),
+                len: PhantomData,
+            }
+        }
+    }
+}
+
+impl<'a, B: ReserveBuffer + ?Sized> ConstWrite<'a, ReserveAdapter<'a, B>> for B {
+    #[track_caller]
+    fn const_writer<N: Unsigned>(&'a mut self) -> ConstWriter<ReserveAdapter<'a, B>, N> {
+        ConstWriter {
+            writer_adapter: ReserveAdapter::with_len(self, N::USIZE),
+            len: PhantomData,
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use self::vec::StableVec;
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+mod vec {
+    extern crate alloc;
+    use alloc::vec::Vec;
+    use core::marker::PhantomData;
+    use core::mem::MaybeUninit;
+    use typenum::Unsigned;
+    use crate::reserve::{ReserveAdapter, ReserveBuffer};
+    use super::{ConstWrite, ConstWriter};
+
+    /// Nightly `vec` module needs `allocator_api`, so vector is written through [`ReserveBuffer`]
+    /// of this wrapper. `Vec<u8>` itself isn't `ReserveBuffer`, it would clash with nightly `ConstWrite`.
+    #[repr(transparent)]
+    pub struct StableVec(Vec<u8>);
+
+    impl ReserveBuffer for StableVec {
+        fn reserve(&mut self, len: usize) -> &mut [MaybeUninit<u8>] {
+            self.0.reserve(len);
+            self.0.spare_capacity_mut()
+        }
+
+        fn commit(&mut self, len: usize) {
+            // `ReserveAdapter` wrote `len` bytes to spare capacity
+            unsafe { self.0.set_len(self.0.len() + len) }
+        }
+    }
+
+    impl<'a> ConstWrite<'a, ReserveAdapter<'a, StableVec>> for Vec<u8> {
+        #[track_caller]
+        fn const_writer<N: Unsigned>(&'a mut self) -> ConstWriter<ReserveAdapter<'a, StableVec>, N> {
+            // `StableVec` is transparent wrapper
+            let vec = unsafe { &mut *(self as *mut Vec<u8>).cast::<StableVec>() };
+            ConstWriter {
+                writer_adapter: ReserveAdapter::with_len(vec, N::USIZE),
+                len: PhantomData,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstWrite;
+    use super::typenum::{U3, U10};
+
+    #[test]
+    fn stable_slice_write() {
+        let mut buff = [0u8; 10];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = ref_buff.const_writer::<U10>()
+            .write_u16_be(1)
+            .write_u32_le(2)
+            .convert::<U3>()
+            .write_u16_le(3);
+        assert_eq!(writer.remaining(), 1);
+        drop(writer);
+        assert_eq!(ref_buff.len(), 2);
+        assert_eq!(buff[..8], [0, 1, 2, 0, 0, 0, 3, 0]);
+    }
+}
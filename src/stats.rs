@@ -0,0 +1,116 @@
+//! Write statistics per call site, enabled with `stats` feature.
+//!
+//! Every adapter write is counted with its source location, so serializer hot spots and
+//! storms of tiny writes are easy to find. [`ConstWriter`](crate::ConstWriter) methods pass
+//! location of their caller, writes of format module helpers and `ConstWritable` impls
+//! are counted at the helper or impl which makes them.
+//! Counting takes global lock, feature is meant for profiling builds only.
+//!
+//! ```
+//! use const_writer::ConstWrite;
+//!
+//! let mut vec = vec![];
+//! for i in 0..10u16 {
+//!     vec.const_writer::<2>().write_u16_le(i);
+//! }
+//! let line = line!() - 2;
+//! let site = const_writer::stats::snapshot().into_iter()
+//!     .find(|(location, _)| location.line() == line)
+//!     .unwrap();
+//! assert_eq!((site.1.writes, site.1.bytes), (10, 20));
+//!
+//! // at program end
+//! const_writer::stats::dump(&mut std::io::stderr()).unwrap();
+//! ```
+use std::collections::BTreeMap;
+use std::io;
+use std::panic::Location;
+use std::sync::Mutex;
+use std::vec::Vec;
+
+/// Counters of single call site
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallSiteStats {
+    /// Number of write calls
+    pub writes: u64,
+    /// Number of bytes written
+    pub bytes: u64,
+}
+
+static STATS: Mutex<BTreeMap<Location<'static>, CallSiteStats>> = Mutex::new(BTreeMap::new());
+
+fn lock() -> std::sync::MutexGuard<'static, BTreeMap<Location<'static>, CallSiteStats>> {
+    // counters stay consistent even if some thread panicked while holding lock
+    STATS.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+pub(crate) fn record(location: &'static Location<'static>, bytes: usize) {
+    let mut stats = lock();
+    let site = stats.entry(*location).or_default();
+    site.writes += 1;
+    site.bytes += bytes as u64;
+}
+
+/// Copy of collected counters, sorted by location
+pub fn snapshot() -> Vec<(Location<'static>, CallSiteStats)> {
+    lock().iter().map(|(location, stats)| (*location, *stats)).collect()
+}
+
+/// Clears collected counters
+pub fn reset() {
+    lock().clear();
+}
+
+/// Writes table of call sites, most written bytes first
+pub fn dump<W: io::Write + ?Sized>(out: &mut W) -> io::Result<()> {
+    let mut sites = snapshot();
+    sites.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then(b.1.writes.cmp(&a.1.writes)));
+    writeln!(out, "{:>12} {:>12} {:>8}  location", "bytes", "writes", "avg")?;
+    for (location, stats) in sites {
+        writeln!(
+            out,
+            "{:>12} {:>12} {:>8.1}  {}",
+            stats.bytes,
+            stats.writes,
+            stats.bytes as f64 / stats.writes as f64,
+            location
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn stats_call_sites() {
+        let mut vec = std::vec![];
+        let line = line!() + 1;
+        vec.const_writer::<7>().write_u8_le(1).write_slice(&[2; 4]).write_u16_be(3);
+
+        let sites: std::vec::Vec<_> = super::snapshot().into_iter()
+            .filter(|(location, _)| location.file().ends_with("stats.rs") && location.line() == line)
+            .map(|(location, stats)| (location.column(), stats.bytes))
+            .collect();
+        assert_eq!(sites.len(), 3);
+        assert_eq!(sites.iter().map(|site| site.1).collect::<std::vec::Vec<_>>(), [1, 4, 2]);
+
+        let mut out = std::vec![];
+        super::dump(&mut out).unwrap();
+        assert!(std::string::String::from_utf8(out).unwrap().contains("stats.rs"));
+    }
+
+    #[test]
+    fn stats_count_every_write_once() {
+        let mut vec = std::vec![];
+        let line = line!() + 1;
+        vec.const_writer::<14>().write_record::<2>(|w| w.write_u16_le(1)).write_u32_be(2);
+
+        let bytes: u64 = super::snapshot().into_iter()
+            .filter(|(location, _)| location.file().ends_with("stats.rs") && location.line() == line)
+            .map(|(_, stats)| stats.bytes)
+            .sum();
+        assert_eq!(bytes, vec.len() as u64);
+    }
+}
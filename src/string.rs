@@ -0,0 +1,192 @@
+//! Writer for `String` which can only write UTF-8.
+//!
+//! [`ConstWriter`](crate::ConstWriter) writes arbitrary bytes, so `String` gets its own
+//! [`ConstStrWriter`] with text methods only: ASCII, chars, padded strings, decimal
+//! and hex numbers. Capacity is reserved on creation like for `Vec<u8>`, numbers of
+//! variable length reserve their worst case and return actual length.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::string::ConstStrWrite;
+//!
+//! // RESP array header and bulk string header
+//! let mut resp = String::new();
+//! let (writer, _) = resp.const_str_writer::<48>()
+//!     .write_ascii(b'*')
+//!     .write_u64_dec(2);
+//! let (writer, _) = writer.write_crlf()
+//!     .write_ascii(b'$')
+//!     .write_u64_dec(3);
+//! writer.write_crlf();
+//! assert_eq!(resp, "*2\r\n$3\r\n");
+//! ```
+use crate::{ConstWriterAdapter, ConstWriterAdapterCreate};
+use crate::vec::VecWriterAdapter;
+
+extern crate alloc;
+use alloc::string::String;
+
+/// Writer of `N` bytes of UTF-8 text
+pub struct ConstStrWriter<'a, const N: usize> {
+    writer_adapter: VecWriterAdapter<'a>,
+}
+
+/// Creates [`ConstStrWriter`]
+pub trait ConstStrWrite {
+    /// Get [`ConstStrWriter`] to write `N` bytes, capacity is reserved
+    fn const_str_writer<const N: usize>(&mut self) -> ConstStrWriter<'_, N>;
+}
+
+impl ConstStrWrite for String {
+    #[track_caller]
+    fn const_str_writer<const N: usize>(&mut self) -> ConstStrWriter<'_, N> {
+        // only complete UTF-8 sequences are written, so string stays valid when adapter sets its length
+        let vec = unsafe { self.as_mut_vec() };
+        ConstStrWriter {
+            writer_adapter: unsafe { VecWriterAdapter::new::<N>(vec) },
+        }
+    }
+}
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+impl<'a, const N: usize> ConstStrWriter<'a, N> {
+    fn cast<const M: usize>(self) -> ConstStrWriter<'a, M> {
+        ConstStrWriter {
+            writer_adapter: self.writer_adapter,
+        }
+    }
+
+    /// Writes `bytes`, which must be valid UTF-8
+    unsafe fn put(mut self, bytes: &[u8]) -> Self {
+        for byte in bytes {
+            self.writer_adapter = self.writer_adapter.write(&[*byte]);
+        }
+        self
+    }
+
+    /// Writes ASCII character
+    ///
+    /// # Panics
+    /// If `value` isn't ASCII
+    #[track_caller]
+    pub fn write_ascii(self, value: u8) -> ConstStrWriter<'a, {N - 1}> {
+        assert!(value.is_ascii(), "byte isn't ASCII: {}", value);
+        unsafe { self.put(&[value]) }.cast()
+    }
+
+    /// Writes `\r\n`
+    pub fn write_crlf(self) -> ConstStrWriter<'a, {N - 2}> {
+        unsafe { self.put(b"\r\n") }.cast()
+    }
+
+    /// Writes `value`, reserving 4 bytes. Returns number of written bytes.
+    pub fn write_char(self, value: char) -> (ConstStrWriter<'a, {N - 4}>, usize) {
+        let mut buff = [0u8; 4];
+        let len = value.encode_utf8(&mut buff).len();
+        (unsafe { self.put(&buff[..len]) }.cast(), len)
+    }
+
+    /// Writes `value` padded with spaces on the right to `M` bytes
+    ///
+    /// # Panics
+    /// If `value` is longer than `M` bytes
+    #[track_caller]
+    pub fn write_str_padded<const M: usize>(self, value: &str) -> ConstStrWriter<'a, {N - M}> {
+        assert!(value.len() <= M, "string too long: {} > {}", value.len(), M);
+        let mut writer = unsafe { self.put(value.as_bytes()) };
+        for _ in value.len()..M {
+            writer = unsafe { writer.put(b" ") };
+        }
+        writer.cast()
+    }
+
+    /// Writes `value` in decimal, reserving 20 bytes. Returns number of written bytes.
+    pub fn write_u64_dec(self, value: u64) -> (ConstStrWriter<'a, {N - 20}>, usize) {
+        let mut buff = [0u8; 20];
+        let start = decimal(value, &mut buff);
+        (unsafe { self.put(&buff[start..]) }.cast(), 20 - start)
+    }
+
+    /// Writes `value` in decimal, reserving 20 bytes. Returns number of written bytes.
+    pub fn write_i64_dec(self, value: i64) -> (ConstStrWriter<'a, {N - 20}>, usize) {
+        let mut buff = [0u8; 20];
+        let mut start = decimal(value.unsigned_abs(), &mut buff);
+        if value < 0 {
+            start -= 1;
+            buff[start] = b'-';
+        }
+        (unsafe { self.put(&buff[start..]) }.cast(), 20 - start)
+    }
+
+    /// Writes `value` as 2 lowercase hex digits
+    pub fn write_u8_hex(self, value: u8) -> ConstStrWriter<'a, {N - 2}> {
+        unsafe { self.put(&hex(value as u64, 2)[14..]) }.cast()
+    }
+
+    /// Writes `value` as 8 lowercase hex digits
+    pub fn write_u32_hex(self, value: u32) -> ConstStrWriter<'a, {N - 8}> {
+        unsafe { self.put(&hex(value as u64, 8)[8..]) }.cast()
+    }
+
+    /// Writes `value` as 16 lowercase hex digits
+    pub fn write_u64_hex(self, value: u64) -> ConstStrWriter<'a, {N - 16}> {
+        unsafe { self.put(&hex(value, 16)) }.cast()
+    }
+
+    /// Helper to access const_generic param
+    pub fn remaining(&self) -> usize {
+        N
+    }
+}
+
+impl<'a, const N: usize> core::fmt::Debug for ConstStrWriter<'a, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ConstStrWriter")
+            .field("remaining", &N)
+            .field("written_so_far", &self.writer_adapter.written_so_far())
+            .finish()
+    }
+}
+
+/// Writes digits of `value` to end of `buff`, returns index of first digit
+fn decimal(mut value: u64, buff: &mut [u8; 20]) -> usize {
+    let mut start = 20;
+    loop {
+        start -= 1;
+        buff[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            return start;
+        }
+    }
+}
+
+/// Low `digits` hex digits of `value`, right aligned in 16 bytes
+fn hex(value: u64, digits: usize) -> [u8; 16] {
+    let mut buff = [b'0'; 16];
+    for i in 0..digits {
+        buff[15 - i] = HEX[(value >> (4 * i) & 0xF) as usize];
+    }
+    buff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstStrWrite;
+
+    extern crate alloc;
+    use alloc::string::String;
+
+    #[test]
+    fn string_numbers_and_text() {
+        let mut text = String::from("> ");
+        let (writer, len) = text.const_str_writer::<64>().write_i64_dec(i64::MIN);
+        assert_eq!(len, 20);
+        let (writer, len) = writer.write_ascii(b' ').write_char('λ');
+        assert_eq!(len, 2);
+        writer.write_u8_hex(0xA).write_u32_hex(0xBEEF).write_str_padded::<4>("ok");
+        assert_eq!(text, "> -9223372036854775808 λ0a0000beefok  ");
+    }
+}
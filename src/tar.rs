@@ -0,0 +1,162 @@
+//! USTAR header block.
+//!
+//! Header is filled in one pass into 512 byte block, numeric fields are octal ASCII and
+//! checksum is computed over the block.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::tar::{self, EntryType, Header};
+//!
+//! let header = Header { name: "hello.txt", mode: 0o644, size: 5, mtime: 1_600_000_000, ..Header::new(EntryType::Regular) };
+//! let mut vec = vec![];
+//! tar::write_header(vec.const_writer::<512>(), &header);
+//! assert_eq!(vec[..9], *b"hello.txt");
+//! assert_eq!(vec[124..136], *b"00000000005\0");
+//! assert_eq!(vec[257..265], *b"ustar\x0000");
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Size of header block
+pub const BLOCK: usize = 512;
+
+/// Type flag of entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryType {
+    Regular = b'0' as isize,
+    HardLink = b'1' as isize,
+    Symlink = b'2' as isize,
+    CharDevice = b'3' as isize,
+    BlockDevice = b'4' as isize,
+    Directory = b'5' as isize,
+    Fifo = b'6' as isize,
+}
+
+/// Header fields, names longer than 100 bytes are split into prefix and name at `/`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header<'a> {
+    pub name: &'a str,
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    pub size: u64,
+    /// seconds since unix epoch
+    pub mtime: u64,
+    pub entry_type: EntryType,
+    pub link_name: &'a str,
+    pub user_name: &'a str,
+    pub group_name: &'a str,
+}
+
+impl<'a> Header<'a> {
+    /// Header with empty names and zero numbers
+    pub fn new(entry_type: EntryType) -> Self {
+        Self {
+            name: "",
+            mode: 0,
+            uid: 0,
+            gid: 0,
+            size: 0,
+            mtime: 0,
+            entry_type,
+            link_name: "",
+            user_name: "",
+            group_name: "",
+        }
+    }
+}
+
+#[track_caller]
+fn put_str(field: &mut [u8], value: &str, name: &str) {
+    assert!(value.len() <= field.len(), "tar {} too long: {} > {}", name, value.len(), field.len());
+    field[..value.len()].copy_from_slice(value.as_bytes());
+}
+
+/// Zero padded octal number terminated with NUL
+#[track_caller]
+fn put_octal(field: &mut [u8], mut value: u64, name: &str) {
+    let (terminator, digits) = field.split_last_mut().unwrap();
+    *terminator = 0;
+    for digit in digits.iter_mut().rev() {
+        *digit = b'0' + (value & 7) as u8;
+        value >>= 3;
+    }
+    assert!(value == 0, "tar {} doesn't fit in {} octal digits", name, digits.len());
+}
+
+/// Splits path into prefix and name fields
+#[track_caller]
+fn split_name(path: &str) -> (&str, &str) {
+    if path.len() <= 100 {
+        return ("", path);
+    }
+    let split = path.char_indices()
+        .filter(|&(i, c)| c == '/' && i <= 155 && path.len() - i - 1 <= 100)
+        .map(|(i, _)| i)
+        .next();
+    match split {
+        Some(i) => (&path[..i], &path[i + 1..]),
+        None => panic!("tar path can't be split into prefix and name: {}", path),
+    }
+}
+
+/// Writes 512 byte USTAR header
+///
+/// # Panics
+/// If name doesn't fit, or number doesn't fit in its octal field
+#[track_caller]
+pub fn write_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, header: &Header<'_>) -> ConstWriter<T, {N - 512}> {
+    let mut block = [0u8; BLOCK];
+    let (prefix, name) = split_name(header.name);
+    put_str(&mut block[0..100], name, "name");
+    put_octal(&mut block[100..108], header.mode as u64, "mode");
+    put_octal(&mut block[108..116], header.uid as u64, "uid");
+    put_octal(&mut block[116..124], header.gid as u64, "gid");
+    put_octal(&mut block[124..136], header.size, "size");
+    put_octal(&mut block[136..148], header.mtime, "mtime");
+    block[156] = header.entry_type as u8;
+    put_str(&mut block[157..257], header.link_name, "link name");
+    block[257..265].copy_from_slice(b"ustar\x0000");
+    put_str(&mut block[265..297], header.user_name, "user name");
+    put_str(&mut block[297..329], header.group_name, "group name");
+    put_octal(&mut block[329..337], 0, "device major");
+    put_octal(&mut block[337..345], 0, "device minor");
+    put_str(&mut block[345..500], prefix, "prefix");
+
+    // checksum is computed with checksum field filled with spaces
+    block[148..156].copy_from_slice(b"        ");
+    let checksum = block.iter().map(|&b| b as u64).sum::<u64>();
+    put_octal(&mut block[148..155], checksum, "checksum");
+    block[155] = b' ';
+
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&block),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntryType, Header};
+    use crate::ConstWrite;
+
+    #[test]
+    fn tar_long_name_checksum() {
+        let mut path = [b'f'; 141];
+        path[..60].fill(b'd');
+        path[60] = b'/';
+        let header = Header { name: core::str::from_utf8(&path).unwrap(), ..Header::new(EntryType::Directory) };
+        let mut buff = [0u8; 512];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_header(ref_buff.const_writer::<512>(), &header);
+        assert_eq!(buff[..80], path[61..]);
+        assert_eq!(buff[345..405], path[..60]);
+
+        let stored = u32::from_str_radix(core::str::from_utf8(&buff[148..154]).unwrap(), 8).unwrap();
+        buff[148..156].copy_from_slice(b"        ");
+        let sum = buff.iter().map(|&b| b as u32).sum::<u32>();
+        assert_eq!(stored, sum);
+    }
+}
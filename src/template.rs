@@ -0,0 +1,125 @@
+//! `const fn` writer over `[u8; L]` to assemble packet templates at compile time.
+//!
+//! Variable fields are patched at runtime with regular writer.
+//!
+#![cfg_attr(nightly, doc = "```")]
+#![cfg_attr(not(nightly), doc = "```ignore")]
+//! use const_writer::ConstWrite;
+//! use const_writer::template::Template;
+//!
+//! const HELLO: [u8; 8] = Template::new()
+//!     .write_u32_be(0xCAFEBABE) // magic
+//!     .write_u16_le(1) // version
+//!     .write_u16_le(0) // session id, patched at runtime
+//!     .finish();
+//!
+//! let mut packet = HELLO;
+//! let mut session = &mut packet[6..];
+//! session.const_writer::<2>().write_u16_le(42);
+//! assert_eq!(packet, [0xCA, 0xFE, 0xBA, 0xBE, 1, 0, 42, 0]);
+//! ```
+//!
+//! Overflowing template fails const evaluation:
+//! ```compile_fail
+//! use const_writer::template::Template;
+//!
+//! const BAD: [u8; 2] = Template::new().write_u32_le(1).finish();
+//! ```
+
+/// Writer which can be used in const context, length is checked when template is evaluated
+pub struct Template<const L: usize> {
+    buff: [u8; L],
+    pos: usize,
+}
+
+macro_rules! implement_write {
+    ($name:ident, $type:ty, $endian:ident) => {
+        pub const fn $name(self, value: $type) -> Self {
+            self.write_slice(&value.$endian())
+        }
+    }
+}
+
+impl<const L: usize> Template<L> {
+    pub const fn new() -> Self {
+        Self {
+            buff: [0; L],
+            pos: 0,
+        }
+    }
+
+    /// Number of bytes left
+    pub const fn remaining(&self) -> usize {
+        L - self.pos
+    }
+
+    pub const fn write_slice<const M: usize>(mut self, value: &[u8; M]) -> Self {
+        assert!(M <= L - self.pos, "template too short");
+        let mut i = 0;
+        while i < M {
+            self.buff[self.pos + i] = value[i];
+            i += 1;
+        }
+        self.pos += M;
+        self
+    }
+
+    /// Returns filled array
+    ///
+    /// # Panics
+    /// If template isn't filled completely
+    pub const fn finish(self) -> [u8; L] {
+        assert!(self.pos == L, "template isn't filled");
+        self.buff
+    }
+
+    implement_write!(write_u8_le, u8, to_le_bytes);
+    implement_write!(write_u16_le, u16, to_le_bytes);
+    implement_write!(write_u32_le, u32, to_le_bytes);
+    implement_write!(write_u64_le, u64, to_le_bytes);
+    implement_write!(write_u128_le, u128, to_le_bytes);
+
+    implement_write!(write_i8_le, i8, to_le_bytes);
+    implement_write!(write_i16_le, i16, to_le_bytes);
+    implement_write!(write_i32_le, i32, to_le_bytes);
+    implement_write!(write_i64_le, i64, to_le_bytes);
+    implement_write!(write_i128_le, i128, to_le_bytes);
+
+    implement_write!(write_u8_be, u8, to_be_bytes);
+    implement_write!(write_u16_be, u16, to_be_bytes);
+    implement_write!(write_u32_be, u32, to_be_bytes);
+    implement_write!(write_u64_be, u64, to_be_bytes);
+    implement_write!(write_u128_be, u128, to_be_bytes);
+
+    implement_write!(write_i8_be, i8, to_be_bytes);
+    implement_write!(write_i16_be, i16, to_be_bytes);
+    implement_write!(write_i32_be, i32, to_be_bytes);
+    implement_write!(write_i64_be, i64, to_be_bytes);
+    implement_write!(write_i128_be, i128, to_be_bytes);
+
+    implement_write!(write_f32_le, f32, to_le_bytes);
+    implement_write!(write_f64_le, f64, to_le_bytes);
+    implement_write!(write_f32_be, f32, to_be_bytes);
+    implement_write!(write_f64_be, f64, to_be_bytes);
+}
+
+impl<const L: usize> Default for Template<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+
+    static TABLE: [u8; 6] = Template::new()
+        .write_i16_be(-2)
+        .write_f32_le(1.0)
+        .finish();
+
+    #[test]
+    fn template_static() {
+        assert_eq!(TABLE, [0xff, 0xfe, 0, 0, 0x80, 0x3f]);
+    }
+}
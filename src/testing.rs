@@ -0,0 +1,123 @@
+//! Property checks for serializers with values generated by [`arbitrary`].
+//!
+//! Every generated value goes through [`assert_roundtrip`], so write must fill exactly `SIZE`
+//! bytes without overrunning it (checked in release builds too) and read must give same value back.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use arbitrary::{Arbitrary, Unstructured};
+//!
+//! const_writer::const_layout! {
+//!     #[derive(Debug, PartialEq)]
+//!     struct Point {
+//!         x: i32_le,
+//!         y: u16_be,
+//!     }
+//! }
+//!
+//! impl<'a> Arbitrary<'a> for Point {
+//!     fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+//!         Ok(Point { x: u.arbitrary()?, y: u.arbitrary()? })
+//!     }
+//! }
+//!
+//! const_writer::testing::fuzz_roundtrip::<Point>(100);
+//! ```
+//!
+//! NaN isn't equal to itself, so `Arbitrary` impls for types with floats should skip it.
+use core::fmt::Debug;
+use arbitrary::{Arbitrary, Unstructured};
+use crate::{ConstReadable, ConstWritable};
+use crate::roundtrip::assert_roundtrip;
+
+/// Generates value from `data` and checks roundtrip, body for `fuzz_target!`
+#[track_caller]
+pub fn check_roundtrip<'a, V>(data: &'a [u8]) -> arbitrary::Result<()>
+where
+    V: ConstWritable + ConstReadable + PartialEq + Debug + Arbitrary<'a>,
+    [(); <V as ConstWritable>::SIZE]:,
+{
+    let value = V::arbitrary(&mut Unstructured::new(data))?;
+    assert_roundtrip(&value);
+    Ok(())
+}
+
+/// Checks roundtrip for `count` values generated from pseudo random bytes, seed is fixed
+/// so failures are reproducible
+#[track_caller]
+pub fn fuzz_roundtrip<V>(count: usize)
+where
+    V: ConstWritable + ConstReadable + PartialEq + Debug + for<'a> Arbitrary<'a>,
+    [(); <V as ConstWritable>::SIZE]:,
+{
+    let mut state = 0x2545F4914F6CDD1Du64;
+    let mut data = [0u8; 256];
+    for _ in 0..count {
+        for chunk in data.chunks_mut(8) {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            chunk.copy_from_slice(&state.to_le_bytes());
+        }
+        check_roundtrip::<V>(&data).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    crate::const_layout! {
+        #[derive(Debug, PartialEq)]
+        struct Sample {
+            a: u8,
+            b: i64_be,
+            c: u128_le,
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for Sample {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            Ok(Sample { a: u.arbitrary()?, b: u.arbitrary()?, c: u.arbitrary()? })
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Overrun(u8);
+
+    impl<'a> Arbitrary<'a> for Overrun {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            Ok(Overrun(u.arbitrary()?))
+        }
+    }
+
+    unsafe impl crate::ConstWritable for Overrun {
+        const SIZE: usize = 1;
+
+        unsafe fn write_to<T: crate::ConstWriterAdapter>(&self, writer_adapter: T) -> T {
+            writer_adapter.write(&[self.0, self.0])
+        }
+    }
+
+    unsafe impl crate::ConstReadable for Overrun {
+        const SIZE: usize = 1;
+
+        unsafe fn read_from<T: crate::ConstReaderAdapter>(reader_adapter: &mut T) -> Self {
+            Self(reader_adapter.read::<1>()[0])
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "value overran its SIZE")]
+    fn fuzz_overrun_panics() {
+        super::check_roundtrip::<Overrun>(&[1]).unwrap();
+    }
+
+    #[test]
+    fn fuzz_layout_roundtrip() {
+        super::fuzz_roundtrip::<Sample>(1000);
+        super::check_roundtrip::<Sample>(&[]).unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+//! NTP and PTP timestamp fields.
+//!
+//! NTP timestamp is 8 bytes: seconds since 1900-01-01 UTC and 32 bit fraction of second.
+//! PTP timestamp is 10 bytes: 48 bit seconds since 1970-01-01 TAI and nanoseconds.
+//! Both are big endian.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use core::time::Duration;
+//! use const_writer::ConstWrite;
+//! use const_writer::timestamp;
+//!
+//! let mut vec = vec![];
+//! let writer = vec.const_writer::<18>();
+//! let writer = timestamp::write_ntp(writer, Duration::new(timestamp::NTP_UNIX_OFFSET, 500_000_000));
+//! timestamp::write_ptp(writer, Duration::new(1, 5));
+//! assert_eq!(vec[..8], [0x83, 0xAA, 0x7E, 0x80, 0x80, 0, 0, 0]);
+//! assert_eq!(vec[8..], [0, 0, 0, 0, 0, 1, 0, 0, 0, 5]);
+//! ```
+use core::time::Duration;
+use crate::{ConstWriter, ConstWriterAdapter};
+
+/// Seconds from NTP epoch (1900) to unix epoch (1970)
+pub const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// TAI - UTC in seconds, valid since 2017-01-01
+pub const UTC_OFFSET: u64 = 37;
+
+/// Writes NTP timestamp of `since_epoch` after NTP epoch.
+/// Seconds wrap around at era boundary in 2036, like on the wire.
+pub fn write_ntp<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, since_epoch: Duration) -> ConstWriter<T, {N - 8}> {
+    let seconds = since_epoch.as_secs() as u32;
+    let fraction = ((since_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    let value = (seconds as u64) << 32 | fraction;
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&value.to_be_bytes()),
+        }
+    }
+}
+
+/// Writes PTP timestamp of `since_epoch` after PTP epoch
+///
+/// # Panics
+/// If seconds don't fit in 48 bits
+#[track_caller]
+pub fn write_ptp<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, since_epoch: Duration) -> ConstWriter<T, {N - 10}> {
+    let seconds = since_epoch.as_secs();
+    assert!(seconds < 1 << 48, "PTP seconds don't fit in 48 bits: {}", seconds);
+    let seconds = seconds.to_be_bytes();
+    let nanos = since_epoch.subsec_nanos().to_be_bytes();
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[
+                seconds[2], seconds[3], seconds[4], seconds[5], seconds[6], seconds[7],
+                nanos[0], nanos[1], nanos[2], nanos[3],
+            ]),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn since_unix(time: std::time::SystemTime) -> Duration {
+    match time.duration_since(std::time::UNIX_EPOCH) {
+        Ok(duration) => duration,
+        Err(_) => panic!("time is before unix epoch: {:?}", time),
+    }
+}
+
+/// Writes NTP timestamp of `time`
+///
+/// # Panics
+/// If `time` is before unix epoch
+#[cfg(feature = "std")]
+#[track_caller]
+pub fn write_ntp_system_time<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, time: std::time::SystemTime) -> ConstWriter<T, {N - 8}> {
+    write_ntp(writer, since_unix(time) + Duration::from_secs(NTP_UNIX_OFFSET))
+}
+
+/// Writes PTP timestamp of `time`, `utc_offset` is TAI - UTC in seconds, usually [`UTC_OFFSET`]
+///
+/// # Panics
+/// If `time` is before unix epoch
+#[cfg(feature = "std")]
+#[track_caller]
+pub fn write_ptp_system_time<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, time: std::time::SystemTime, utc_offset: u64) -> ConstWriter<T, {N - 10}> {
+    write_ptp(writer, since_unix(time) + Duration::from_secs(utc_offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use crate::ConstWrite;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ntp_from_system_time() {
+        let time = std::time::UNIX_EPOCH + Duration::from_millis(1_250);
+        let mut buff = [0u8; 18];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        let writer = super::write_ntp_system_time(ref_buff.const_writer::<18>(), time);
+        super::write_ptp_system_time(writer, time, super::UTC_OFFSET);
+        assert_eq!(buff[..8], [0x83, 0xAA, 0x7E, 0x81, 0x40, 0, 0, 0]);
+        assert_eq!(buff[8..], [0, 0, 0, 0, 0, 38, 0x0E, 0xE6, 0xB2, 0x80]);
+    }
+
+    #[test]
+    #[should_panic(expected = "PTP seconds don't fit in 48 bits")]
+    fn ptp_seconds_overflow() {
+        let mut buff = [0u8; 10];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_ptp(ref_buff.const_writer::<10>(), Duration::from_secs(1 << 48));
+    }
+}
@@ -0,0 +1,81 @@
+//! TLS record and handshake message headers.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::tls;
+//!
+//! let mut vec = vec![];
+//! let writer = tls::write_record_header(vec.const_writer::<9>(), tls::HANDSHAKE, tls::TLS1_2, 4);
+//! tls::write_handshake_header(writer, tls::FINISHED, 0);
+//! assert_eq!(vec, [22, 3, 3, 0, 4, 20, 0, 0, 0]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+pub const CHANGE_CIPHER_SPEC: u8 = 20;
+pub const ALERT: u8 = 21;
+pub const HANDSHAKE: u8 = 22;
+pub const APPLICATION_DATA: u8 = 23;
+
+/// Record version of first ClientHello
+pub const TLS1_0: u16 = 0x0301;
+/// Record version of TLS 1.2 and later
+pub const TLS1_2: u16 = 0x0303;
+
+pub const CLIENT_HELLO: u8 = 1;
+pub const SERVER_HELLO: u8 = 2;
+pub const NEW_SESSION_TICKET: u8 = 4;
+pub const ENCRYPTED_EXTENSIONS: u8 = 8;
+pub const CERTIFICATE: u8 = 11;
+pub const CERTIFICATE_REQUEST: u8 = 13;
+pub const CERTIFICATE_VERIFY: u8 = 15;
+pub const FINISHED: u8 = 20;
+pub const KEY_UPDATE: u8 = 24;
+
+/// Largest record fragment, 2^14 bytes of plaintext and 2048 bytes of expansion
+pub const MAX_FRAGMENT_LEN: u16 = (1 << 14) + 2048;
+
+/// Writes 5 byte record header with fragment of `len` bytes
+///
+/// # Panics
+/// If `len` is greater than [`MAX_FRAGMENT_LEN`]
+#[track_caller]
+pub fn write_record_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, content_type: u8, version: u16, len: u16) -> ConstWriter<T, {N - 5}> {
+    assert!(len <= MAX_FRAGMENT_LEN, "TLS record too long: {}", len);
+    let version = version.to_be_bytes();
+    let len = len.to_be_bytes();
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[content_type, version[0], version[1], len[0], len[1]]),
+        }
+    }
+}
+
+/// Writes 4 byte handshake header, length is 24 bit like in
+/// [`ConstWriter::write_u24_be`](crate::ConstWriter::write_u24_be)
+///
+/// # Panics
+/// If `len24` doesn't fit in 24 bits
+#[track_caller]
+pub fn write_handshake_header<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, msg_type: u8, len24: u32) -> ConstWriter<T, {N - 4}> {
+    assert!(len24 <= 0xFF_FFFF, "value doesn't fit in 24 bits: {}", len24);
+    let len = len24.to_be_bytes();
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[msg_type, len[1], len[2], len[3]]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    #[should_panic(expected = "value doesn't fit in 24 bits: 16777216")]
+    fn tls_handshake_len_overflow() {
+        let mut buff = [0u8; 4];
+        super::write_handshake_header(buff.const_writer::<4>(), super::CLIENT_HELLO, 1 << 24);
+    }
+}
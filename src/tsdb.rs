@@ -0,0 +1,393 @@
+//! Time-series points `(timestamp u64, value f64)`.
+//!
+//! Points are written either raw, 16 bytes each, or with [`DeltaEncoder`]: delta-of-delta of
+//! timestamp as zigzag LEB128 followed by value. Encoded length varies, so encoder reserves
+//! worst case [`MAX_DELTA_POINT_LEN`] bytes from budget and returns actual length.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::tsdb::{DeltaDecoder, DeltaEncoder};
+//!
+//! let mut block = vec![];
+//! let mut encoder = DeltaEncoder::new();
+//! for (i, value) in [20.5, 20.25, 20.0].iter().enumerate() {
+//!     encoder.write_point(block.const_writer::<18>(), 1_700_000_000 + 60 * i as u64, *value);
+//! }
+//! // regular interval takes 1 byte of timestamp after second point
+//! assert_eq!(block.len(), 13 + 9 + 9);
+//!
+//! let mut decoder = DeltaDecoder::new();
+//! let mut rest = &block[..];
+//! let mut points = vec![];
+//! while let Some((timestamp, value, len)) = decoder.read_point(rest) {
+//!     points.push((timestamp, value));
+//!     rest = &rest[len..];
+//! }
+//! assert_eq!(points, [(1_700_000_000, 20.5), (1_700_000_060, 20.25), (1_700_000_120, 20.0)]);
+//! ```
+//!
+//! Values can be compressed [Gorilla](https://www.vldb.org/pvldb/vol8/p1816-teller.pdf) style,
+//! XOR with previous value is written bit packed with [`XorEncoder`] through
+//! [`ConstBitWriter`], so bits are packed little endian unlike original Gorilla:
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::tsdb::{XorDecoder, XorEncoder};
+//!
+//! let values = [12.0, 12.0, 24.0, 15.5];
+//! let mut block = vec![];
+//! let mut encoder = XorEncoder::new();
+//! for value in values.iter() {
+//!     block.const_writer::<10>().write_f64_xor_compressed(&mut encoder, *value);
+//! }
+//! encoder.finish(block.const_writer::<1>());
+//! assert!(block.len() < 8 * values.len());
+//!
+//! let decoded: Vec<f64> = XorDecoder::new(&block, values.len()).collect();
+//! assert_eq!(decoded, values);
+//! ```
+use core::convert::TryInto;
+use crate::{ConstWriter, ConstWriterAdapter};
+use crate::bits::{const_bit_writer, BitQueue, ConstBitWriter};
+
+/// Length of raw point
+pub const POINT_LEN: usize = 16;
+/// Worst case length of delta encoded point: 10 bytes of LEB128 and 8 bytes of value
+pub const MAX_DELTA_POINT_LEN: usize = 18;
+
+/// Writes raw point, both fields little endian
+pub fn write_point<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, timestamp: u64, value: f64) -> ConstWriter<T, {N - POINT_LEN}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&timestamp.to_le_bytes())
+                .write(&value.to_le_bytes()),
+        }
+    }
+}
+
+/// Encodes timestamps as delta-of-delta from previous points
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeltaEncoder {
+    timestamp: u64,
+    delta: i64,
+    count: usize,
+}
+
+impl DeltaEncoder {
+    pub const fn new() -> Self {
+        Self {
+            timestamp: 0,
+            delta: 0,
+            count: 0,
+        }
+    }
+
+    /// Number of points written
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Writes point, returns number of written bytes. Timestamps may go backwards,
+    /// differences wrap around.
+    pub fn write_point<T: ConstWriterAdapter, const N: usize>(&mut self, writer: ConstWriter<T, {N}>, timestamp: u64, value: f64) -> (ConstWriter<T, {N - MAX_DELTA_POINT_LEN}>, usize) {
+        let delta = timestamp.wrapping_sub(self.timestamp) as i64;
+        let dod = delta.wrapping_sub(self.delta);
+        // first point stores timestamp itself, second one plain delta
+        self.delta = if self.count == 0 { 0 } else { delta };
+        self.timestamp = timestamp;
+        self.count += 1;
+
+        let mut zigzag = ((dod << 1) ^ (dod >> 63)) as u64;
+        let mut buff = [0u8; MAX_DELTA_POINT_LEN];
+        let mut len = 0;
+        loop {
+            buff[len] = zigzag as u8 & 0x7F;
+            zigzag >>= 7;
+            len += 1;
+            if zigzag == 0 {
+                break;
+            }
+            buff[len - 1] |= 0x80;
+        }
+        buff[len..len + 8].copy_from_slice(&value.to_le_bytes());
+        len += 8;
+
+        // unused part of reserved bytes is left to following writes
+        let mut writer_adapter = writer.writer_adapter;
+        for byte in &buff[..len] {
+            writer_adapter = unsafe { writer_adapter.write(&[*byte]) };
+        }
+        (ConstWriter { writer_adapter }, len)
+    }
+}
+
+/// Reads points written by [`DeltaEncoder`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeltaDecoder {
+    timestamp: u64,
+    delta: i64,
+    count: usize,
+}
+
+impl DeltaDecoder {
+    pub const fn new() -> Self {
+        Self {
+            timestamp: 0,
+            delta: 0,
+            count: 0,
+        }
+    }
+
+    /// Reads point from start of `bytes`, returns timestamp, value and encoded length.
+    /// `None` if `bytes` hold no complete point.
+    pub fn read_point(&mut self, bytes: &[u8]) -> Option<(u64, f64, usize)> {
+        let mut zigzag = 0u64;
+        let mut len = 0;
+        loop {
+            if len == 10 {
+                return None;
+            }
+            let byte = *bytes.get(len)?;
+            zigzag |= ((byte & 0x7F) as u64) << (7 * len);
+            len += 1;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let value = f64::from_le_bytes(bytes.get(len..len + 8)?.try_into().unwrap());
+        let dod = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+
+        let delta = self.delta.wrapping_add(dod);
+        let timestamp = self.timestamp.wrapping_add(delta as u64);
+        self.delta = if self.count == 0 { 0 } else { delta };
+        self.timestamp = timestamp;
+        self.count += 1;
+        Some((timestamp, value, len + 8))
+    }
+}
+
+/// Worst case number of bytes [`ConstWriter::write_f64_xor_compressed`] writes
+pub const MAX_XOR_VALUE_LEN: usize = 10;
+/// Worst case number of bits of encoded value: control bits, leading zeros, length and 64 meaningful bits
+const MAX_XOR_VALUE_BITS: usize = 2 + 5 + 6 + 64;
+
+/// State of XOR compression: previous value and pending bits.
+///
+/// Stream is bit packed, so it must be ended with [`XorEncoder::finish`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct XorEncoder {
+    prev: u64,
+    /// leading and trailing zeros of previous meaningful bits
+    window: Option<(u32, u32)>,
+    count: usize,
+    /// bits which don't make full byte yet
+    queue: BitQueue,
+}
+
+impl XorEncoder {
+    pub const fn new() -> Self {
+        Self {
+            prev: 0,
+            window: None,
+            count: 0,
+            queue: BitQueue::new(),
+        }
+    }
+
+    /// Number of values written
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    fn encode<'a>(&mut self, mut writer: ConstBitWriter<'a, MAX_XOR_VALUE_BITS>, value: f64) -> ConstBitWriter<'a, 0> {
+        let value = value.to_bits();
+        let xor = value ^ self.prev;
+        self.prev = value;
+        self.count += 1;
+        if self.count == 1 {
+            writer.put(value, 64);
+        } else if xor == 0 {
+            writer.put(0, 1);
+        } else {
+            // leading zeros are stored in 5 bits
+            let leading = xor.leading_zeros().min(31);
+            let trailing = xor.trailing_zeros();
+            match self.window {
+                Some((prev_leading, prev_trailing)) if leading >= prev_leading && trailing >= prev_trailing => {
+                    // control bits `1, 0` in stream order
+                    writer.put(0b01, 2);
+                    writer.put(xor >> prev_trailing, (64 - prev_leading - prev_trailing) as usize);
+                }
+                _ => {
+                    let meaningful = 64 - leading - trailing;
+                    writer.put(0b11, 2);
+                    writer.put(leading as u64, 5);
+                    // 64 doesn't fit in 6 bits and is stored as 0
+                    writer.put(meaningful as u64 & 0x3F, 6);
+                    writer.put(xor >> trailing, meaningful as usize);
+                    self.window = Some((leading, trailing));
+                }
+            }
+        }
+        writer.skip()
+    }
+
+    /// Encodes `value` after pending bits, returns staged bytes and number of bits in them.
+    /// Bits which don't make full byte stay pending.
+    fn stage(&mut self, value: f64) -> ([u8; MAX_XOR_VALUE_LEN + 1], usize) {
+        // pending bits are less than a byte, together with encoded value they take at most 11 bytes
+        let mut buff = [0u8; MAX_XOR_VALUE_LEN + 1];
+        let pending = (self.queue.partial().unwrap_or(0), self.queue.bits());
+        let bits = const_bit_writer::<{7 + MAX_XOR_VALUE_BITS}>(&mut buff)
+            .write_with::<7>(|mut w| {
+                w.put(pending.0 as u64, pending.1 as usize);
+                w.skip()
+            })
+            .write_with::<MAX_XOR_VALUE_BITS>(|w| self.encode(w, value))
+            .bits_written();
+        self.queue = BitQueue::new();
+        self.queue.push(buff[bits / 8] as u64, (bits % 8) as u32);
+        (buff, bits)
+    }
+
+    /// Writes pending bits padded with zeros, returns number of written bytes
+    pub fn finish<T: ConstWriterAdapter, const N: usize>(self, writer: ConstWriter<T, {N}>) -> (ConstWriter<T, {N - 1}>, usize) {
+        match self.queue.partial() {
+            Some(byte) => (unsafe { ConstWriter { writer_adapter: writer.writer_adapter.write(&[byte]) } }, 1),
+            None => (ConstWriter { writer_adapter: writer.writer_adapter }, 0),
+        }
+    }
+}
+
+impl<T: ConstWriterAdapter, const N: usize> ConstWriter<T, {N}> {
+    /// Writes `value` XOR compressed against previous value of `encoder`, reserving
+    /// [`MAX_XOR_VALUE_LEN`] bytes of budget. Returns number of written bytes, bits which
+    /// don't make full byte stay in `encoder`.
+    pub fn write_f64_xor_compressed(self, encoder: &mut XorEncoder, value: f64) -> (ConstWriter<T, {N - MAX_XOR_VALUE_LEN}>, usize) {
+        let (buff, bits) = encoder.stage(value);
+        // unused part of reserved bytes is left to following writes
+        let len = bits / 8;
+        let mut writer_adapter = self.writer_adapter;
+        for byte in &buff[..len] {
+            writer_adapter = unsafe { writer_adapter.write(&[*byte]) };
+        }
+        (ConstWriter { writer_adapter }, len)
+    }
+}
+
+/// Reads `count` values written by [`XorEncoder`]
+#[derive(Debug, Clone)]
+pub struct XorDecoder<'a> {
+    bytes: &'a [u8],
+    /// position in bits
+    position: usize,
+    prev: u64,
+    window: (u32, u32),
+    remaining: usize,
+    first: bool,
+}
+
+impl<'a> XorDecoder<'a> {
+    pub fn new(bytes: &'a [u8], count: usize) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            prev: 0,
+            window: (0, 0),
+            remaining: count,
+            first: true,
+        }
+    }
+
+    fn read(&mut self, bits: u32) -> Option<u64> {
+        let mut value = 0u64;
+        for i in 0..bits {
+            let byte = self.bytes.get(self.position / 8)?;
+            value |= ((byte >> (self.position % 8) & 1) as u64) << i;
+            self.position += 1;
+        }
+        Some(value)
+    }
+}
+
+impl<'a> Iterator for XorDecoder<'a> {
+    type Item = f64;
+
+    /// `None` after `count` values or if stream ends early
+    fn next(&mut self) -> Option<f64> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let value = if self.first {
+            self.first = false;
+            self.read(64)?
+        } else if self.read(1)? == 0 {
+            self.prev
+        } else {
+            if self.read(1)? == 1 {
+                let leading = self.read(5)? as u32;
+                let meaningful = match self.read(6)? as u32 {
+                    0 => 64,
+                    meaningful => meaningful,
+                };
+                self.window = (leading, 64 - leading - meaningful);
+            }
+            let (leading, trailing) = self.window;
+            self.prev ^ self.read(64 - leading - trailing)? << trailing
+        };
+        self.prev = value;
+        self.remaining -= 1;
+        Some(f64::from_bits(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+    use super::{DeltaDecoder, DeltaEncoder, XorDecoder, XorEncoder};
+
+    #[test]
+    fn delta_point_worst_case() {
+        let mut buff = [0u8; 36];
+        let mut encoder = DeltaEncoder::new();
+        let (writer, len) = encoder.write_point(buff.const_writer::<36>(), u64::MAX, 1.0);
+        assert_eq!(len, 9);
+        // delta of -2^63 is worst case
+        let (_, len) = encoder.write_point(writer, u64::MAX / 2, -1.0);
+        assert_eq!(len, 18);
+
+        let mut decoder = DeltaDecoder::new();
+        assert_eq!(decoder.read_point(&buff), Some((u64::MAX, 1.0, 9)));
+        assert_eq!(decoder.read_point(&buff[9..]), Some((u64::MAX / 2, -1.0, 18)));
+    }
+
+    #[test]
+    fn xor_compressed_worst_case() {
+        let values = [0.0, f64::from_bits(u64::MAX), f64::from_bits(1), -0.0];
+        let mut buff = [0u8; 41];
+        let mut encoder = XorEncoder::new();
+        let (writer, len) = buff.const_writer::<41>().write_f64_xor_compressed(&mut encoder, values[0]);
+        assert_eq!(len, 8);
+        // new window with all 64 bits meaningful
+        let (writer, len) = writer.write_f64_xor_compressed(&mut encoder, values[1]);
+        assert_eq!(len, 9);
+        let (writer, _) = writer.write_f64_xor_compressed(&mut encoder, values[2]);
+        let (writer, _) = writer.write_f64_xor_compressed(&mut encoder, values[3]);
+        encoder.finish(writer);
+
+        let decoded = XorDecoder::new(&buff, 4).map(f64::to_bits);
+        assert!(decoded.eq(values.iter().map(|v| v.to_bits())));
+    }
+}
@@ -0,0 +1,145 @@
+//! io_uring submission queue entry, for custom ring glue.
+//!
+//! Entry is written with [`const_writer_exact`] over 64 byte array, so all fields are checked
+//! to fill entry exactly at compile time and writing has no runtime checks. Fields are
+//! in native byte order, like `struct io_uring_sqe` in kernel.
+//!
+//! ```
+//! use const_writer::uring::{opcode, Sqe, SQE_LEN};
+//!
+//! let buff = [0u8; 16];
+//! let sqe = Sqe {
+//!     opcode: opcode::WRITE,
+//!     fd: 1,
+//!     addr: buff.as_ptr() as u64,
+//!     len: buff.len() as u32,
+//!     user_data: 42,
+//!     ..Sqe::default()
+//! };
+//! // usually entry of mapped submission queue
+//! let mut entry = [0u8; SQE_LEN];
+//! sqe.write_to(&mut entry);
+//! assert_eq!(entry[0], opcode::WRITE);
+//! assert_eq!(entry[32..40], 42u64.to_ne_bytes());
+//! ```
+use crate::array::const_writer_exact;
+
+/// Length of submission queue entry
+pub const SQE_LEN: usize = 64;
+
+/// Operation codes, `IORING_OP_*`
+pub mod opcode {
+    pub const NOP: u8 = 0;
+    pub const READV: u8 = 1;
+    pub const WRITEV: u8 = 2;
+    pub const FSYNC: u8 = 3;
+    pub const READ_FIXED: u8 = 4;
+    pub const WRITE_FIXED: u8 = 5;
+    pub const POLL_ADD: u8 = 6;
+    pub const POLL_REMOVE: u8 = 7;
+    pub const SENDMSG: u8 = 9;
+    pub const RECVMSG: u8 = 10;
+    pub const TIMEOUT: u8 = 11;
+    pub const ACCEPT: u8 = 13;
+    pub const ASYNC_CANCEL: u8 = 14;
+    pub const CONNECT: u8 = 16;
+    pub const OPENAT: u8 = 18;
+    pub const CLOSE: u8 = 19;
+    pub const READ: u8 = 22;
+    pub const WRITE: u8 = 23;
+    pub const SEND: u8 = 26;
+    pub const RECV: u8 = 27;
+}
+
+/// Entry flags, `IOSQE_*`
+pub mod flags {
+    pub const FIXED_FILE: u8 = 1 << 0;
+    pub const IO_DRAIN: u8 = 1 << 1;
+    pub const IO_LINK: u8 = 1 << 2;
+    pub const IO_HARDLINK: u8 = 1 << 3;
+    pub const ASYNC: u8 = 1 << 4;
+    pub const BUFFER_SELECT: u8 = 1 << 5;
+    pub const CQE_SKIP_SUCCESS: u8 = 1 << 6;
+}
+
+/// Fields of submission queue entry, unions are named after most common member
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sqe {
+    pub opcode: u8,
+    pub flags: u8,
+    pub ioprio: u16,
+    pub fd: i32,
+    /// `off` or `addr2`
+    pub off: u64,
+    /// `addr` or `splice_off_in`
+    pub addr: u64,
+    pub len: u32,
+    /// operation flags union, like `rw_flags` or `msg_flags`
+    pub op_flags: u32,
+    pub user_data: u64,
+    /// `buf_index` or `buf_group`
+    pub buf_index: u16,
+    pub personality: u16,
+    /// `splice_fd_in` or `file_index`
+    pub file_index: u32,
+    pub addr3: u64,
+}
+
+impl Sqe {
+    /// Writes whole entry, reserved tail is zeroed
+    pub fn write_to(&self, sqe: &mut [u8; SQE_LEN]) {
+        const_writer_exact(sqe)
+            .write_slice(&[self.opcode, self.flags])
+            .write_slice(&self.ioprio.to_ne_bytes())
+            .write_slice(&self.fd.to_ne_bytes())
+            .write_slice(&self.off.to_ne_bytes())
+            .write_slice(&self.addr.to_ne_bytes())
+            .write_slice(&self.len.to_ne_bytes())
+            .write_slice(&self.op_flags.to_ne_bytes())
+            .write_slice(&self.user_data.to_ne_bytes())
+            .write_slice(&self.buf_index.to_ne_bytes())
+            .write_slice(&self.personality.to_ne_bytes())
+            .write_slice(&self.file_index.to_ne_bytes())
+            .write_slice(&self.addr3.to_ne_bytes())
+            .write_slice(&[0; 8]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flags, opcode, Sqe, SQE_LEN};
+
+    #[test]
+    fn sqe_field_offsets() {
+        let sqe = Sqe {
+            opcode: opcode::READ_FIXED,
+            flags: flags::FIXED_FILE | flags::IO_LINK,
+            ioprio: 1,
+            fd: 3,
+            off: 4,
+            addr: 5,
+            len: 6,
+            op_flags: 7,
+            user_data: 8,
+            buf_index: 9,
+            personality: 10,
+            file_index: 11,
+            addr3: 12,
+        };
+        let mut entry = [0xFFu8; SQE_LEN];
+        sqe.write_to(&mut entry);
+        assert_eq!(entry[..2], [4, 5]);
+        assert_eq!(entry[2..4], 1u16.to_ne_bytes());
+        assert_eq!(entry[4..8], 3i32.to_ne_bytes());
+        assert_eq!(entry[8..16], 4u64.to_ne_bytes());
+        assert_eq!(entry[16..24], 5u64.to_ne_bytes());
+        assert_eq!(entry[24..28], 6u32.to_ne_bytes());
+        assert_eq!(entry[28..32], 7u32.to_ne_bytes());
+        assert_eq!(entry[32..40], 8u64.to_ne_bytes());
+        assert_eq!(entry[40..42], 9u16.to_ne_bytes());
+        assert_eq!(entry[42..44], 10u16.to_ne_bytes());
+        assert_eq!(entry[44..48], 11u32.to_ne_bytes());
+        assert_eq!(entry[48..56], 12u64.to_ne_bytes());
+        assert_eq!(entry[56..], [0; 8]);
+    }
+}
@@ -0,0 +1,183 @@
+//! USB standard descriptors, all fields are little endian.
+//!
+//! Configuration descriptor carries total length of itself and all interface and endpoint
+//! descriptors after it, with const budget it's computed at compile time.
+//!
+//! ```
+//! #![feature(generic_const_exprs)]
+//! #![allow(incomplete_features)]
+//! use const_writer::ConstWrite;
+//! use const_writer::usb::{self, ConfigDescriptor, EndpointDescriptor, InterfaceDescriptor};
+//!
+//! let config = ConfigDescriptor { num_interfaces: 1, configuration_value: 1, configuration: 0, attributes: 0x80, max_power: 50 };
+//! let interface = InterfaceDescriptor { interface_number: 0, alternate_setting: 0, num_endpoints: 1, class: 3, subclass: 0, protocol: 0, interface: 0 };
+//! let endpoint = EndpointDescriptor { address: 0x81, attributes: 3, max_packet_size: 8, interval: 10 };
+//! let mut vec = vec![];
+//! usb::write_config_descriptor::<16, _, _>(vec.const_writer::<25>(), &config, |w| {
+//!     let w = usb::write_interface_descriptor(w, &interface);
+//!     usb::write_endpoint_descriptor(w, &endpoint)
+//! });
+//! assert_eq!(vec[..4], [9, 2, 25, 0]);
+//! assert_eq!(vec[18..], [7, 5, 0x81, 3, 8, 0, 10]);
+//! ```
+use crate::{ConstWriter, ConstWriterAdapter};
+
+pub const DEVICE: u8 = 1;
+pub const CONFIGURATION: u8 = 2;
+pub const INTERFACE: u8 = 4;
+pub const ENDPOINT: u8 = 5;
+
+/// Compile time check of configuration total length
+struct TotalLen<const TOTAL: usize>;
+
+impl<const TOTAL: usize> TotalLen<TOTAL> {
+    const CHECK: () = assert!(TOTAL >= 9 && TOTAL <= u16::MAX as usize, "configuration total length must be in 9..=65535");
+}
+
+/// Device descriptor fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceDescriptor {
+    /// BCD, e.g. `0x0200` for USB 2.0
+    pub usb: u16,
+    pub class: u8,
+    pub subclass: u8,
+    pub protocol: u8,
+    pub max_packet_size0: u8,
+    pub vendor: u16,
+    pub product: u16,
+    /// BCD
+    pub device: u16,
+    pub manufacturer: u8,
+    pub product_string: u8,
+    pub serial_number: u8,
+    pub num_configurations: u8,
+}
+
+/// Configuration descriptor fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigDescriptor {
+    pub num_interfaces: u8,
+    pub configuration_value: u8,
+    pub configuration: u8,
+    pub attributes: u8,
+    /// in 2 mA units
+    pub max_power: u8,
+}
+
+/// Interface descriptor fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterfaceDescriptor {
+    pub interface_number: u8,
+    pub alternate_setting: u8,
+    pub num_endpoints: u8,
+    pub class: u8,
+    pub subclass: u8,
+    pub protocol: u8,
+    pub interface: u8,
+}
+
+/// Endpoint descriptor fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointDescriptor {
+    /// endpoint number, high bit set for IN endpoints
+    pub address: u8,
+    pub attributes: u8,
+    pub max_packet_size: u16,
+    pub interval: u8,
+}
+
+/// Writes 18 byte device descriptor
+pub fn write_device_descriptor<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, d: &DeviceDescriptor) -> ConstWriter<T, {N - 18}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter
+                .write(&[18, DEVICE])
+                .write(&d.usb.to_le_bytes())
+                .write(&[d.class, d.subclass, d.protocol, d.max_packet_size0])
+                .write(&d.vendor.to_le_bytes())
+                .write(&d.product.to_le_bytes())
+                .write(&d.device.to_le_bytes())
+                .write(&[d.manufacturer, d.product_string, d.serial_number, d.num_configurations]),
+        }
+    }
+}
+
+fn config_header(total: usize, d: &ConfigDescriptor) -> [u8; 9] {
+    let total = (total as u16).to_le_bytes();
+    [9, CONFIGURATION, total[0], total[1], d.num_interfaces, d.configuration_value, d.configuration, d.attributes, d.max_power]
+}
+
+/// Writes 9 byte configuration descriptor, `TOTAL` is length of whole configuration including it
+pub fn write_config_descriptor_header<const TOTAL: usize, T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, d: &ConfigDescriptor) -> ConstWriter<T, {N - 9}> {
+    let () = TotalLen::<TOTAL>::CHECK;
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&config_header(TOTAL, d)),
+        }
+    }
+}
+
+/// Writes configuration descriptor followed by `LEN` bytes of descriptors written by `f`
+pub fn write_config_descriptor<const LEN: usize, T: ConstWriterAdapter, const N: usize>(
+    writer: ConstWriter<T, {N}>,
+    d: &ConfigDescriptor,
+    f: impl FnOnce(ConstWriter<T, LEN>) -> ConstWriter<T, 0>,
+) -> ConstWriter<T, {N - (LEN + 9)}> {
+    let () = TotalLen::<{LEN + 9}>::CHECK;
+    let writer_adapter = unsafe { writer.writer_adapter.write(&config_header(LEN + 9, d)) };
+    ConstWriter {
+        writer_adapter: f(ConstWriter { writer_adapter }).writer_adapter,
+    }
+}
+
+/// Writes 9 byte interface descriptor
+pub fn write_interface_descriptor<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, d: &InterfaceDescriptor) -> ConstWriter<T, {N - 9}> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[
+                9, INTERFACE,
+                d.interface_number, d.alternate_setting, d.num_endpoints,
+                d.class, d.subclass, d.protocol, d.interface,
+            ]),
+        }
+    }
+}
+
+/// Writes 7 byte endpoint descriptor
+pub fn write_endpoint_descriptor<T: ConstWriterAdapter, const N: usize>(writer: ConstWriter<T, {N}>, d: &EndpointDescriptor) -> ConstWriter<T, {N - 7}> {
+    let max_packet_size = d.max_packet_size.to_le_bytes();
+    unsafe {
+        ConstWriter {
+            writer_adapter: writer.writer_adapter.write(&[
+                7, ENDPOINT, d.address, d.attributes, max_packet_size[0], max_packet_size[1], d.interval,
+            ]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConstWrite;
+
+    #[test]
+    fn usb_device_descriptor() {
+        let device = super::DeviceDescriptor {
+            usb: 0x0200,
+            class: 0,
+            subclass: 0,
+            protocol: 0,
+            max_packet_size0: 64,
+            vendor: 0x1209,
+            product: 0x0001,
+            device: 0x0100,
+            manufacturer: 1,
+            product_string: 2,
+            serial_number: 3,
+            num_configurations: 1,
+        };
+        let mut buff = [0u8; 18];
+        let mut ref_buff = buff.as_mut() as &mut [u8];
+        super::write_device_descriptor(ref_buff.const_writer::<18>(), &device);
+        assert_eq!(buff, [18, 1, 0, 2, 0, 0, 0, 64, 0x09, 0x12, 1, 0, 0, 1, 1, 2, 3, 1]);
+    }
+}
@@ -1,51 +1,233 @@
-use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate};
+//! Adapters for `Vec<u8>`.
+//!
+//! Module uses only `core` and `alloc`, so it is available on `no_std` targets with heap:
+//! ```toml
+//! const-writer = { version = "0.1", default-features = false, features = ["alloc"] }
+//! ```
+use crate::{ConstWriterAdapter, ConstWrite, ConstWriterAdapterCreate, ConstWriter};
 
 extern crate alloc;
+use alloc::alloc::{Allocator, Global};
 use alloc::vec::Vec;
 
-pub struct VecWriterAdapter<'a> {
-    vec: &'a mut Vec<u8>,
-    ptr: *mut u8
+/// How [`VecWriterAdapter`] reserves vector capacity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrowthPolicy {
+    /// Uses `Vec::reserve`, capacity at least doubles on reallocation.
+    /// Good when frame is built with many `grow_to` steps.
+    Amortized,
+    /// Uses `Vec::reserve_exact`, no extra capacity is allocated.
+    Exact,
 }
 
-unsafe impl<'a> ConstWriterAdapterCreate<'a, Vec<u8>> for VecWriterAdapter<'a> {
-    unsafe fn new<const N: usize>(buff: &'a mut Vec<u8>) -> Self {
-        buff.reserve(N);
-        let ptr = core::mem::transmute(buff.as_mut_ptr().add(buff.len()));
+impl GrowthPolicy {
+    fn reserve<A: Allocator>(self, vec: &mut Vec<u8, A>, additional: usize) {
+        match self {
+            GrowthPolicy::Amortized => vec.reserve(additional),
+            GrowthPolicy::Exact => vec.reserve_exact(additional),
+        }
+    }
+}
+
+/// [`ConstWriter`] over vector, to shorten signatures
+pub type VecWriter<'a, const N: usize, A = Global> = ConstWriter<VecWriterAdapter<'a, A>, N>;
+
+/// Adapter for `Vec<u8, A>`, reserves space on creation and advances vector length on drop.
+///
+/// Adapter holds vector exclusively, so vector can't be touched while writer is alive.
+/// Buffer pointer is taken once and refreshed only in `grow`, which can reallocate.
+pub struct VecWriterAdapter<'a, A: Allocator = Global> {
+    vec: &'a mut Vec<u8, A>,
+    /// start of vector buffer, from `Vec::as_mut_ptr`
+    ptr: *mut u8,
+    /// offset of next byte to write from start of vector buffer
+    pos: usize,
+    policy: GrowthPolicy,
+}
+
+impl<'a, A: Allocator> VecWriterAdapter<'a, A> {
+    #[track_caller]
+    unsafe fn with_policy<const N: usize>(buff: &'a mut Vec<u8, A>, policy: GrowthPolicy) -> Self {
+        policy.reserve(buff, N);
         Self {
+            ptr: buff.as_mut_ptr(),
+            pos: buff.len(),
             vec: buff,
-            ptr
+            policy
         }
     }
 }
 
-impl<'a> ConstWriterAdapter for VecWriterAdapter<'a> {
-    unsafe fn write<const N: usize>(mut self, value: &[u8; N]) -> Self {
-        core::ptr::copy_nonoverlapping(value.as_ptr(), self.ptr, N);
-        self.ptr = self.ptr.add(N);
+// raw pointer is into vector which adapter borrows exclusively
+unsafe impl<'a, A: Allocator> Send for VecWriterAdapter<'a, A> where Vec<u8, A>: Send {}
+unsafe impl<'a, A: Allocator> Sync for VecWriterAdapter<'a, A> where Vec<u8, A>: Sync {}
+
+unsafe impl<'a, A: Allocator> ConstWriterAdapterCreate<'a, Vec<u8, A>> for VecWriterAdapter<'a, A> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(buff: &'a mut Vec<u8, A>) -> Self {
+        Self::with_policy::<N>(buff, GrowthPolicy::Amortized)
+    }
+}
+
+impl<'a, A: Allocator> crate::sealed::Sealed for VecWriterAdapter<'a, A> {}
+
+impl<'a, A: Allocator> core::fmt::Debug for VecWriterAdapter<'a, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a, A: Allocator> ConstWriterAdapter for VecWriterAdapter<'a, A> {
+    fn written_so_far(&self) -> usize {
+        self.pos - self.vec.len()
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        core::ptr::copy_nonoverlapping(value.as_ptr(), self.ptr.add(self.pos), N);
+        self.pos += N;
         self
     }
 
+    #[track_caller]
     unsafe fn grow<const M: usize>(mut self) -> Self {
-        let written_bytes = self.ptr.offset_from(self.vec.as_ptr()) as usize;
-        self.vec.reserve(written_bytes + M);
-        // vec.reserve() can move inner buffer so we update our pointer
-        self.ptr = core::mem::transmute::<_, *mut u8>(self.vec.as_mut_ptr()).add(written_bytes);
+        // vec len is updated only on drop, so bytes written by adapter are reserved too
+        self.policy.reserve(self.vec, self.pos - self.vec.len() + M);
+        // reserve can reallocate
+        self.ptr = self.vec.as_mut_ptr();
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(self) -> Self {
+        core::ptr::write_bytes(self.ptr.add(self.pos), crate::POISON, N);
         self
     }
 }
 
-impl<'a> Drop for VecWriterAdapter<'a> {
+impl<'a, A: Allocator> Drop for VecWriterAdapter<'a, A> {
     /// When dropping adapter we advancing vector
     fn drop(&mut self) {
         unsafe {
-            let new_len = self.ptr.offset_from(self.vec.as_ptr()) as usize;
-            self.vec.set_len(new_len);
+            self.vec.set_len(self.pos);
         }
     }
 }
 
-impl<'a> ConstWrite<'a, VecWriterAdapter<'a>> for Vec<u8> {}
+impl<'a, A: Allocator> crate::GrowableAdapter for VecWriterAdapter<'a, A> {}
+
+impl<'a, A: Allocator> ConstWrite<'a, VecWriterAdapter<'a, A>> for Vec<u8, A> {}
+
+impl<'a, A: Allocator> crate::min::MinConstWrite<'a, VecWriterAdapter<'a, A>> for Vec<u8, A> {}
+
+/// Get [`ConstWriter`] to write `N` bytes to vector, which reserves capacity with given policy
+/// ```
+/// use const_writer::vec::{const_writer_with_policy, GrowthPolicy};
+///
+/// let mut vec = vec![];
+/// const_writer_with_policy::<4, _>(&mut vec, GrowthPolicy::Exact)
+///     .write_u16_le(1)
+///     .grow_to::<10>()
+///     .write_u64_le(2);
+/// assert_eq!(vec.len(), 10);
+/// ```
+#[track_caller]
+pub fn const_writer_with_policy<const N: usize, A: Allocator>(vec: &mut Vec<u8, A>, policy: GrowthPolicy) -> ConstWriter<VecWriterAdapter<'_, A>, N> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: crate::poisoned::<_, N>(VecWriterAdapter::with_policy::<N>(vec, policy))
+        }
+    }
+}
+
+/// Adapter which stages written bytes in inline `[u8; CAP]` buffer
+/// and appends them to vector at once on drop.
+///
+/// Writes don't go through vector pointer, so for small frames it is as fast as writing to array.
+/// ```
+/// use const_writer::vec::staged_const_writer;
+///
+/// let mut vec = vec![1];
+/// staged_const_writer::<6>(&mut vec)
+///     .write_u32_le(2)
+///     .write_u16_le(3);
+/// assert_eq!(vec, [1, 2, 0, 0, 0, 3, 0]);
+/// ```
+pub struct StagedVecAdapter<'a, const CAP: usize, A: Allocator = Global> {
+    vec: &'a mut Vec<u8, A>,
+    buff: core::mem::MaybeUninit<[u8; CAP]>,
+    len: usize,
+}
+
+unsafe impl<'a, const CAP: usize, A: Allocator> ConstWriterAdapterCreate<'a, Vec<u8, A>> for StagedVecAdapter<'a, CAP, A> {
+    #[track_caller]
+    unsafe fn new<const N: usize>(buff: &'a mut Vec<u8, A>) -> Self {
+        assert!(N <= CAP, "staging buffer too short: {} < {} ({})", CAP, N, core::any::type_name::<Self>());
+        Self {
+            vec: buff,
+            buff: core::mem::MaybeUninit::uninit(),
+            len: 0,
+        }
+    }
+}
+
+impl<'a, const CAP: usize, A: Allocator> crate::sealed::Sealed for StagedVecAdapter<'a, CAP, A> {}
+
+impl<'a, const CAP: usize, A: Allocator> core::fmt::Debug for StagedVecAdapter<'a, CAP, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::debug_adapter(self, f)
+    }
+}
+
+impl<'a, const CAP: usize, A: Allocator> ConstWriterAdapter for StagedVecAdapter<'a, CAP, A> {
+    fn written_so_far(&self) -> usize {
+        self.len
+    }
+
+    unsafe fn write_raw<const N: usize>(mut self, value: &[u8; N]) -> Self {
+        let ptr = self.buff.as_mut_ptr().cast::<u8>().add(self.len);
+        core::ptr::copy_nonoverlapping(value.as_ptr(), ptr, N);
+        self.len += N;
+        self
+    }
+
+    #[track_caller]
+    unsafe fn grow<const M: usize>(self) -> Self {
+        assert!(
+            M <= CAP - self.len,
+            "remaining staging buffer too short to grow: {} < {} ({})",
+            CAP - self.len,
+            M,
+            core::any::type_name::<Self>()
+        );
+        self
+    }
+
+    #[cfg(all(feature = "debug-poison", debug_assertions))]
+    unsafe fn poison<const N: usize>(mut self) -> Self {
+        core::ptr::write_bytes(self.buff.as_mut_ptr().cast::<u8>().add(self.len), crate::POISON, N);
+        self
+    }
+}
+
+impl<'a, const CAP: usize, A: Allocator> Drop for StagedVecAdapter<'a, CAP, A> {
+    /// When dropping adapter we append staged bytes to vector
+    fn drop(&mut self) {
+        unsafe {
+            let staged = core::slice::from_raw_parts(self.buff.as_ptr().cast::<u8>(), self.len);
+            self.vec.extend_from_slice(staged);
+        }
+    }
+}
+
+/// Get [`ConstWriter`] to write `N` bytes to vector through [`StagedVecAdapter`]
+#[track_caller]
+pub fn staged_const_writer<const N: usize>(vec: &mut Vec<u8>) -> ConstWriter<StagedVecAdapter<'_, N>, N> {
+    unsafe {
+        ConstWriter {
+            writer_adapter: crate::poisoned::<_, N>(StagedVecAdapter::new::<N>(vec))
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -97,6 +279,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn vec_write_grow() {
         let mut vec = vec![];
 
@@ -110,6 +293,111 @@ mod tests {
         assert_eq!(&vec, &[123, 0, 0, 0, 1, 124, 0, 0, 0, 24]);
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn vec_grow_exact() {
+        let mut vec = vec![0u8; 3];
+        vec.shrink_to_fit();
+
+        super::const_writer_with_policy::<2, _>(&mut vec, super::GrowthPolicy::Exact)
+            .write_u16_le(1)
+            .convert::<4>()
+            .write_u32_le(2);
+
+        assert_eq!(vec.capacity(), 9);
+        assert_eq!(&vec, &[0, 0, 0, 1, 0, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn vec_write_grow_to_and_shrink() {
+        let mut vec = vec![];
+
+        vec.const_writer::<5>()
+            .write_u32_le(123)
+            .write_u8_le(1)
+            .grow_to::<10>()
+            .write_u32_le(124)
+            .shrink::<2>()
+            .write_u8_le(24);
+
+        assert_eq!(&vec, &[123, 0, 0, 0, 1, 124, 0, 0, 0, 24]);
+    }
+
+    #[test]
+    fn vec_grow_to_exact() {
+        let mut vec = vec![0u8; 3];
+        vec.shrink_to_fit();
+
+        super::const_writer_with_policy::<2, _>(&mut vec, super::GrowthPolicy::Exact)
+            .write_u16_le(1)
+            .grow_to::<4>()
+            .write_u32_le(2);
+
+        assert_eq!(vec.capacity(), 9);
+        assert_eq!(&vec, &[0, 0, 0, 1, 0, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn vec_writer_debug() {
+        let mut vec = vec![1, 2, 3];
+        let writer = vec.const_writer::<6>().write_u16_le(4);
+        assert_eq!(writer.written_so_far(), 2);
+        let debug = alloc::format!("{:?}", writer);
+        assert!(debug.starts_with("ConstWriter { remaining: 4, written_so_far: 2, adapter: \"const_writer::vec::VecWriterAdapter"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn vec_writer_send() {
+        let mut vec = vec![];
+        let writer = vec.const_writer::<4>();
+        std::thread::scope(|scope| {
+            scope.spawn(move || writer.write_u32_le(1));
+        });
+        assert_eq!(vec, [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn vec_custom_allocator() {
+        use alloc::alloc::{AllocError, Allocator, Global, Layout};
+        use core::cell::Cell;
+        use core::ptr::NonNull;
+
+        struct Counting(Cell<usize>);
+
+        unsafe impl Allocator for &Counting {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + 1);
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let allocator = Counting(Cell::new(0));
+        let mut vec = Vec::new_in(&allocator);
+        vec.const_writer::<4>()
+            .write_u32_be(0x01020304);
+
+        assert_eq!(&vec, &[1, 2, 3, 4]);
+        assert_eq!(allocator.0.get(), 1);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn staged_vec_grow() {
+        let mut vec = vec![];
+
+        super::staged_const_writer::<10>(&mut vec)
+            .write_u32_le(123)
+            .convert::<6>()
+            .write_u16_be(5);
+
+        assert_eq!(&vec, &[123, 0, 0, 0, 0, 5]);
+    }
+
     #[bench]
     fn bench_bytes_vec(b: &mut Bencher) {
         use bytes::BufMut;
@@ -137,4 +425,18 @@ mod tests {
             assert_eq!(vec.len(), 31);
         });
     }
+
+    #[bench]
+    fn bench_const_writer_staged_vec(b: &mut Bencher) {
+        b.iter(|| {
+            let mut vec = Vec::with_capacity(31);
+            super::staged_const_writer::<31>(&mut vec)
+                .write_u8_be(0x01)
+                .write_u16_be(0x0203)
+                .write_u32_be(0x04050607)
+                .write_u64_be(0x08090A0B0C0D0E0F)
+                .write_u128_be(0x101112131415161718191A1B1C1D1E1F);
+            assert_eq!(vec.len(), 31);
+        });
+    }
 }
\ No newline at end of file
@@ -0,0 +1,78 @@
+//! Checks that writes compile to plain stores without branches or calls. Slice and Vec writers
+//! check space once when created, then writes are plain stores too.
+//!
+//! Builds `examples/codegen.rs` to assembly, so it is slow and ignored by default:
+//! `cargo test --test codegen -- --ignored`
+#![cfg(nightly)]
+use std::path::Path;
+use std::process::Command;
+
+/// Function name, expected number of stores and number of branches and calls
+const EXPECTED: &[(&str, usize, usize)] = &[
+    ("codegen_write_u8_le", 1, 0),
+    ("codegen_write_u16_le", 1, 0),
+    ("codegen_write_u32_le", 1, 0),
+    ("codegen_write_u64_le", 1, 0),
+    ("codegen_write_u128_le", 2, 0),
+    ("codegen_write_u16_be", 1, 0),
+    ("codegen_write_u32_be", 1, 0),
+    ("codegen_write_u64_be", 1, 0),
+    ("codegen_write_u128_be", 2, 0),
+    ("codegen_write_f32_le", 1, 0),
+    ("codegen_write_f64_le", 1, 0),
+    ("codegen_write_chain", 2, 0),
+    ("codegen_write_shrink", 3, 0),
+    // call of length check, 3 stores and advance of slice start and length
+    ("codegen_write_slice", 5, 1),
+    // capacity check with reserve call on cold path, 3 stores and length
+    ("codegen_write_vec", 4, 3),
+];
+
+fn function_body<'a>(asm: &'a str, name: &str) -> Vec<&'a str> {
+    asm.lines()
+        .skip_while(|line| *line != format!("{}:", name))
+        .skip(1)
+        .take_while(|line| !line.contains(".cfi_endproc"))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('.'))
+        .collect()
+}
+
+#[test]
+#[ignore]
+#[cfg(target_arch = "x86_64")]
+fn codegen_single_store() {
+    let target = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    let status = Command::new(env!("CARGO"))
+        .args(["rustc", "--release", "--example", "codegen", "--"])
+        .args(["--emit", "asm", "-C", "llvm-args=-x86-asm-syntax=intel"])
+        .env("CARGO_TARGET_DIR", &target)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // builds with other flags leave their own assembly, latest one is ours
+    let asm_file = std::fs::read_dir(target.join("release/examples"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "s"))
+        .max_by_key(|path| path.metadata().unwrap().modified().unwrap())
+        .expect("assembly file not found");
+    let asm = std::fs::read_to_string(asm_file).unwrap();
+
+    for &(name, stores, branches) in EXPECTED {
+        let body = function_body(&asm, name);
+        assert!(!body.is_empty(), "{} not found", name);
+        let actual = body.iter()
+            .filter(|instruction| instruction.starts_with("call") || instruction.starts_with('j'))
+            .count();
+        assert_eq!(actual, branches, "{} branches: {:?}", name, body);
+        // intel syntax, so destination is first operand, indirect call has no destination
+        let actual = body.iter()
+            .filter(|instruction| !instruction.starts_with("call"))
+            .filter(|instruction| instruction.split(',').next().unwrap().contains("ptr ["))
+            .count();
+        assert_eq!(actual, stores, "{} stores: {:?}", name, body);
+    }
+}